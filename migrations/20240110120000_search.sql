@@ -0,0 +1,118 @@
+-- Full-text search documents for tasks, projects, teams and members.
+--
+-- The vectors live in their own table instead of tsvector columns on the resource tables:
+-- sqlx can't decode tsvector, and the resource tables are read with `SELECT *` and
+-- `RETURNING *` throughout. The triggers below keep the documents and their GIN index in sync.
+
+CREATE TABLE public.search_documents (
+    resource_type text NOT NULL,
+    resource_id uuid NOT NULL,
+    updated_at timestamp with time zone DEFAULT now() NOT NULL,
+
+    title text NOT NULL,
+    body text,
+
+    document tsvector NOT NULL
+);
+
+ALTER TABLE ONLY public.search_documents
+    ADD CONSTRAINT search_documents_pkey PRIMARY KEY (resource_type, resource_id);
+
+CREATE INDEX search_documents_document_idx ON public.search_documents USING gin (document);
+
+
+CREATE FUNCTION public.upsert_search_document(_resource_type text, _resource_id uuid, _title text, _body text) RETURNS void
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+  INSERT INTO public.search_documents (resource_type, resource_id, title, body, document)
+  VALUES (
+    _resource_type,
+    _resource_id,
+    COALESCE(_title, ''),
+    _body,
+    setweight(to_tsvector('simple', COALESCE(_title, '')), 'A') ||
+    setweight(to_tsvector('simple', COALESCE(_body, '')), 'B')
+  )
+  ON CONFLICT (resource_type, resource_id) DO UPDATE
+  SET
+    updated_at = NOW(),
+    title = EXCLUDED.title,
+    body = EXCLUDED.body,
+    document = EXCLUDED.document;
+END;
+$$;
+
+
+CREATE FUNCTION public.index_task_search_document() RETURNS trigger
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+  IF TG_OP = 'DELETE' THEN
+    DELETE FROM public.search_documents WHERE resource_type = 'Task' AND resource_id = OLD.id;
+    RETURN OLD;
+  END IF;
+
+  PERFORM public.upsert_search_document('Task', NEW.id, NEW.title, NEW.description);
+  RETURN NEW;
+END;
+$$;
+
+CREATE FUNCTION public.index_project_search_document() RETURNS trigger
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+  IF TG_OP = 'DELETE' THEN
+    DELETE FROM public.search_documents WHERE resource_type = 'Project' AND resource_id = OLD.id;
+    RETURN OLD;
+  END IF;
+
+  PERFORM public.upsert_search_document('Project', NEW.id, NEW.name, NEW.description);
+  RETURN NEW;
+END;
+$$;
+
+CREATE FUNCTION public.index_team_search_document() RETURNS trigger
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+  IF TG_OP = 'DELETE' THEN
+    DELETE FROM public.search_documents WHERE resource_type = 'Team' AND resource_id = OLD.id;
+    RETURN OLD;
+  END IF;
+
+  PERFORM public.upsert_search_document('Team', NEW.id, NEW.name, NULL);
+  RETURN NEW;
+END;
+$$;
+
+CREATE FUNCTION public.index_member_search_document() RETURNS trigger
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+  IF TG_OP = 'DELETE' THEN
+    DELETE FROM public.search_documents WHERE resource_type = 'Member' AND resource_id = OLD.id;
+    RETURN OLD;
+  END IF;
+
+  PERFORM public.upsert_search_document('Member', NEW.id, NEW.name, NULL);
+  RETURN NEW;
+END;
+$$;
+
+
+CREATE TRIGGER index_tasks_search_document AFTER INSERT OR DELETE OR UPDATE OF title, description ON public.tasks FOR EACH ROW EXECUTE FUNCTION public.index_task_search_document();
+
+CREATE TRIGGER index_projects_search_document AFTER INSERT OR DELETE OR UPDATE OF name, description ON public.projects FOR EACH ROW EXECUTE FUNCTION public.index_project_search_document();
+
+CREATE TRIGGER index_teams_search_document AFTER INSERT OR DELETE OR UPDATE OF name ON public.teams FOR EACH ROW EXECUTE FUNCTION public.index_team_search_document();
+
+CREATE TRIGGER index_members_search_document AFTER INSERT OR DELETE OR UPDATE OF name ON public.members FOR EACH ROW EXECUTE FUNCTION public.index_member_search_document();
+
+
+-- Backfill existing rows.
+
+SELECT public.upsert_search_document('Task', id, title, description) FROM public.tasks;
+SELECT public.upsert_search_document('Project', id, name, description) FROM public.projects;
+SELECT public.upsert_search_document('Team', id, name, NULL) FROM public.teams;
+SELECT public.upsert_search_document('Member', id, name, NULL) FROM public.members;
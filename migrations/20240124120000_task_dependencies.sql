@@ -0,0 +1,21 @@
+-- Blocking relations between tasks: `blocker_id` must be finished before `blocked_id` can start.
+
+CREATE TABLE public.task_dependencies (
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+
+    blocker_id uuid NOT NULL,
+    blocked_id uuid NOT NULL,
+
+    CONSTRAINT task_dependencies_not_self CHECK (blocker_id <> blocked_id)
+);
+
+ALTER TABLE ONLY public.task_dependencies
+    ADD CONSTRAINT task_dependencies_pkey PRIMARY KEY (blocker_id, blocked_id);
+
+ALTER TABLE ONLY public.task_dependencies
+    ADD CONSTRAINT task_dependencies_blocker_id_fkey FOREIGN KEY (blocker_id) REFERENCES public.tasks(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+ALTER TABLE ONLY public.task_dependencies
+    ADD CONSTRAINT task_dependencies_blocked_id_fkey FOREIGN KEY (blocked_id) REFERENCES public.tasks(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+CREATE INDEX task_dependencies_blocked_id_idx ON public.task_dependencies USING btree (blocked_id);
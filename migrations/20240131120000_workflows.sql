@@ -0,0 +1,142 @@
+-- Per-project workflow states. Projects without their own states use the default workflow
+-- (the states with a NULL project_id). `tasks.status` is kept in sync with the state category
+-- so existing TaskStatus consumers keep working.
+
+CREATE TABLE public.workflow_states (
+    id uuid DEFAULT gen_random_uuid() NOT NULL,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    updated_at timestamp with time zone DEFAULT now() NOT NULL,
+
+    project_id uuid,
+
+    name text NOT NULL,
+    category text NOT NULL,
+    "position" integer DEFAULT 0 NOT NULL,
+
+    CONSTRAINT workflow_states_category_check CHECK (category IN ('Unstarted', 'Started', 'Completed', 'Canceled'))
+);
+
+ALTER TABLE ONLY public.workflow_states
+    ADD CONSTRAINT workflow_states_pkey PRIMARY KEY (id);
+
+ALTER TABLE ONLY public.workflow_states
+    ADD CONSTRAINT workflow_states_project_id_fkey FOREIGN KEY (project_id) REFERENCES public.projects(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+CREATE UNIQUE INDEX workflow_states_project_id_name_key ON public.workflow_states USING btree (COALESCE(project_id, '00000000-0000-0000-0000-000000000000'::uuid), name);
+
+CREATE TRIGGER set_public_workflow_states_updated_at BEFORE UPDATE ON public.workflow_states FOR EACH ROW EXECUTE FUNCTION public.set_current_timestamp_updated_at();
+
+
+CREATE TABLE public.workflow_transitions (
+    from_state_id uuid NOT NULL,
+    to_state_id uuid NOT NULL,
+
+    CONSTRAINT workflow_transitions_not_self CHECK (from_state_id <> to_state_id)
+);
+
+ALTER TABLE ONLY public.workflow_transitions
+    ADD CONSTRAINT workflow_transitions_pkey PRIMARY KEY (from_state_id, to_state_id);
+
+ALTER TABLE ONLY public.workflow_transitions
+    ADD CONSTRAINT workflow_transitions_from_state_id_fkey FOREIGN KEY (from_state_id) REFERENCES public.workflow_states(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+ALTER TABLE ONLY public.workflow_transitions
+    ADD CONSTRAINT workflow_transitions_to_state_id_fkey FOREIGN KEY (to_state_id) REFERENCES public.workflow_states(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+
+-- Default workflow, mirroring the former fixed TaskStatus values. Every transition is allowed.
+
+INSERT INTO public.workflow_states (project_id, name, category, "position") VALUES
+    (NULL, 'None', 'Unstarted', 0),
+    (NULL, 'Backlog', 'Unstarted', 1),
+    (NULL, 'ToDo', 'Unstarted', 2),
+    (NULL, 'InProgress', 'Started', 3),
+    (NULL, 'Done', 'Completed', 4),
+    (NULL, 'Canceled', 'Canceled', 5);
+
+INSERT INTO public.workflow_transitions (from_state_id, to_state_id)
+SELECT a.id, b.id
+FROM public.workflow_states a, public.workflow_states b
+WHERE a.project_id IS NULL AND b.project_id IS NULL AND a.id <> b.id;
+
+
+CREATE FUNCTION public.task_status_category(_status text) RETURNS text
+    LANGUAGE sql IMMUTABLE
+    AS $$
+  SELECT CASE COALESCE(_status, 'None')
+    WHEN 'InProgress' THEN 'Started'
+    WHEN 'Done' THEN 'Completed'
+    WHEN 'Canceled' THEN 'Canceled'
+    ELSE 'Unstarted'
+  END
+$$;
+
+-- The project whose states make up the workflow of `_project_id`, or NULL for the default workflow.
+CREATE FUNCTION public.workflow_project_id(_project_id uuid) RETURNS uuid
+    LANGUAGE sql STABLE
+    AS $$
+  SELECT CASE
+    WHEN EXISTS (SELECT 1 FROM public.workflow_states WHERE project_id = _project_id) THEN _project_id
+    ELSE NULL
+  END
+$$;
+
+-- Picks the state matching a legacy status: same name first, otherwise the first state of its category.
+CREATE FUNCTION public.resolve_workflow_state(_project_id uuid, _status text) RETURNS uuid
+    LANGUAGE sql STABLE
+    AS $$
+  SELECT id
+  FROM public.workflow_states
+  WHERE
+    project_id IS NOT DISTINCT FROM public.workflow_project_id(_project_id)
+    AND category = public.task_status_category(_status)
+  ORDER BY (name = COALESCE(_status, 'None')) DESC, "position"
+  LIMIT 1
+$$;
+
+-- The legacy status reported for a state.
+CREATE FUNCTION public.workflow_state_task_status(_state_id uuid) RETURNS text
+    LANGUAGE sql STABLE
+    AS $$
+  SELECT CASE
+    WHEN name IN ('None', 'Backlog', 'ToDo', 'InProgress', 'Done', 'Canceled')
+      AND public.task_status_category(name) = category THEN name
+    WHEN category = 'Started' THEN 'InProgress'
+    WHEN category = 'Completed' THEN 'Done'
+    WHEN category = 'Canceled' THEN 'Canceled'
+    ELSE 'ToDo'
+  END
+  FROM public.workflow_states
+  WHERE id = _state_id
+$$;
+
+
+ALTER TABLE public.tasks ADD COLUMN state_id uuid;
+
+ALTER TABLE ONLY public.tasks
+    ADD CONSTRAINT tasks_state_id_fkey FOREIGN KEY (state_id) REFERENCES public.workflow_states(id) ON UPDATE CASCADE ON DELETE SET NULL;
+
+CREATE INDEX tasks_state_id_idx ON public.tasks USING btree (state_id);
+
+UPDATE public.tasks SET state_id = public.resolve_workflow_state(project_id, status);
+
+
+CREATE FUNCTION public.sync_task_workflow_state() RETURNS trigger
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+  IF TG_OP = 'INSERT' OR NEW.state_id IS DISTINCT FROM OLD.state_id THEN
+    IF NEW.state_id IS NOT NULL THEN
+      NEW.status := public.workflow_state_task_status(NEW.state_id);
+    ELSE
+      NEW.state_id := public.resolve_workflow_state(NEW.project_id, NEW.status);
+    END IF;
+  ELSIF NEW.status IS DISTINCT FROM OLD.status OR NEW.project_id IS DISTINCT FROM OLD.project_id THEN
+    NEW.state_id := public.resolve_workflow_state(NEW.project_id, NEW.status);
+  END IF;
+
+  RETURN NEW;
+END;
+$$;
+
+CREATE TRIGGER sync_public_tasks_workflow_state BEFORE INSERT OR UPDATE ON public.tasks FOR EACH ROW EXECUTE FUNCTION public.sync_task_workflow_state();
@@ -0,0 +1,132 @@
+-- Human-readable task keys such as API-42. The prefix comes from the task's project, falling back
+-- to the first prefixed team working on it, and then to TASK. Every key a task ever had is kept in
+-- `task_keys`, so keys from before a move keep resolving as aliases.
+
+CREATE TABLE public.task_key_counters (
+    scope_type text NOT NULL,
+    scope_id uuid NOT NULL,
+
+    last_number integer DEFAULT 0 NOT NULL
+);
+
+ALTER TABLE ONLY public.task_key_counters
+    ADD CONSTRAINT task_key_counters_pkey PRIMARY KEY (scope_type, scope_id);
+
+
+CREATE TABLE public.task_keys (
+    key text NOT NULL,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+
+    task_id uuid NOT NULL
+);
+
+ALTER TABLE ONLY public.task_keys
+    ADD CONSTRAINT task_keys_pkey PRIMARY KEY (key);
+
+-- Deferred because keys are allocated from a BEFORE INSERT trigger on tasks.
+ALTER TABLE ONLY public.task_keys
+    ADD CONSTRAINT task_keys_task_id_fkey FOREIGN KEY (task_id) REFERENCES public.tasks(id) ON UPDATE CASCADE ON DELETE CASCADE DEFERRABLE INITIALLY DEFERRED;
+
+CREATE INDEX task_keys_task_id_idx ON public.task_keys USING btree (task_id);
+
+
+CREATE FUNCTION public.task_key_scope(_project_id uuid, OUT scope_type text, OUT scope_id uuid, OUT prefix text)
+    LANGUAGE plpgsql STABLE
+    AS $$
+BEGIN
+  SELECT 'Project', projects.id, upper(btrim(projects.prefix))
+  INTO scope_type, scope_id, prefix
+  FROM public.projects
+  WHERE projects.id = _project_id AND NULLIF(btrim(projects.prefix), '') IS NOT NULL;
+
+  IF FOUND THEN
+    RETURN;
+  END IF;
+
+  SELECT 'Team', teams.id, upper(btrim(teams.prefix))
+  INTO scope_type, scope_id, prefix
+  FROM public.teams
+  JOIN public.teams_by_projects ON teams_by_projects.team_id = teams.id
+  WHERE teams_by_projects.project_id = _project_id AND NULLIF(btrim(teams.prefix), '') IS NOT NULL
+  ORDER BY teams.created_at, teams.id
+  LIMIT 1;
+
+  IF FOUND THEN
+    RETURN;
+  END IF;
+
+  scope_type := 'Workspace';
+  scope_id := '00000000-0000-0000-0000-000000000000';
+  prefix := 'TASK';
+END;
+$$;
+
+-- Takes the next number of the scope. Numbers whose key is already taken (two scopes sharing a
+-- prefix) are skipped.
+CREATE FUNCTION public.allocate_task_key(_task_id uuid, _project_id uuid) RETURNS text
+    LANGUAGE plpgsql
+    AS $$
+DECLARE
+  _scope record;
+  _number integer;
+  _key text;
+BEGIN
+  SELECT * INTO _scope FROM public.task_key_scope(_project_id);
+
+  LOOP
+    INSERT INTO public.task_key_counters (scope_type, scope_id, last_number)
+    VALUES (_scope.scope_type, _scope.scope_id, 1)
+    ON CONFLICT (scope_type, scope_id) DO UPDATE
+    SET last_number = public.task_key_counters.last_number + 1
+    RETURNING last_number INTO _number;
+
+    _key := _scope.prefix || '-' || _number;
+
+    INSERT INTO public.task_keys (key, task_id) VALUES (_key, _task_id)
+    ON CONFLICT (key) DO NOTHING;
+
+    EXIT WHEN FOUND;
+  END LOOP;
+
+  RETURN _key;
+END;
+$$;
+
+
+ALTER TABLE public.tasks ADD COLUMN key text;
+
+DO $$
+DECLARE
+  _task record;
+BEGIN
+  FOR _task IN SELECT id, project_id FROM public.tasks ORDER BY count, created_at LOOP
+    UPDATE public.tasks SET key = public.allocate_task_key(_task.id, _task.project_id) WHERE id = _task.id;
+  END LOOP;
+END;
+$$;
+
+ALTER TABLE public.tasks ALTER COLUMN key SET NOT NULL;
+
+ALTER TABLE ONLY public.tasks
+    ADD CONSTRAINT tasks_key_key UNIQUE (key);
+
+
+CREATE FUNCTION public.assign_task_key() RETURNS trigger
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+  IF TG_OP = 'INSERT' THEN
+    NEW.key := public.allocate_task_key(NEW.id, NEW.project_id);
+  ELSIF NEW.project_id IS DISTINCT FROM OLD.project_id
+    AND (SELECT (scope_type, scope_id) FROM public.task_key_scope(NEW.project_id))
+      IS DISTINCT FROM (SELECT (scope_type, scope_id) FROM public.task_key_scope(OLD.project_id)) THEN
+    NEW.key := public.allocate_task_key(NEW.id, NEW.project_id);
+  ELSE
+    NEW.key := OLD.key;
+  END IF;
+
+  RETURN NEW;
+END;
+$$;
+
+CREATE TRIGGER assign_public_tasks_key BEFORE INSERT OR UPDATE OF project_id, key ON public.tasks FOR EACH ROW EXECUTE FUNCTION public.assign_task_key();
@@ -0,0 +1,26 @@
+-- Recurrence rules of tasks. The row always points at the latest occurrence of the series; the
+-- scheduler copies that task into the next occurrence and moves the row onto the copy.
+
+CREATE TABLE public.task_recurrences (
+    task_id uuid NOT NULL,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    updated_at timestamp with time zone DEFAULT now() NOT NULL,
+
+    rule text NOT NULL,
+    starts_at timestamp with time zone NOT NULL,
+
+    current_occurrence_at timestamp with time zone NOT NULL,
+    next_occurrence_at timestamp with time zone,
+
+    occurrences integer DEFAULT 1 NOT NULL
+);
+
+ALTER TABLE ONLY public.task_recurrences
+    ADD CONSTRAINT task_recurrences_pkey PRIMARY KEY (task_id);
+
+ALTER TABLE ONLY public.task_recurrences
+    ADD CONSTRAINT task_recurrences_task_id_fkey FOREIGN KEY (task_id) REFERENCES public.tasks(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+CREATE INDEX task_recurrences_next_occurrence_at_idx ON public.task_recurrences USING btree (next_occurrence_at) WHERE next_occurrence_at IS NOT NULL;
+
+CREATE TRIGGER set_public_task_recurrences_updated_at BEFORE UPDATE ON public.task_recurrences FOR EACH ROW EXECUTE FUNCTION public.set_current_timestamp_updated_at();
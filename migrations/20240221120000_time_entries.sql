@@ -0,0 +1,35 @@
+-- Time tracked on tasks. Entries without `ended_at` are running timers; a member can only have one.
+
+CREATE TABLE public.time_entries (
+    id uuid DEFAULT gen_random_uuid() NOT NULL,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    updated_at timestamp with time zone DEFAULT now() NOT NULL,
+
+    task_id uuid NOT NULL,
+    member_id uuid NOT NULL,
+
+    started_at timestamp with time zone NOT NULL,
+    ended_at timestamp with time zone,
+    duration integer GENERATED ALWAYS AS (EXTRACT(EPOCH FROM (ended_at - started_at))::integer) STORED,
+
+    note text,
+
+    CONSTRAINT time_entries_ended_after_started CHECK (ended_at IS NULL OR ended_at >= started_at)
+);
+
+ALTER TABLE ONLY public.time_entries
+    ADD CONSTRAINT time_entries_pkey PRIMARY KEY (id);
+
+ALTER TABLE ONLY public.time_entries
+    ADD CONSTRAINT time_entries_task_id_fkey FOREIGN KEY (task_id) REFERENCES public.tasks(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+ALTER TABLE ONLY public.time_entries
+    ADD CONSTRAINT time_entries_member_id_fkey FOREIGN KEY (member_id) REFERENCES public.members(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+CREATE INDEX time_entries_task_id_idx ON public.time_entries USING btree (task_id);
+
+CREATE INDEX time_entries_member_id_started_at_idx ON public.time_entries USING btree (member_id, started_at);
+
+CREATE UNIQUE INDEX time_entries_running_timer_key ON public.time_entries USING btree (member_id) WHERE ended_at IS NULL;
+
+CREATE TRIGGER set_public_time_entries_updated_at BEFORE UPDATE ON public.time_entries FOR EACH ROW EXECUTE FUNCTION public.set_current_timestamp_updated_at();
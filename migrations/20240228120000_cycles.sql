@@ -0,0 +1,44 @@
+-- Team cycles (sprints) and point estimates on tasks.
+
+CREATE TABLE public.cycles (
+    id uuid DEFAULT gen_random_uuid() NOT NULL,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    updated_at timestamp with time zone DEFAULT now() NOT NULL,
+
+    team_id uuid NOT NULL,
+
+    name text NOT NULL,
+    starts_at timestamp with time zone NOT NULL,
+    ends_at timestamp with time zone NOT NULL,
+
+    -- Moves unfinished tasks to the team's next cycle when this one is closed.
+    carry_over boolean DEFAULT true NOT NULL,
+    closed_at timestamp with time zone,
+
+    CONSTRAINT cycles_ends_after_starts CHECK (ends_at > starts_at)
+);
+
+ALTER TABLE ONLY public.cycles
+    ADD CONSTRAINT cycles_pkey PRIMARY KEY (id);
+
+ALTER TABLE ONLY public.cycles
+    ADD CONSTRAINT cycles_team_id_fkey FOREIGN KEY (team_id) REFERENCES public.teams(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+CREATE INDEX cycles_team_id_starts_at_idx ON public.cycles USING btree (team_id, starts_at);
+
+CREATE INDEX cycles_open_ends_at_idx ON public.cycles USING btree (ends_at) WHERE closed_at IS NULL;
+
+CREATE TRIGGER set_public_cycles_updated_at BEFORE UPDATE ON public.cycles FOR EACH ROW EXECUTE FUNCTION public.set_current_timestamp_updated_at();
+
+
+ALTER TABLE public.tasks ADD COLUMN estimate integer;
+
+ALTER TABLE public.tasks ADD COLUMN cycle_id uuid;
+
+ALTER TABLE ONLY public.tasks
+    ADD CONSTRAINT tasks_estimate_check CHECK (estimate IS NULL OR estimate >= 0);
+
+ALTER TABLE ONLY public.tasks
+    ADD CONSTRAINT tasks_cycle_id_fkey FOREIGN KEY (cycle_id) REFERENCES public.cycles(id) ON UPDATE CASCADE ON DELETE SET NULL;
+
+CREATE INDEX tasks_cycle_id_idx ON public.tasks USING btree (cycle_id);
@@ -0,0 +1,34 @@
+-- Project milestones.
+
+CREATE TABLE public.milestones (
+    id uuid DEFAULT gen_random_uuid() NOT NULL,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    updated_at timestamp with time zone DEFAULT now() NOT NULL,
+
+    project_id uuid NOT NULL,
+
+    name text NOT NULL,
+    description text,
+    target_date timestamp with time zone,
+    status text DEFAULT 'Planned'::text NOT NULL,
+
+    CONSTRAINT milestones_status_check CHECK (status IN ('Planned', 'InProgress', 'Completed', 'Canceled'))
+);
+
+ALTER TABLE ONLY public.milestones
+    ADD CONSTRAINT milestones_pkey PRIMARY KEY (id);
+
+ALTER TABLE ONLY public.milestones
+    ADD CONSTRAINT milestones_project_id_fkey FOREIGN KEY (project_id) REFERENCES public.projects(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+CREATE INDEX milestones_project_id_idx ON public.milestones USING btree (project_id);
+
+CREATE TRIGGER set_public_milestones_updated_at BEFORE UPDATE ON public.milestones FOR EACH ROW EXECUTE FUNCTION public.set_current_timestamp_updated_at();
+
+
+ALTER TABLE public.tasks ADD COLUMN milestone_id uuid;
+
+ALTER TABLE ONLY public.tasks
+    ADD CONSTRAINT tasks_milestone_id_fkey FOREIGN KEY (milestone_id) REFERENCES public.milestones(id) ON UPDATE CASCADE ON DELETE SET NULL;
+
+CREATE INDEX tasks_milestone_id_idx ON public.tasks USING btree (milestone_id);
@@ -0,0 +1,52 @@
+-- Custom fields, defined per project or for the whole organization (project_id NULL), and
+-- their values per task.
+
+CREATE TABLE public.custom_fields (
+    id uuid DEFAULT gen_random_uuid() NOT NULL,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    updated_at timestamp with time zone DEFAULT now() NOT NULL,
+
+    project_id uuid,
+
+    name text NOT NULL,
+    description text,
+    field_type text NOT NULL,
+    options text[] DEFAULT '{}'::text[] NOT NULL,
+    "position" integer DEFAULT 0 NOT NULL,
+
+    CONSTRAINT custom_fields_field_type_check CHECK (field_type IN ('Text', 'Number', 'SingleSelect', 'MultiSelect', 'Date', 'Member', 'Url'))
+);
+
+ALTER TABLE ONLY public.custom_fields
+    ADD CONSTRAINT custom_fields_pkey PRIMARY KEY (id);
+
+ALTER TABLE ONLY public.custom_fields
+    ADD CONSTRAINT custom_fields_project_id_fkey FOREIGN KEY (project_id) REFERENCES public.projects(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+CREATE UNIQUE INDEX custom_fields_project_id_name_key ON public.custom_fields USING btree (COALESCE(project_id, '00000000-0000-0000-0000-000000000000'::uuid), lower(name));
+
+CREATE TRIGGER set_public_custom_fields_updated_at BEFORE UPDATE ON public.custom_fields FOR EACH ROW EXECUTE FUNCTION public.set_current_timestamp_updated_at();
+
+
+CREATE TABLE public.task_custom_field_values (
+    task_id uuid NOT NULL,
+    field_id uuid NOT NULL,
+    value jsonb NOT NULL,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    updated_at timestamp with time zone DEFAULT now() NOT NULL
+);
+
+ALTER TABLE ONLY public.task_custom_field_values
+    ADD CONSTRAINT task_custom_field_values_pkey PRIMARY KEY (task_id, field_id);
+
+ALTER TABLE ONLY public.task_custom_field_values
+    ADD CONSTRAINT task_custom_field_values_task_id_fkey FOREIGN KEY (task_id) REFERENCES public.tasks(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+ALTER TABLE ONLY public.task_custom_field_values
+    ADD CONSTRAINT task_custom_field_values_field_id_fkey FOREIGN KEY (field_id) REFERENCES public.custom_fields(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+CREATE INDEX task_custom_field_values_field_id_idx ON public.task_custom_field_values USING btree (field_id);
+
+CREATE INDEX task_custom_field_values_value_idx ON public.task_custom_field_values USING gin (value jsonb_path_ops);
+
+CREATE TRIGGER set_public_task_custom_field_values_updated_at BEFORE UPDATE ON public.task_custom_field_values FOR EACH ROW EXECUTE FUNCTION public.set_current_timestamp_updated_at();
@@ -0,0 +1,40 @@
+-- Files attached to tasks. Contents live in the storage backend under storage_key.
+
+CREATE TABLE public.attachments (
+    id uuid DEFAULT gen_random_uuid() NOT NULL,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    updated_at timestamp with time zone DEFAULT now() NOT NULL,
+
+    task_id uuid NOT NULL,
+    uploader_id uuid NOT NULL,
+
+    filename text NOT NULL,
+    content_type text NOT NULL,
+    size bigint NOT NULL,
+    storage_key text NOT NULL,
+
+    CONSTRAINT attachments_size_check CHECK (size >= 0)
+);
+
+ALTER TABLE ONLY public.attachments
+    ADD CONSTRAINT attachments_pkey PRIMARY KEY (id);
+
+ALTER TABLE ONLY public.attachments
+    ADD CONSTRAINT attachments_storage_key_key UNIQUE (storage_key);
+
+ALTER TABLE ONLY public.attachments
+    ADD CONSTRAINT attachments_task_id_fkey FOREIGN KEY (task_id) REFERENCES public.tasks(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+ALTER TABLE ONLY public.attachments
+    ADD CONSTRAINT attachments_uploader_id_fkey FOREIGN KEY (uploader_id) REFERENCES public.members(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+CREATE INDEX attachments_task_id_idx ON public.attachments USING btree (task_id);
+
+CREATE TRIGGER set_public_attachments_updated_at BEFORE UPDATE ON public.attachments FOR EACH ROW EXECUTE FUNCTION public.set_current_timestamp_updated_at();
+
+
+-- Organization limits, the server defaults apply while they are empty.
+
+ALTER TABLE public.self ADD COLUMN max_attachment_size bigint;
+
+ALTER TABLE public.self ADD COLUMN max_attachments_total_size bigint;
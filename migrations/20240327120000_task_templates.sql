@@ -0,0 +1,28 @@
+-- Named task trees that can be instantiated again. The tree is kept as a document so later
+-- changes to the source task don't alter the template.
+
+CREATE TABLE public.task_templates (
+    id uuid DEFAULT gen_random_uuid() NOT NULL,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    updated_at timestamp with time zone DEFAULT now() NOT NULL,
+
+    owner_id uuid NOT NULL,
+    project_id uuid,
+
+    name text NOT NULL,
+    description text,
+    tree jsonb NOT NULL
+);
+
+ALTER TABLE ONLY public.task_templates
+    ADD CONSTRAINT task_templates_pkey PRIMARY KEY (id);
+
+ALTER TABLE ONLY public.task_templates
+    ADD CONSTRAINT task_templates_owner_id_fkey FOREIGN KEY (owner_id) REFERENCES public.members(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+ALTER TABLE ONLY public.task_templates
+    ADD CONSTRAINT task_templates_project_id_fkey FOREIGN KEY (project_id) REFERENCES public.projects(id) ON UPDATE CASCADE ON DELETE SET NULL;
+
+CREATE INDEX task_templates_project_id_idx ON public.task_templates USING btree (project_id);
+
+CREATE TRIGGER set_public_task_templates_updated_at BEFORE UPDATE ON public.task_templates FOR EACH ROW EXECUTE FUNCTION public.set_current_timestamp_updated_at();
@@ -0,0 +1,82 @@
+-- Soft delete for tasks, projects and teams. Deleted rows keep their relations so they can be
+-- restored, and are purged after the retention period. Rows deleted together share the same
+-- deleted_at, which is how a task is restored with its subtasks.
+
+ALTER TABLE public.tasks ADD COLUMN deleted_at timestamp with time zone;
+ALTER TABLE public.tasks ADD COLUMN deleted_by uuid;
+
+ALTER TABLE public.projects ADD COLUMN deleted_at timestamp with time zone;
+ALTER TABLE public.projects ADD COLUMN deleted_by uuid;
+
+ALTER TABLE public.teams ADD COLUMN deleted_at timestamp with time zone;
+ALTER TABLE public.teams ADD COLUMN deleted_by uuid;
+
+ALTER TABLE ONLY public.tasks
+    ADD CONSTRAINT tasks_deleted_by_fkey FOREIGN KEY (deleted_by) REFERENCES public.members(id) ON UPDATE CASCADE ON DELETE SET NULL;
+
+ALTER TABLE ONLY public.projects
+    ADD CONSTRAINT projects_deleted_by_fkey FOREIGN KEY (deleted_by) REFERENCES public.members(id) ON UPDATE CASCADE ON DELETE SET NULL;
+
+ALTER TABLE ONLY public.teams
+    ADD CONSTRAINT teams_deleted_by_fkey FOREIGN KEY (deleted_by) REFERENCES public.members(id) ON UPDATE CASCADE ON DELETE SET NULL;
+
+CREATE INDEX tasks_deleted_at_idx ON public.tasks USING btree (deleted_at) WHERE deleted_at IS NOT NULL;
+
+CREATE INDEX projects_deleted_at_idx ON public.projects USING btree (deleted_at) WHERE deleted_at IS NOT NULL;
+
+CREATE INDEX teams_deleted_at_idx ON public.teams USING btree (deleted_at) WHERE deleted_at IS NOT NULL;
+
+
+-- Deleted rows leave the search index and come back when restored.
+
+CREATE OR REPLACE FUNCTION public.index_task_search_document() RETURNS trigger
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+  IF TG_OP = 'DELETE' OR NEW.deleted_at IS NOT NULL THEN
+    DELETE FROM public.search_documents WHERE resource_type = 'Task' AND resource_id = COALESCE(NEW.id, OLD.id);
+    RETURN COALESCE(NEW, OLD);
+  END IF;
+
+  PERFORM public.upsert_search_document('Task', NEW.id, NEW.title, NEW.description);
+  RETURN NEW;
+END;
+$$;
+
+CREATE OR REPLACE FUNCTION public.index_project_search_document() RETURNS trigger
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+  IF TG_OP = 'DELETE' OR NEW.deleted_at IS NOT NULL THEN
+    DELETE FROM public.search_documents WHERE resource_type = 'Project' AND resource_id = COALESCE(NEW.id, OLD.id);
+    RETURN COALESCE(NEW, OLD);
+  END IF;
+
+  PERFORM public.upsert_search_document('Project', NEW.id, NEW.name, NEW.description);
+  RETURN NEW;
+END;
+$$;
+
+CREATE OR REPLACE FUNCTION public.index_team_search_document() RETURNS trigger
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+  IF TG_OP = 'DELETE' OR NEW.deleted_at IS NOT NULL THEN
+    DELETE FROM public.search_documents WHERE resource_type = 'Team' AND resource_id = COALESCE(NEW.id, OLD.id);
+    RETURN COALESCE(NEW, OLD);
+  END IF;
+
+  PERFORM public.upsert_search_document('Team', NEW.id, NEW.name, NULL);
+  RETURN NEW;
+END;
+$$;
+
+DROP TRIGGER index_tasks_search_document ON public.tasks;
+DROP TRIGGER index_projects_search_document ON public.projects;
+DROP TRIGGER index_teams_search_document ON public.teams;
+
+CREATE TRIGGER index_tasks_search_document AFTER INSERT OR DELETE OR UPDATE OF title, description, deleted_at ON public.tasks FOR EACH ROW EXECUTE FUNCTION public.index_task_search_document();
+
+CREATE TRIGGER index_projects_search_document AFTER INSERT OR DELETE OR UPDATE OF name, description, deleted_at ON public.projects FOR EACH ROW EXECUTE FUNCTION public.index_project_search_document();
+
+CREATE TRIGGER index_teams_search_document AFTER INSERT OR DELETE OR UPDATE OF name, deleted_at ON public.teams FOR EACH ROW EXECUTE FUNCTION public.index_team_search_document();
@@ -0,0 +1,29 @@
+-- Manual order of tasks inside a board column, which is a project and a workflow state. Ranks are
+-- fractional indexes over the base 36 digits compared bytewise, so a task moves between two others
+-- by changing only its own rank. Ranks never end with 0, which leaves room before every rank.
+-- Tasks without a rank go after the ranked ones, in creation order.
+
+ALTER TABLE public.tasks ADD COLUMN rank text COLLATE "C";
+
+ALTER TABLE ONLY public.tasks
+    ADD CONSTRAINT tasks_rank_check CHECK (rank ~ '^[0-9a-z]*[1-9a-z]$');
+
+CREATE INDEX tasks_project_id_state_id_rank_idx ON public.tasks USING btree (project_id, state_id, rank);
+
+
+-- A task changing column goes last in the new one, unless its rank is set by the same update.
+
+CREATE FUNCTION public.reset_task_rank() RETURNS trigger
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+  IF (NEW.project_id IS DISTINCT FROM OLD.project_id OR NEW.state_id IS DISTINCT FROM OLD.state_id)
+     AND NEW.rank IS NOT DISTINCT FROM OLD.rank THEN
+    NEW.rank := NULL;
+  END IF;
+  RETURN NEW;
+END;
+$$;
+
+-- Triggers fire by name, so this one sees the state derived by sync_public_tasks_workflow_state.
+CREATE TRIGGER update_public_tasks_rank BEFORE UPDATE ON public.tasks FOR EACH ROW EXECUTE FUNCTION public.reset_task_rank();
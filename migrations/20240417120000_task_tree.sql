@@ -0,0 +1,7 @@
+-- Subtasks form a tree through parent_id. Cycles are rejected when a task is moved, a task can't
+-- be its own parent at all.
+
+ALTER TABLE ONLY public.tasks
+    ADD CONSTRAINT tasks_parent_id_check CHECK (parent_id <> id);
+
+CREATE INDEX tasks_parent_id_idx ON public.tasks USING btree (parent_id);
@@ -0,0 +1,98 @@
+-- Members watching a task or a project are notified of its activity, and watchers of a project of
+-- the activity of its tasks. Owners, leads and assignees start watching automatically.
+
+CREATE TABLE public.watchers (
+    member_id uuid NOT NULL,
+    resource_type text NOT NULL,
+    resource_id uuid NOT NULL,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    CONSTRAINT watchers_resource_type_check CHECK (resource_type IN ('Task', 'Project'))
+);
+
+ALTER TABLE ONLY public.watchers
+    ADD CONSTRAINT watchers_pkey PRIMARY KEY (member_id, resource_type, resource_id);
+
+ALTER TABLE ONLY public.watchers
+    ADD CONSTRAINT watchers_member_id_fkey FOREIGN KEY (member_id) REFERENCES public.members(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+CREATE INDEX watchers_resource_type_resource_id_idx ON public.watchers USING btree (resource_type, resource_id);
+
+
+CREATE TABLE public.notifications (
+    id uuid DEFAULT gen_random_uuid() NOT NULL,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    updated_at timestamp with time zone DEFAULT now() NOT NULL,
+
+    member_id uuid NOT NULL,
+    activity_id uuid NOT NULL,
+    read_at timestamp with time zone
+);
+
+ALTER TABLE ONLY public.notifications
+    ADD CONSTRAINT notifications_pkey PRIMARY KEY (id);
+
+ALTER TABLE ONLY public.notifications
+    ADD CONSTRAINT notifications_member_id_fkey FOREIGN KEY (member_id) REFERENCES public.members(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+ALTER TABLE ONLY public.notifications
+    ADD CONSTRAINT notifications_activity_id_fkey FOREIGN KEY (activity_id) REFERENCES public.activity(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+CREATE INDEX notifications_member_id_created_at_idx ON public.notifications USING btree (member_id, created_at);
+
+CREATE INDEX notifications_unread_member_id_idx ON public.notifications USING btree (member_id) WHERE read_at IS NULL;
+
+CREATE TRIGGER set_public_notifications_updated_at BEFORE UPDATE ON public.notifications FOR EACH ROW EXECUTE FUNCTION public.set_current_timestamp_updated_at();
+
+
+-- Watchers are only added when someone becomes owner, lead or assignee, so unwatching sticks.
+-- Leads aren't foreign keys, so unknown members are skipped.
+
+CREATE FUNCTION public.add_watcher(member_id uuid, resource_type text, resource_id uuid) RETURNS void
+    LANGUAGE sql
+    AS $$
+  INSERT INTO public.watchers (member_id, resource_type, resource_id)
+  SELECT $1, $2, $3
+  WHERE EXISTS (SELECT 1 FROM public.members WHERE members.id = $1)
+  ON CONFLICT DO NOTHING;
+$$;
+
+CREATE FUNCTION public.watch_owned_resource() RETURNS trigger
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+  IF TG_OP = 'INSERT' OR NEW.owner_id IS DISTINCT FROM OLD.owner_id THEN
+    PERFORM public.add_watcher(NEW.owner_id, TG_ARGV[0], NEW.id);
+  END IF;
+  IF TG_OP = 'INSERT' OR NEW.lead_id IS DISTINCT FROM OLD.lead_id THEN
+    PERFORM public.add_watcher(NEW.lead_id, TG_ARGV[0], NEW.id);
+  END IF;
+  RETURN NEW;
+END;
+$$;
+
+CREATE TRIGGER watch_public_tasks AFTER INSERT OR UPDATE OF owner_id, lead_id ON public.tasks FOR EACH ROW EXECUTE FUNCTION public.watch_owned_resource('Task');
+
+CREATE TRIGGER watch_public_projects AFTER INSERT OR UPDATE OF owner_id, lead_id ON public.projects FOR EACH ROW EXECUTE FUNCTION public.watch_owned_resource('Project');
+
+CREATE FUNCTION public.watch_assigned_task() RETURNS trigger
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+  PERFORM public.add_watcher(NEW.assignee_id, 'Task', NEW.task_id);
+  RETURN NEW;
+END;
+$$;
+
+CREATE TRIGGER watch_public_tasks_by_assignees AFTER INSERT ON public.tasks_by_assignees FOR EACH ROW EXECUTE FUNCTION public.watch_assigned_task();
+
+
+INSERT INTO public.watchers (member_id, resource_type, resource_id)
+SELECT watching.member_id, watching.resource_type, watching.resource_id
+FROM (
+    SELECT owner_id AS member_id, 'Task' AS resource_type, id AS resource_id FROM public.tasks
+    UNION SELECT lead_id, 'Task', id FROM public.tasks
+    UNION SELECT assignee_id, 'Task', task_id FROM public.tasks_by_assignees
+    UNION SELECT owner_id, 'Project', id FROM public.projects
+    UNION SELECT lead_id, 'Project', id FROM public.projects
+) AS watching
+JOIN public.members ON members.id = watching.member_id;
@@ -0,0 +1,82 @@
+-- Email about assignments, mentions and approaching due dates. Events are queued here by the
+-- triggers below and by the mail scheduler, which sends them right away or in a daily digest
+-- depending on the member's preference.
+
+ALTER TABLE public.members
+    ADD COLUMN email_notifications text DEFAULT 'Immediate' NOT NULL,
+    ADD COLUMN email_digest_sent_at timestamp with time zone;
+
+ALTER TABLE ONLY public.members
+    ADD CONSTRAINT members_email_notifications_check CHECK (email_notifications IN ('Immediate', 'Digest', 'Off'));
+
+
+CREATE TABLE public.email_events (
+    id uuid DEFAULT gen_random_uuid() NOT NULL,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+
+    member_id uuid NOT NULL,
+    kind text NOT NULL,
+    task_id uuid NOT NULL,
+    comment_id uuid,
+    due_date timestamp with time zone,
+
+    sent_at timestamp with time zone,
+    attempts integer DEFAULT 0 NOT NULL,
+    last_error text,
+
+    CONSTRAINT email_events_kind_check CHECK (kind IN ('Assigned', 'Mentioned', 'DueSoon'))
+);
+
+ALTER TABLE ONLY public.email_events
+    ADD CONSTRAINT email_events_pkey PRIMARY KEY (id);
+
+ALTER TABLE ONLY public.email_events
+    ADD CONSTRAINT email_events_member_id_fkey FOREIGN KEY (member_id) REFERENCES public.members(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+ALTER TABLE ONLY public.email_events
+    ADD CONSTRAINT email_events_task_id_fkey FOREIGN KEY (task_id) REFERENCES public.tasks(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+ALTER TABLE ONLY public.email_events
+    ADD CONSTRAINT email_events_comment_id_fkey FOREIGN KEY (comment_id) REFERENCES public.comments(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+CREATE INDEX email_events_pending_member_id_idx ON public.email_events USING btree (member_id) WHERE sent_at IS NULL;
+
+-- A comment mentions a member once, a due date is reminded once.
+CREATE UNIQUE INDEX email_events_mentioned_idx ON public.email_events USING btree (member_id, comment_id) WHERE kind = 'Mentioned';
+
+CREATE UNIQUE INDEX email_events_due_soon_idx ON public.email_events USING btree (member_id, task_id, due_date) WHERE kind = 'DueSoon';
+
+
+-- Members assigning themselves to the tasks they create don't need to be told.
+
+CREATE FUNCTION public.queue_assigned_email() RETURNS trigger
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+  INSERT INTO public.email_events (member_id, kind, task_id)
+  SELECT NEW.assignee_id, 'Assigned', tasks.id
+  FROM public.tasks
+  WHERE tasks.id = NEW.task_id AND tasks.owner_id <> NEW.assignee_id AND tasks.deleted_at IS NULL;
+  RETURN NEW;
+END;
+$$;
+
+CREATE TRIGGER queue_public_tasks_by_assignees_email AFTER INSERT ON public.tasks_by_assignees FOR EACH ROW EXECUTE FUNCTION public.queue_assigned_email();
+
+-- Members are mentioned in comment bodies as <@member-id>.
+
+CREATE FUNCTION public.queue_mentioned_emails() RETURNS trigger
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+  INSERT INTO public.email_events (member_id, kind, task_id, comment_id)
+  SELECT DISTINCT members.id, 'Mentioned', NEW.task_id, NEW.id
+  FROM regexp_matches(NEW.body, '<@([0-9a-fA-F-]{36})>', 'g') AS mention
+  JOIN public.members ON members.id::text = lower(mention[1])
+  WHERE members.id <> NEW.author_id
+  ON CONFLICT DO NOTHING;
+  RETURN NEW;
+END;
+$$;
+
+CREATE TRIGGER queue_public_comments_emails AFTER INSERT OR UPDATE OF body ON public.comments FOR EACH ROW EXECUTE FUNCTION public.queue_mentioned_emails();
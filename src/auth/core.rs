@@ -1,4 +1,3 @@
-use async_graphql::Error;
 use chrono::{Duration, Utc};
 use oauth2::{AuthorizationCode, CsrfToken};
 use poem::http::header::SET_COOKIE;
@@ -40,38 +39,32 @@ const GITHUB_USER_API: &str = "https://api.github.com/user";
 pub const COOKIE_SESSION_TOKEN_NAME: &str = "plexo-session-token";
 
 #[handler]
-pub async fn github_sign_in_handler(plexo_engine: Data<&Engine>) -> impl IntoResponse {
+pub async fn github_sign_in_handler(plexo_engine: Data<&Engine>) -> Result<Response> {
     let Some((url, _)) = plexo_engine.0.auth.new_github_authorize_url() else {
-        return Response::builder()
-            .status(StatusCode::INTERNAL_SERVER_ERROR)
-            .header("Content-Type", "application/json")
-            .body(Body::from_json(&Error::new("Internal Server Error (github)")).unwrap());
+        return Err(PlexoAppError::Internal("GitHub sign in isn't configured".to_string()).into());
     };
 
-    Redirect::temporary(url.to_string())
+    Ok(Redirect::temporary(url.to_string())
         // .with_header("Set-Cookie", session_token_cookie.to_string())
         // .with_header(CACHE_CONTROL, "no-cache, no-store, must-revalidate")
         // .with_header(PRAGMA, "no-cache")
         // .with_header(EXPIRES, "0")
-        .into_response()
+        .into_response())
 }
 
 #[handler]
 pub async fn github_callback_handler(
     plexo_engine: Data<&Engine>,
     params: Query<GithubCallbackParams>,
-) -> impl IntoResponse {
+) -> Result<Response> {
     let code = AuthorizationCode::new(params.code.clone());
     let state = CsrfToken::new(params.state.clone());
 
-    let gh_response = plexo_engine.auth.exchange_github_code(code, state).await;
-
-    let Ok(access_token) = gh_response else {
-        return Response::builder()
-            .status(StatusCode::INTERNAL_SERVER_ERROR)
-            .header("Content-Type", "application/json")
-            .body(Body::from_json(&gh_response).unwrap());
-    };
+    let access_token = plexo_engine
+        .auth
+        .exchange_github_code(code, state)
+        .await
+        .map_err(PlexoAppError::Upstream)?;
 
     let client = reqwest::Client::new();
 
@@ -81,54 +74,45 @@ pub async fn github_callback_handler(
         .header("User-Agent", "plexo-agent")
         .send()
         .await
-        .unwrap()
+        .map_err(|e| PlexoAppError::Upstream(e.to_string()))?
         .json::<Value>()
         .await
-        .unwrap();
+        .map_err(|e| PlexoAppError::Upstream(e.to_string()))?;
 
     let github_id: String = github_user_data
         .get("id")
-        .unwrap()
-        .as_i64()
-        .unwrap()
+        .and_then(|v| v.as_i64())
+        .ok_or_else(|| PlexoAppError::Upstream("GitHub user without id".to_string()))?
         .to_string();
 
     let user_email = github_user_data
         .get("email")
-        .map(|v| {
-            v.as_str()
-                .map(|s| s.to_string())
-                .unwrap_or(format!("{}@no-email.github.com", github_id.clone()))
-        })
-        .unwrap();
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+        .unwrap_or(format!("{}@no-email.github.com", github_id.clone()));
 
     let user_name = github_user_data
         .get("name")
-        .map(|v| {
-            v.as_str()
-                .map(|s| s.to_string())
-                .unwrap_or(github_id.clone())
-        })
-        .unwrap();
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+        .unwrap_or(github_id.clone());
 
     let member: crate::sdk::member::Member = match plexo_engine
         .get_member_by_github_id(github_id.clone())
         .await
     {
         Some(member) => member,
-        None => {
-            plexo_engine
-                .create_member_from_github(user_email, user_name, github_id)
-                .await
-        }
+        None => plexo_engine
+            .create_member_from_github(user_email, user_name, github_id)
+            .await
+            .map_err(PlexoAppError::from)?,
     };
 
-    let Ok(session_token) = plexo_engine.auth.jwt_engine.create_session_token(&member) else {
-        return Response::builder()
-            .status(StatusCode::INTERNAL_SERVER_ERROR)
-            .header("Content-Type", "application/json")
-            .body(Body::from_json(&Error::new("Internal Server Error")).unwrap());
-    };
+    let session_token = plexo_engine
+        .auth
+        .jwt_engine
+        .create_session_token(&member)
+        .map_err(|e| PlexoAppError::Internal(e.to_string()))?;
 
     let mut session_token_cookie = Cookie::named(COOKIE_SESSION_TOKEN_NAME);
 
@@ -139,14 +123,14 @@ pub async fn github_callback_handler(
     session_token_cookie.set_expires(Utc::now() + Duration::days(7));
     session_token_cookie.set_path("/");
 
-    Response::builder()
+    Ok(Response::builder()
         .status(StatusCode::FOUND)
         .header(LOCATION, "/")
         .header(CACHE_CONTROL, "no-cache, no-store, must-revalidate")
         .header(PRAGMA, "no-cache")
         .header(EXPIRES, "0")
         .header(SET_COOKIE, session_token_cookie.to_string())
-        .body(Body::empty())
+        .body(Body::empty()))
 }
 
 #[handler]
@@ -178,52 +162,27 @@ pub struct EmailLoginParams {
 pub async fn email_basic_login_handler(
     plexo_engine: Data<&Engine>,
     params: Json<EmailLoginParams>,
-) -> impl IntoResponse {
+) -> Result<Response> {
     let Some(member) = plexo_engine.get_member_by_email(params.email.clone()).await else {
-        return Response::builder()
-            .status(StatusCode::UNAUTHORIZED)
-            .header("Content-Type", "application/json")
-            .body(
-                Body::from_json(json!({
-                    "error": "Member not found"
-                }))
-                .unwrap(),
-            );
+        return Err(PlexoAppError::EmailNotFound.into());
     };
 
     let Some(password_hash) = member.password_hash.clone() else {
-        return Response::builder()
-            .status(StatusCode::UNAUTHORIZED)
-            .header("Content-Type", "application/json")
-            .body(
-                Body::from_json(json!({
-                    "error": "Invalid password"
-                }))
-                .unwrap(),
-            );
+        return Err(PlexoAppError::InvalidPassword.into());
     };
 
     if !plexo_engine
         .auth
         .validate_password(params.password.as_str(), password_hash.as_str())
     {
-        return Response::builder()
-            .status(StatusCode::UNAUTHORIZED)
-            .header("Content-Type", "application/json")
-            .body(
-                Body::from_json(json!({
-                    "error": "Invalid password"
-                }))
-                .unwrap(),
-            );
+        return Err(PlexoAppError::InvalidPassword.into());
     };
 
-    let Ok(session_token) = plexo_engine.auth.jwt_engine.create_session_token(&member) else {
-        return Response::builder()
-            .status(StatusCode::INTERNAL_SERVER_ERROR)
-            .header("Content-Type", "application/json")
-            .body(Body::from_json(&Error::new("Internal Server Error")).unwrap());
-    };
+    let session_token = plexo_engine
+        .auth
+        .jwt_engine
+        .create_session_token(&member)
+        .map_err(|e| PlexoAppError::Internal(e.to_string()))?;
 
     let mut session_token_cookie = Cookie::named(COOKIE_SESSION_TOKEN_NAME);
 
@@ -234,11 +193,11 @@ pub async fn email_basic_login_handler(
     session_token_cookie.set_expires(Utc::now() + Duration::days(7));
     session_token_cookie.set_path("/");
 
-    Response::builder()
+    Ok(Response::builder()
         .status(StatusCode::OK)
         .header(SET_COOKIE, session_token_cookie.to_string())
         .header("Content-Type", "application/json")
-        .body(Body::from_json(json!({ "access_token": session_token })).unwrap())
+        .body(json!({ "access_token": session_token }).to_string()))
 }
 
 #[derive(Debug, Deserialize)]
@@ -271,35 +230,24 @@ pub async fn email_basic_register_handler(
     // let (plexo_engine, member_id) = extract_context(ctx)?;
 
     if (plexo_engine.get_member_by_email(params.email.clone()).await).is_some() {
-        return Ok(Response::builder()
-            .status(StatusCode::UNAUTHORIZED)
-            .header("Content-Type", "application/json")
-            .body(
-                Body::from_json(json!({
-                    "error": "Member already exists"
-                }))
-                .unwrap(),
-            ));
+        return Err(PlexoAppError::EmailAlreadyExists.into());
     };
 
-    let password_hash = plexo_engine.auth.hash_password(params.password.as_str());
+    let password_hash = plexo_engine
+        .auth
+        .hash_password(params.password.as_str())
+        .map_err(|e| PlexoAppError::Internal(e.to_string()))?;
 
-    let Some(member) = plexo_engine
+    let member = plexo_engine
         .create_member_from_email(params.email.clone(), params.name.clone(), password_hash)
         .await
-    else {
-        return Ok(Response::builder()
-            .status(StatusCode::INTERNAL_SERVER_ERROR)
-            .header("Content-Type", "application/json")
-            .body(Body::from_json(&Error::new("Internal Server Error")).unwrap()));
-    };
+        .map_err(PlexoAppError::from)?;
 
-    let Ok(session_token) = plexo_engine.auth.jwt_engine.create_session_token(&member) else {
-        return Ok(Response::builder()
-            .status(StatusCode::INTERNAL_SERVER_ERROR)
-            .header("Content-Type", "application/json")
-            .body(Body::from_json(&Error::new("Internal Server Error")).unwrap()));
-    };
+    let session_token = plexo_engine
+        .auth
+        .jwt_engine
+        .create_session_token(&member)
+        .map_err(|e| PlexoAppError::Internal(e.to_string()))?;
 
     let mut session_token_cookie = Cookie::named(COOKIE_SESSION_TOKEN_NAME);
 
@@ -314,7 +262,7 @@ pub async fn email_basic_register_handler(
         .status(StatusCode::OK)
         .header(SET_COOKIE, session_token_cookie.to_string())
         .header("Content-Type", "application/json")
-        .body(Body::from_json(json!({ "access_token": session_token })).unwrap()))
+        .body(json!({ "access_token": session_token }).to_string()))
 }
 
 #[handler]
@@ -333,5 +281,5 @@ pub async fn logout_handler() -> Result<Response> {
         .status(StatusCode::OK)
         .header(SET_COOKIE, session_token_cookie.to_string())
         .header("Content-Type", "application/json")
-        .body(Body::from_json(json!({ "access_token": "" })).unwrap()))
+        .body(json!({ "access_token": "" }).to_string()))
 }
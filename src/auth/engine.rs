@@ -117,7 +117,7 @@ impl AuthEngine {
         let token_result = self
             .github_client
             .as_ref()
-            .unwrap()
+            .ok_or_else(|| "GitHub sign in isn't configured".to_string())?
             .exchange_code(code)
             .request_async(async_http_client)
             .await;
@@ -147,13 +147,12 @@ impl AuthEngine {
             .is_ok()
     }
 
-    pub fn hash_password(&self, password: &str) -> String {
+    pub fn hash_password(&self, password: &str) -> Result<String, argon2::password_hash::Error> {
         let salt = SaltString::generate(&mut OsRng);
 
         Argon2::default()
             .hash_password(password.as_bytes(), &salt)
-            .unwrap()
-            .to_string()
+            .map(|hash| hash.to_string())
     }
 
     pub fn has_github_client(&self) -> bool {
@@ -20,8 +20,8 @@ pub struct PlexoAuthTokenClaims {
 }
 
 impl PlexoAuthTokenClaims {
-    pub fn member_id(&self) -> Uuid {
-        Uuid::parse_str(&self.sub).unwrap()
+    pub fn member_id(&self) -> Option<Uuid> {
+        Uuid::parse_str(&self.sub).ok()
     }
 }
 
@@ -51,10 +51,14 @@ impl JWTEngine {
     }
 
     pub fn decode_session_token(&self, token: &str) -> Result<PlexoAuthTokenClaims, Error> {
+        // Tokens carrying an `aud` are rejected unless the expected audience is set.
+        let mut validation = jsonwebtoken::Validation::default();
+        validation.set_audience(&["session.plexo.app"]);
+
         let token_data = decode::<PlexoAuthTokenClaims>(
             token,
             &DecodingKey::from_secret(self.access_token_secret.as_ref()),
-            &jsonwebtoken::Validation::default(),
+            &validation,
         )?;
 
         Ok(token_data.claims)
@@ -25,5 +25,48 @@ lazy_static! {
     pub static ref JWT_ACCESS_TOKEN_SECRET: String = var("JWT_ACCESS_TOKEN_SECRET").unwrap_or("secret".into());
     pub static ref JWT_REFRESH_TOKEN_SECRET: String = var("JWT_REFRESH_TOKEN_SECRET").unwrap_or("secret".into());
 
+    pub static ref RECURRENCE_SCHEDULER_INTERVAL_SECS: u64 = var("RECURRENCE_SCHEDULER_INTERVAL_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(60);
+
+    pub static ref CYCLE_SCHEDULER_INTERVAL_SECS: u64 = var("CYCLE_SCHEDULER_INTERVAL_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(300);
+
+    pub static ref ATTACHMENTS_STORAGE_PATH: String = var("ATTACHMENTS_STORAGE_PATH").unwrap_or("/data/attachments".into());
+    /// Used until an admin sets the organization limits.
+    pub static ref MAX_ATTACHMENT_SIZE_BYTES: i64 = var("MAX_ATTACHMENT_SIZE_BYTES").ok().and_then(|v| v.parse().ok()).unwrap_or(25 * 1024 * 1024);
+    pub static ref MAX_ATTACHMENTS_TOTAL_SIZE_BYTES: i64 = var("MAX_ATTACHMENTS_TOTAL_SIZE_BYTES").ok().and_then(|v| v.parse().ok()).unwrap_or(10 * 1024 * 1024 * 1024);
+
+    pub static ref TRASH_RETENTION_DAYS: i32 = var("TRASH_RETENTION_DAYS").ok().and_then(|v| v.parse().ok()).unwrap_or(30);
+    pub static ref TRASH_PURGE_INTERVAL_SECS: u64 = var("TRASH_PURGE_INTERVAL_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(3600);
+
+    /// Board columns are rebalanced once a task rank gets longer than this.
+    pub static ref RANK_REBALANCE_LENGTH: i32 = var("RANK_REBALANCE_LENGTH").ok().and_then(|v| v.parse().ok()).unwrap_or(12);
+    pub static ref RANK_REBALANCE_INTERVAL_SECS: u64 = var("RANK_REBALANCE_INTERVAL_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(600);
+
+    /// Deepest level of subtasks walked by `Task.descendants` and `Task.progress`.
+    pub static ref TASK_TREE_MAX_DEPTH: i32 = var("TASK_TREE_MAX_DEPTH").ok().and_then(|v| v.parse().ok()).unwrap_or(32);
+
+    /// Email goes through SMTP when a host is set, and to a maildir under MAILDIR_PATH otherwise.
+    pub static ref SMTP_HOST: Option<String> = var("SMTP_HOST").ok();
+    pub static ref SMTP_PORT: u16 = var("SMTP_PORT").ok().and_then(|v| v.parse().ok()).unwrap_or(587);
+    pub static ref SMTP_USERNAME: Option<String> = var("SMTP_USERNAME").ok();
+    pub static ref SMTP_PASSWORD: Option<String> = var("SMTP_PASSWORD").ok();
+    pub static ref MAILDIR_PATH: String = var("MAILDIR_PATH").unwrap_or("/data/mail".into());
+    pub static ref MAIL_FROM: String = var("MAIL_FROM").unwrap_or(format!("{} <no-reply@plexo.app>", *ORGANIZATION_NAME));
+
+    pub static ref MAIL_SCHEDULER_INTERVAL_SECS: u64 = var("MAIL_SCHEDULER_INTERVAL_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(30);
+    /// Hour of the day, in UTC, when digests go out.
+    pub static ref MAIL_DIGEST_HOUR: u32 = var("MAIL_DIGEST_HOUR").ok().and_then(|v| v.parse().ok()).unwrap_or(8);
+    /// Assignees and leads are reminded of tasks due within this many hours.
+    pub static ref MAIL_DUE_SOON_HOURS: i32 = var("MAIL_DUE_SOON_HOURS").ok().and_then(|v| v.parse().ok()).unwrap_or(24);
+    pub static ref MAIL_MAX_ATTEMPTS: i32 = var("MAIL_MAX_ATTEMPTS").ok().and_then(|v| v.parse().ok()).unwrap_or(5);
+
+    pub static ref WEBHOOK_SCHEDULER_INTERVAL_SECS: u64 = var("WEBHOOK_SCHEDULER_INTERVAL_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(5);
+    pub static ref WEBHOOK_TIMEOUT_SECS: u64 = var("WEBHOOK_TIMEOUT_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(10);
+    /// A failed delivery is retried after this delay, doubled on every attempt.
+    pub static ref WEBHOOK_RETRY_BASE_SECS: i64 = var("WEBHOOK_RETRY_BASE_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(30);
+    pub static ref WEBHOOK_MAX_ATTEMPTS: i32 = var("WEBHOOK_MAX_ATTEMPTS").ok().and_then(|v| v.parse().ok()).unwrap_or(8);
+
+    /// Notifications waiting for slow subscribers before they start missing some.
+    pub static ref NOTIFICATION_CHANNEL_CAPACITY: usize = var("NOTIFICATION_CHANNEL_CAPACITY").ok().and_then(|v| v.parse().ok()).unwrap_or(1024);
+
     pub static ref STATIC_PAGE_ENABLED: bool = var("STATIC_PAGE_ENABLED").unwrap_or("false".into()).to_lowercase() == "true";
 }
@@ -1,3 +1,8 @@
+use std::sync::Arc;
+
+use poem::{http::StatusCode, Response};
+use serde::Serialize;
+use serde_json::json;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -14,6 +19,209 @@ pub enum PlexoAppError {
     EmailNotFound,
     #[error("Email already exists")]
     EmailAlreadyExists,
+    #[error("{0} {1} not found")]
+    NotFound(&'static str, String),
+    #[error("You are not allowed to perform this action")]
+    Forbidden,
+    #[error("Invalid {0}: {1}")]
+    Validation(String, String),
+    #[error("Invalid input: {}", describe_field_errors(.0))]
+    InvalidInput(Vec<FieldError>),
+    #[error("{0}")]
+    Conflict(String),
+    #[error("Database error")]
+    Database(#[source] Arc<sqlx::Error>),
+    #[error("Upstream service error: {0}")]
+    Upstream(String),
+    #[error("Internal server error")]
+    Internal(String),
+    #[error("Task dependency would create a cycle")]
+    DependencyCycle,
+    #[error("Task can't become a subtask of itself or of its subtasks")]
+    SubtaskCycle,
+    #[error("Task is blocked by unfinished tasks")]
+    TaskBlocked,
+    #[error("Workflow state doesn't belong to the task's project")]
+    InvalidWorkflowState,
+    #[error("Workflow doesn't allow this state transition")]
+    TransitionNotAllowed,
+    #[error("Invalid recurrence rule: {0}")]
+    InvalidRecurrenceRule(String),
+    #[error("A timer is already running")]
+    TimerAlreadyRunning,
+    #[error("No timer is running")]
+    NoRunningTimer,
+    #[error("Invalid custom field value: {0}")]
+    InvalidCustomFieldValue(String),
+    #[error("Attachment is larger than the {0} bytes allowed")]
+    AttachmentTooLarge(i64),
+    #[error("Organization attachment storage is full")]
+    AttachmentQuotaExceeded,
+    #[error("Tasks to move between aren't next to each other in the target column")]
+    InvalidTaskPosition,
     #[error("Poem error")]
     PoemError(#[from] poem::error::NotFoundError),
 }
+
+/// A problem with one field of a mutation input. Nested fields are named by their path, such as
+/// `subtasks.0.title`.
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldError {
+    pub field: String,
+    pub reason: String,
+}
+
+fn describe_field_errors(errors: &[FieldError]) -> String {
+    errors
+        .iter()
+        .map(|e| format!("{} {}", e.field, e.reason))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Stable error codes, sent as `extensions.code` in GraphQL errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlexoErrorCode {
+    Unauthenticated,
+    Forbidden,
+    NotFound,
+    Validation,
+    Conflict,
+    Database,
+    Upstream,
+    Internal,
+}
+
+impl PlexoErrorCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Unauthenticated => "UNAUTHENTICATED",
+            Self::Forbidden => "FORBIDDEN",
+            Self::NotFound => "NOT_FOUND",
+            Self::Validation => "VALIDATION",
+            Self::Conflict => "CONFLICT",
+            Self::Database => "DATABASE",
+            Self::Upstream => "UPSTREAM",
+            Self::Internal => "INTERNAL",
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Self::Unauthenticated => StatusCode::UNAUTHORIZED,
+            Self::Forbidden => StatusCode::FORBIDDEN,
+            Self::NotFound => StatusCode::NOT_FOUND,
+            Self::Validation => StatusCode::UNPROCESSABLE_ENTITY,
+            Self::Conflict => StatusCode::CONFLICT,
+            Self::Database => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::Upstream => StatusCode::BAD_GATEWAY,
+            Self::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Classifies database errors by their Postgres error class.
+    pub fn of_database_error(error: &sqlx::Error) -> Self {
+        match error {
+            sqlx::Error::RowNotFound => Self::NotFound,
+            sqlx::Error::Database(e) if e.is_unique_violation() => Self::Conflict,
+            sqlx::Error::Database(e) if e.is_foreign_key_violation() || e.is_check_violation() => {
+                Self::Validation
+            }
+            _ => Self::Database,
+        }
+    }
+}
+
+impl PlexoAppError {
+    pub fn not_found(resource: &'static str, id: impl ToString) -> Self {
+        Self::NotFound(resource, id.to_string())
+    }
+
+    pub fn validation(field: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self::Validation(field.into(), reason.into())
+    }
+
+    pub fn code(&self) -> PlexoErrorCode {
+        match self {
+            Self::MissingAuthorizationToken
+            | Self::InvalidAuthorizationToken
+            | Self::InvalidPassword
+            | Self::EmailNotFound => PlexoErrorCode::Unauthenticated,
+            Self::Forbidden => PlexoErrorCode::Forbidden,
+            Self::NotFound(..) | Self::PoemError(_) => PlexoErrorCode::NotFound,
+            Self::Validation(..)
+            | Self::InvalidInput(_)
+            | Self::DependencyCycle
+            | Self::SubtaskCycle
+            | Self::InvalidWorkflowState
+            | Self::InvalidRecurrenceRule(_)
+            | Self::InvalidCustomFieldValue(_)
+            | Self::AttachmentTooLarge(_)
+            | Self::InvalidTaskPosition => PlexoErrorCode::Validation,
+            Self::Conflict(_)
+            | Self::EmailAlreadyInUse
+            | Self::EmailAlreadyExists
+            | Self::TaskBlocked
+            | Self::TransitionNotAllowed
+            | Self::TimerAlreadyRunning
+            | Self::NoRunningTimer
+            | Self::AttachmentQuotaExceeded => PlexoErrorCode::Conflict,
+            Self::Database(e) => PlexoErrorCode::of_database_error(e),
+            Self::Upstream(_) => PlexoErrorCode::Upstream,
+            Self::Internal(_) => PlexoErrorCode::Internal,
+        }
+    }
+}
+
+impl From<sqlx::Error> for PlexoAppError {
+    fn from(error: sqlx::Error) -> Self {
+        Self::Database(Arc::new(error))
+    }
+}
+
+/// Data loaders share their errors between the keys of a batch.
+impl From<Arc<sqlx::Error>> for PlexoAppError {
+    fn from(error: Arc<sqlx::Error>) -> Self {
+        Self::Database(error)
+    }
+}
+
+impl From<std::io::Error> for PlexoAppError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Internal(error.to_string())
+    }
+}
+
+impl From<serde_json::Error> for PlexoAppError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Internal(error.to_string())
+    }
+}
+
+impl From<uuid::Error> for PlexoAppError {
+    fn from(error: uuid::Error) -> Self {
+        Self::Internal(error.to_string())
+    }
+}
+
+impl From<async_openai::error::OpenAIError> for PlexoAppError {
+    fn from(error: async_openai::error::OpenAIError) -> Self {
+        Self::Upstream(error.to_string())
+    }
+}
+
+/// REST errors share the shape of GraphQL ones: `{ "error": message, "code": code }`.
+impl poem::error::ResponseError for PlexoAppError {
+    fn status(&self) -> StatusCode {
+        self.code().status()
+    }
+
+    fn as_response(&self) -> Response {
+        let code = self.code();
+
+        Response::builder()
+            .status(code.status())
+            .content_type("application/json")
+            .body(json!({ "error": self.to_string(), "code": code.as_str() }).to_string())
+    }
+}
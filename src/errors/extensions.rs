@@ -0,0 +1,153 @@
+use async_graphql::{Error, ErrorExtensions};
+
+use super::definitions::{PlexoAppError, PlexoErrorCode};
+
+/// Result of GraphQL resolvers. Errors converted with `?` keep the `extensions` of the
+/// [`PlexoAppError`] they come from, which async-graphql's own `Result` drops.
+pub type Result<T, E = PlexoGraphQLError> = std::result::Result<T, E>;
+
+/// A GraphQL error, built from a [`PlexoAppError`] or passed through from async-graphql.
+#[derive(Debug, Clone)]
+pub struct PlexoGraphQLError(Error);
+
+impl<E: Into<PlexoAppError>> From<E> for PlexoGraphQLError {
+    fn from(error: E) -> Self {
+        Self(error.into().extend())
+    }
+}
+
+impl From<Error> for PlexoGraphQLError {
+    fn from(error: Error) -> Self {
+        Self(error)
+    }
+}
+
+impl From<PlexoGraphQLError> for Error {
+    fn from(error: PlexoGraphQLError) -> Self {
+        error.0
+    }
+}
+
+/// Sets the stable `code` of the error in its `extensions`, along with the fields it's about.
+/// Database details are logged and replaced by a generic message.
+impl ErrorExtensions for PlexoAppError {
+    fn extend(&self) -> Error {
+        let code = self.code();
+
+        let message = match self {
+            PlexoAppError::Database(e) => {
+                if code == PlexoErrorCode::Database {
+                    println!("Database error: {:?}", e);
+                }
+
+                match code {
+                    PlexoErrorCode::NotFound => "Not found",
+                    PlexoErrorCode::Conflict => "Resource already exists",
+                    PlexoErrorCode::Validation => {
+                        "Referenced resource doesn't exist or isn't valid"
+                    }
+                    _ => "Database error",
+                }
+                .to_string()
+            }
+            PlexoAppError::Internal(detail) => {
+                println!("Internal error: {}", detail);
+
+                self.to_string()
+            }
+            _ => self.to_string(),
+        };
+
+        Error::new(message).extend_with(|_, extensions| {
+            extensions.set("code", code.as_str());
+
+            match self {
+                PlexoAppError::NotFound(resource, id) => {
+                    extensions.set("resource", *resource);
+                    extensions.set("id", id.as_str());
+                }
+                PlexoAppError::Validation(field, _) => {
+                    extensions.set("field", field.as_str());
+                }
+                PlexoAppError::InvalidInput(errors) => {
+                    if let Ok(fields) = async_graphql::to_value(errors) {
+                        extensions.set("fields", fields);
+                    }
+                }
+                _ => {}
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_graphql::{Request, Value};
+    use chrono::Utc;
+    use sqlx::postgres::PgPoolOptions;
+    use uuid::Uuid;
+
+    use crate::{
+        auth::{core::PlexoAuthToken, engine::AuthEngine},
+        config::DATABASE_URL,
+        sdk::member::{Member, MemberRole},
+        system::{core::Engine, schema::GraphQLSchema},
+    };
+
+    use super::*;
+
+    #[test]
+    fn extends_errors_with_their_code_and_fields() {
+        let error = PlexoAppError::not_found("Task", "42").extend();
+        let extensions = error.extensions.unwrap();
+
+        assert_eq!(error.message, "Task 42 not found");
+        assert_eq!(extensions.get("code"), Some(&Value::from("NOT_FOUND")));
+        assert_eq!(extensions.get("resource"), Some(&Value::from("Task")));
+        assert_eq!(extensions.get("id"), Some(&Value::from("42")));
+
+        let error: Error = PlexoGraphQLError::from(sqlx::Error::RowNotFound).into();
+        let extensions = error.extensions.unwrap();
+
+        assert_eq!(error.message, "Not found");
+        assert_eq!(extensions.get("code"), Some(&Value::from("NOT_FOUND")));
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a database at DATABASE_URL"]
+    async fn unknown_task_is_not_found() {
+        dotenvy::dotenv().ok();
+
+        let pool = PgPoolOptions::new().connect(&DATABASE_URL).await.unwrap();
+        let auth = AuthEngine::new("secret".into(), "secret".into(), None, None, None);
+        let engine = Engine::new(pool, auth);
+
+        let member = Member {
+            id: Uuid::new_v4(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            name: "Test".into(),
+            email: "test@plexo.app".into(),
+            github_id: None,
+            google_id: None,
+            photo_url: None,
+            role: MemberRole::Member,
+            password_hash: None,
+        };
+        let token = engine
+            .auth
+            .jwt_engine
+            .create_session_token(&member)
+            .unwrap();
+
+        let query = format!(r#"{{ taskById(id: "{}") {{ id }} }}"#, Uuid::new_v4());
+        let response = engine
+            .graphql_api_schema()
+            .execute(Request::new(query).data(PlexoAuthToken(token)))
+            .await;
+
+        let extensions = response.errors[0].extensions.as_ref().unwrap();
+
+        assert_eq!(extensions.get("code"), Some(&Value::from("NOT_FOUND")));
+    }
+}
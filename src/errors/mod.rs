@@ -1 +1,2 @@
 pub mod definitions;
+pub mod extensions;
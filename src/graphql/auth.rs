@@ -1,20 +1,27 @@
-use async_graphql::{Context, Result};
+use async_graphql::Context;
 use uuid::Uuid;
 
-use crate::{auth::core::PlexoAuthToken, errors::definitions::PlexoAppError, system::core::Engine};
+use crate::{
+    auth::core::PlexoAuthToken,
+    errors::{definitions::PlexoAppError, extensions::Result},
+    system::core::Engine,
+};
 
 pub fn extract_context(ctx: &Context<'_>) -> Result<(Engine, Uuid)> {
     let Ok(auth_token) = &ctx.data::<PlexoAuthToken>() else {
-        return Err(PlexoAppError:: MissingAuthorizationToken.into());
+        return Err(PlexoAppError::MissingAuthorizationToken.into());
     };
 
     let plexo_engine = ctx.data::<Engine>()?.to_owned();
 
-    let Ok(claims) = plexo_engine.auth.extract_claims(auth_token) else {
-        return Err(PlexoAppError:: InvalidAuthorizationToken.into());
+    let Some(member_id) = plexo_engine
+        .auth
+        .extract_claims(auth_token)
+        .ok()
+        .and_then(|claims| claims.member_id())
+    else {
+        return Err(PlexoAppError::InvalidAuthorizationToken.into());
     };
 
-    let member_id = claims.member_id();
-
     Ok((plexo_engine, member_id))
 }
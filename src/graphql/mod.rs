@@ -2,3 +2,4 @@ pub mod auth;
 pub mod mutations;
 pub mod queries;
 pub mod subscription;
+pub mod validation;
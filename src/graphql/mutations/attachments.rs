@@ -0,0 +1,212 @@
+use async_graphql::{dataloader::DataLoader, Context, Object, Upload};
+use mime::Mime;
+use tokio::io::AsyncReadExt;
+use uuid::Uuid;
+
+use crate::{
+    errors::{definitions::PlexoAppError, extensions::Result},
+    graphql::{auth::extract_context, validation::InputValidator},
+    sdk::{
+        activity::{ActivityOperationType, ActivityResourceType},
+        attachment::{Attachment, AttachmentLimits},
+        loaders::{AttachmentLoader, TaskLoader},
+        member::MemberRole,
+    },
+    system::attachments::{attachment_limits, lock_attachment_quota},
+};
+
+#[derive(Default)]
+pub struct AttachmentsMutation;
+
+#[Object]
+impl AttachmentsMutation {
+    /// Attaches a file to a task, sent as a GraphQL multipart upload. Files over the size limit
+    /// are already refused while they are received.
+    async fn upload_attachment(
+        &self,
+        ctx: &Context<'_>,
+        task_id: Uuid,
+        file: Upload,
+    ) -> Result<Attachment> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        let task_loader = ctx.data::<DataLoader<TaskLoader>>()?;
+        task_loader
+            .load_one(task_id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("Task", task_id))?;
+
+        let upload = file.value(ctx)?;
+        let size = upload.size()? as i64;
+
+        let limits = plexo_engine.get_attachment_limits().await?;
+
+        if size > limits.max_attachment_size {
+            return Err(PlexoAppError::AttachmentTooLarge(limits.max_attachment_size).into());
+        }
+
+        // Checked again with the insert, this only spares storing files that can't fit.
+        if limits.used_size + size > limits.max_attachments_total_size {
+            return Err(PlexoAppError::AttachmentQuotaExceeded.into());
+        }
+
+        let filename = upload.filename.clone();
+        let content_type = upload
+            .content_type
+            .as_deref()
+            .and_then(|content_type| content_type.parse::<Mime>().ok())
+            .unwrap_or(mime::APPLICATION_OCTET_STREAM)
+            .to_string();
+
+        let id = Uuid::new_v4();
+        let storage_key = format!("tasks/{task_id}/{id}");
+
+        // Uploads are spooled to a temporary file, which is streamed to the storage.
+        let mut content = tokio::fs::File::from_std(upload.content).take(size as u64);
+
+        plexo_engine.storage.put(&storage_key, &mut content).await?;
+
+        let inserted: Result<()> = async {
+            let mut tx = plexo_engine.pool.begin().await?;
+
+            lock_attachment_quota(&mut tx).await?;
+
+            let limits = attachment_limits(&mut tx).await?;
+
+            if limits.used_size + size > limits.max_attachments_total_size {
+                return Err(PlexoAppError::AttachmentQuotaExceeded.into());
+            }
+
+            sqlx::query!(
+                r#"
+                INSERT INTO attachments (id, task_id, uploader_id, filename, content_type, size, storage_key)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                "#,
+                id,
+                task_id,
+                member_id,
+                filename,
+                content_type,
+                size,
+                storage_key,
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            tx.commit().await?;
+
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = inserted {
+            plexo_engine.storage.delete(&storage_key).await.ok();
+            return Err(e);
+        }
+
+        plexo_engine
+            .record_activity(
+                ActivityOperationType::Create,
+                ActivityResourceType::Attachment,
+                id,
+                member_id,
+            )
+            .await;
+
+        let loader = ctx.data::<DataLoader<AttachmentLoader>>()?;
+
+        Ok(loader
+            .load_one(id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("Attachment", id))?)
+    }
+
+    /// Deletes an attachment. Only its uploader, the task owner and admins can do it.
+    async fn delete_attachment(&self, ctx: &Context<'_>, id: Uuid) -> Result<Attachment> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<AttachmentLoader>>()?;
+        let attachment = loader
+            .load_one(id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("Attachment", id))?;
+
+        let task_loader = ctx.data::<DataLoader<TaskLoader>>()?;
+        let task = task_loader.load_one(attachment.task_id).await?;
+
+        let is_allowed = attachment.uploader_id == member_id
+            || task.is_some_and(|task| task.owner_id == member_id)
+            || matches!(
+                plexo_engine.get_member_by_id(member_id).await,
+                Some(member) if member.role == MemberRole::Admin
+            );
+
+        if !is_allowed {
+            return Err(PlexoAppError::Forbidden.into());
+        }
+
+        sqlx::query!(
+            r#"
+            DELETE FROM attachments
+            WHERE id = $1
+            "#,
+            id,
+        )
+        .execute(&*plexo_engine.pool)
+        .await?;
+
+        if let Err(e) = plexo_engine.storage.delete(&attachment.storage_key).await {
+            println!("Failed to delete attachment {}: {:?}", id, e);
+        }
+
+        plexo_engine
+            .record_activity(
+                ActivityOperationType::Delete,
+                ActivityResourceType::Attachment,
+                id,
+                member_id,
+            )
+            .await;
+
+        Ok(attachment)
+    }
+
+    /// Sets the organization attachment limits, in bytes. Empty limits fall back to the
+    /// server defaults.
+    async fn set_attachment_limits(
+        &self,
+        ctx: &Context<'_>,
+        max_attachment_size: Option<i64>,
+        max_attachments_total_size: Option<i64>,
+    ) -> Result<AttachmentLimits> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        match plexo_engine.get_member_by_id(member_id).await {
+            Some(member) if member.role == MemberRole::Admin => {}
+            _ => return Err(PlexoAppError::Forbidden.into()),
+        }
+
+        let mut validator = InputValidator::new();
+
+        validator
+            .non_negative("maxAttachmentSize", max_attachment_size)
+            .non_negative("maxAttachmentsTotalSize", max_attachments_total_size);
+
+        validator.finish()?;
+
+        sqlx::query!(
+            r#"
+            UPDATE self
+            SET
+                max_attachment_size = $1,
+                max_attachments_total_size = $2
+            "#,
+            max_attachment_size,
+            max_attachments_total_size,
+        )
+        .execute(&*plexo_engine.pool)
+        .await?;
+
+        Ok(plexo_engine.get_attachment_limits().await?)
+    }
+}
@@ -1,7 +1,12 @@
-use async_graphql::{Context, Object, Result, SimpleObject};
+use async_graphql::{Context, Object, SimpleObject};
 
 use crate::{
-    errors::definitions::PlexoAppError, graphql::auth::extract_context, system::core::Engine,
+    errors::{definitions::PlexoAppError, extensions::Result},
+    graphql::{
+        auth::extract_context,
+        validation::{InputValidator, NAME_MAX_LENGTH},
+    },
+    system::core::Engine,
 };
 
 #[derive(Default)]
@@ -38,9 +43,11 @@ impl AuthMutation {
             return Err(PlexoAppError::InvalidPassword.into());
         };
 
-        let Ok(session_token) = plexo_engine.auth.jwt_engine.create_session_token(&member) else {
-            return Err(PlexoAppError::InvalidPassword.into());
-        };
+        let session_token = plexo_engine
+            .auth
+            .jwt_engine
+            .create_session_token(&member)
+            .map_err(|e| PlexoAppError::Internal(e.to_string()))?;
 
         Ok(LoginResponse {
             token: session_token,
@@ -57,22 +64,33 @@ impl AuthMutation {
     ) -> Result<LoginResponse> {
         let (plexo_engine, _member_id) = extract_context(ctx)?;
 
+        let mut validator = InputValidator::new();
+
+        validator
+            .email("email", Some(&email))
+            .required_text("name", &name, NAME_MAX_LENGTH)
+            .required_text("password", &password, NAME_MAX_LENGTH);
+
+        validator.finish()?;
+
         if (plexo_engine.get_member_by_email(email.clone()).await).is_some() {
             return Err(PlexoAppError::EmailAlreadyExists.into());
         };
 
-        let password_hash = plexo_engine.auth.hash_password(password.as_str());
+        let password_hash = plexo_engine
+            .auth
+            .hash_password(password.as_str())
+            .map_err(|e| PlexoAppError::Internal(e.to_string()))?;
 
-        let Some(member) = plexo_engine
+        let member = plexo_engine
             .create_member_from_email(email.clone(), name.clone(), password_hash)
-            .await
-        else {
-            return Err(PlexoAppError::EmailAlreadyExists.into());
-        };
+            .await?;
 
-        let Ok(session_token) = plexo_engine.auth.jwt_engine.create_session_token(&member) else {
-            return Err(PlexoAppError::InvalidPassword.into());
-        };
+        let session_token = plexo_engine
+            .auth
+            .jwt_engine
+            .create_session_token(&member)
+            .map_err(|e| PlexoAppError::Internal(e.to_string()))?;
 
         Ok(LoginResponse {
             token: session_token,
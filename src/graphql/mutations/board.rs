@@ -0,0 +1,187 @@
+use async_graphql::{dataloader::DataLoader, Context, MaybeUndefined, Object};
+use uuid::Uuid;
+
+use super::workflows::resolve_task_transition;
+use crate::{
+    errors::{definitions::PlexoAppError, extensions::Result},
+    graphql::{
+        auth::extract_context,
+        validation::{InputValidator, Reference},
+    },
+    sdk::{
+        activity::{ActivityOperationType, ActivityResourceType},
+        loaders::TaskLoader,
+        task::{Task, TaskStatus},
+    },
+    system::{
+        ranks::rank_in_column,
+        subtasks::{is_in_subtree, lock_task_tree},
+    },
+};
+
+#[derive(Default)]
+pub struct BoardMutation;
+
+#[Object]
+impl BoardMutation {
+    /// Moves a task to a board column and position at once. The task goes right after
+    /// `afterId` and right before `beforeId`, which must be next to each other in the column of
+    /// the task's project and workflow state. Without either, the task goes last, unless the call only
+    /// changes its parent.
+    async fn move_task(
+        &self,
+        ctx: &Context<'_>,
+        id: Uuid,
+        status: Option<TaskStatus>,
+        #[graphql(desc = "Workflow state to move the task to, takes precedence over `status`")]
+        state_id: Option<Uuid>,
+        before_id: Option<Uuid>,
+        after_id: Option<Uuid>,
+        #[graphql(desc = "Allows starting or finishing a task that still has open blockers")]
+        force: Option<bool>,
+        #[graphql(desc = "New parent of the task, null makes it a top level task")]
+        parent_id: MaybeUndefined<Uuid>,
+    ) -> Result<Task> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        let parent_id = match parent_id {
+            MaybeUndefined::Undefined => None,
+            MaybeUndefined::Null => Some(None),
+            MaybeUndefined::Value(parent_id) => Some(Some(parent_id)),
+        };
+
+        let mut validator = InputValidator::new();
+
+        validator
+            .exists(
+                &plexo_engine,
+                "parentId",
+                Reference::Task,
+                parent_id.flatten(),
+            )
+            .await?;
+
+        validator.finish()?;
+
+        let reposition = parent_id.is_none()
+            || status.is_some()
+            || state_id.is_some()
+            || before_id.is_some()
+            || after_id.is_some();
+
+        let current = sqlx::query!(
+            r#"
+            SELECT project_id, state_id FROM tasks
+            WHERE id = $1 AND deleted_at IS NULL
+            "#,
+            id,
+        )
+        .fetch_optional(&*plexo_engine.pool)
+        .await?
+        .ok_or_else(|| PlexoAppError::not_found("Task", id))?;
+
+        let next_state_id = resolve_task_transition(
+            &mut *plexo_engine.pool.acquire().await?,
+            id,
+            current.project_id,
+            current.state_id,
+            current.project_id,
+            state_id,
+            status.map(|s| s.to_str()),
+            force.unwrap_or(false),
+        )
+        .await?;
+
+        // The status is derived from the workflow state by the database when one is set.
+        let status = match next_state_id {
+            Some(_) => None,
+            None => status.map(|s| s.to_str()),
+        };
+
+        let mut tx = plexo_engine.pool.begin().await?;
+
+        if let Some(parent_id) = parent_id {
+            lock_task_tree(&mut tx).await?;
+
+            if let Some(parent_id) = parent_id {
+                if is_in_subtree(&mut tx, id, parent_id).await? {
+                    return Err(PlexoAppError::SubtaskCycle.into());
+                }
+            }
+
+            sqlx::query!(
+                r#"
+                UPDATE tasks SET parent_id = $2
+                WHERE id = $1
+                "#,
+                id,
+                parent_id,
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        let moved = sqlx::query!(
+            r#"
+            UPDATE tasks
+            SET status = COALESCE($2, status), state_id = COALESCE($3, state_id)
+            WHERE id = $1
+            RETURNING project_id, state_id
+            "#,
+            id,
+            status,
+            next_state_id,
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if reposition {
+            let rank = rank_in_column(
+                &mut tx,
+                id,
+                moved.project_id,
+                moved.state_id,
+                before_id,
+                after_id,
+            )
+            .await?
+            .ok_or(PlexoAppError::InvalidTaskPosition)?;
+
+            sqlx::query!(
+                r#"
+                UPDATE tasks SET rank = $2
+                WHERE id = $1
+                "#,
+                id,
+                rank,
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        let loader = ctx.data::<DataLoader<TaskLoader>>()?;
+        let task = loader
+            .load_one(id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("Task", id))?;
+
+        plexo_engine
+            .subscription_manager
+            .send_task_event(task.clone())
+            .await
+            .ok();
+
+        plexo_engine
+            .record_activity(
+                ActivityOperationType::Update,
+                ActivityResourceType::Task,
+                task.id,
+                member_id,
+            )
+            .await;
+
+        Ok(task)
+    }
+}
@@ -0,0 +1,354 @@
+use async_graphql::{dataloader::DataLoader, Context, InputObject, Object, SimpleObject};
+use chrono::{DateTime, Utc};
+use sqlx::{PgConnection, Postgres, QueryBuilder};
+use uuid::Uuid;
+
+use super::workflows::resolve_task_transition;
+use crate::{
+    errors::{definitions::PlexoAppError, extensions::Result},
+    graphql::{
+        auth::extract_context,
+        queries::resources::TaskFilter,
+        validation::{InputValidator, Reference},
+    },
+    sdk::{
+        activity::{ActivityOperationType, ActivityResourceType},
+        loaders::TaskLoader,
+        task::{TaskPriority, TaskStatus},
+        utilities::DateTimeBridge,
+    },
+};
+
+#[derive(Default)]
+pub struct BulkMutation;
+
+/// Changes applied to every selected task. Unset fields are left as they are.
+#[derive(InputObject, Default)]
+pub struct BulkTaskPatch {
+    pub status: Option<TaskStatus>,
+    pub priority: Option<TaskPriority>,
+    pub lead_id: Option<Uuid>,
+    pub project_id: Option<Uuid>,
+    pub due_date: Option<DateTime<Utc>>,
+    pub add_labels: Option<Vec<Uuid>>,
+    pub remove_labels: Option<Vec<Uuid>>,
+    pub add_assignees: Option<Vec<Uuid>>,
+    pub remove_assignees: Option<Vec<Uuid>>,
+}
+
+#[derive(SimpleObject)]
+pub struct BulkTaskResult {
+    pub count: i64,
+    pub ids: Vec<Uuid>,
+}
+
+/// Locks and returns the live tasks picked by either `ids` or `filter`.
+async fn select_tasks(
+    conn: &mut PgConnection,
+    ids: Option<Vec<Uuid>>,
+    filter: Option<TaskFilter>,
+) -> Result<Vec<(Uuid, Option<Uuid>, Option<Uuid>)>> {
+    let mut query: QueryBuilder<'_, Postgres> = QueryBuilder::new(
+        "SELECT id, project_id, state_id FROM tasks WHERE deleted_at IS NULL AND ",
+    );
+
+    match (ids, filter) {
+        (Some(ids), None) => {
+            query.push("id = ANY(").push_bind(ids).push(")");
+        }
+        (None, Some(filter)) => filter.push_sql(&mut query),
+        _ => {
+            return Err(
+                PlexoAppError::validation("ids", "Either ids or filter must be given").into(),
+            )
+        }
+    }
+
+    query.push(" ORDER BY created_at, id FOR UPDATE");
+
+    Ok(query.build_query_as().fetch_all(&mut *conn).await?)
+}
+
+#[Object]
+impl BulkMutation {
+    /// Applies a patch to the tasks picked by `ids` or `filter` in a single transaction.
+    async fn bulk_update_tasks(
+        &self,
+        ctx: &Context<'_>,
+        ids: Option<Vec<Uuid>>,
+        filter: Option<TaskFilter>,
+        patch: BulkTaskPatch,
+        #[graphql(desc = "Allows starting or finishing tasks that still have open blockers")]
+        force: Option<bool>,
+    ) -> Result<BulkTaskResult> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        let mut validator = InputValidator::new();
+
+        validator
+            .exists(
+                &plexo_engine,
+                "patch.leadId",
+                Reference::Member,
+                patch.lead_id,
+            )
+            .await?;
+        validator
+            .exists(
+                &plexo_engine,
+                "patch.projectId",
+                Reference::Project,
+                patch.project_id,
+            )
+            .await?;
+        validator
+            .exists(
+                &plexo_engine,
+                "patch.addLabels",
+                Reference::Label,
+                patch.add_labels.iter().flatten().copied(),
+            )
+            .await?;
+        validator
+            .exists(
+                &plexo_engine,
+                "patch.addAssignees",
+                Reference::Member,
+                patch.add_assignees.iter().flatten().copied(),
+            )
+            .await?;
+
+        validator.finish()?;
+
+        let mut tx = plexo_engine.pool.begin().await?;
+
+        let tasks = select_tasks(&mut tx, ids, filter).await?;
+        let ids: Vec<Uuid> = tasks.iter().map(|(id, _, _)| *id).collect();
+
+        if let Some(status) = patch.status {
+            for (id, project_id, state_id) in &tasks {
+                resolve_task_transition(
+                    &mut tx,
+                    *id,
+                    *project_id,
+                    *state_id,
+                    patch.project_id.or(*project_id),
+                    None,
+                    Some(status.to_str()),
+                    force.unwrap_or(false),
+                )
+                .await?;
+            }
+        }
+
+        // The workflow state follows the status through the database.
+        sqlx::query!(
+            r#"
+            UPDATE tasks
+            SET
+                status = COALESCE($2, status),
+                priority = COALESCE($3, priority),
+                lead_id = COALESCE($4, lead_id),
+                project_id = COALESCE($5, project_id),
+                due_date = COALESCE($6, due_date)
+            WHERE id = ANY($1)
+            "#,
+            &ids,
+            patch.status.map(|s| s.to_str()),
+            patch.priority.map(|p| p.to_str()),
+            patch.lead_id,
+            patch.project_id,
+            patch.due_date.map(DateTimeBridge::from_date_time),
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if let Some(project_id) = patch.project_id {
+            // Fields of the previous projects don't apply anymore.
+            sqlx::query!(
+                r#"
+                DELETE FROM task_custom_field_values
+                USING custom_fields
+                WHERE custom_fields.id = task_custom_field_values.field_id
+                    AND task_custom_field_values.task_id = ANY($1)
+                    AND custom_fields.project_id IS NOT NULL
+                    AND custom_fields.project_id <> $2
+                "#,
+                &ids,
+                project_id,
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        if let Some(labels) = patch.remove_labels {
+            sqlx::query!(
+                r#"
+                DELETE FROM labels_by_tasks
+                WHERE task_id = ANY($1) AND label_id = ANY($2)
+                "#,
+                &ids,
+                &labels,
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        if let Some(labels) = patch.add_labels {
+            sqlx::query!(
+                r#"
+                INSERT INTO labels_by_tasks (task_id, label_id)
+                SELECT task_id, label_id
+                FROM unnest($1::uuid[]) AS task_id, unnest($2::uuid[]) AS label_id
+                ON CONFLICT DO NOTHING
+                "#,
+                &ids,
+                &labels,
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        if let Some(assignees) = patch.remove_assignees {
+            sqlx::query!(
+                r#"
+                DELETE FROM tasks_by_assignees
+                WHERE task_id = ANY($1) AND assignee_id = ANY($2)
+                "#,
+                &ids,
+                &assignees,
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        if let Some(assignees) = patch.add_assignees {
+            sqlx::query!(
+                r#"
+                INSERT INTO tasks_by_assignees (task_id, assignee_id)
+                SELECT task_id, assignee_id
+                FROM unnest($1::uuid[]) AS task_id, unnest($2::uuid[]) AS assignee_id
+                ON CONFLICT DO NOTHING
+                "#,
+                &ids,
+                &assignees,
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        let loader = ctx.data::<DataLoader<TaskLoader>>()?;
+        let updated = loader.load_many(ids.clone()).await?;
+
+        for id in &ids {
+            if let Some(task) = updated.get(id) {
+                plexo_engine
+                    .subscription_manager
+                    .send_task_event(task.clone())
+                    .await
+                    .ok();
+            }
+        }
+
+        plexo_engine
+            .record_activities(
+                ActivityOperationType::Update,
+                ActivityResourceType::Task,
+                &ids,
+                member_id,
+            )
+            .await;
+
+        Ok(BulkTaskResult {
+            count: ids.len() as i64,
+            ids,
+        })
+    }
+
+    /// Moves the tasks picked by `ids` or `filter` to the trash, with their subtasks, in a
+    /// single transaction. The returned ids include the subtasks.
+    async fn bulk_delete_tasks(
+        &self,
+        ctx: &Context<'_>,
+        ids: Option<Vec<Uuid>>,
+        filter: Option<TaskFilter>,
+    ) -> Result<BulkTaskResult> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        let mut tx = plexo_engine.pool.begin().await?;
+
+        let selected: Vec<Uuid> = select_tasks(&mut tx, ids, filter)
+            .await?
+            .into_iter()
+            .map(|(id, _, _)| id)
+            .collect();
+
+        let tree: Vec<Uuid> = sqlx::query!(
+            r#"
+            WITH RECURSIVE tree AS (
+                SELECT id FROM tasks WHERE id = ANY($1) AND deleted_at IS NULL
+                UNION
+                SELECT tasks.id FROM tasks
+                JOIN tree ON tasks.parent_id = tree.id
+                WHERE tasks.deleted_at IS NULL
+            )
+            SELECT id AS "id!" FROM tree
+            "#,
+            &selected,
+        )
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .map(|r| r.id)
+        .collect();
+
+        // Trashed tasks can't be loaded anymore, so the events are built from them beforehand.
+        let loader = ctx.data::<DataLoader<TaskLoader>>()?;
+        let deleted_tasks = loader.load_many(tree.clone()).await?;
+
+        // Everything shares the transaction's NOW(), so subtasks are restored with their parent.
+        let deleted: Vec<Uuid> = sqlx::query!(
+            r#"
+            UPDATE tasks
+            SET deleted_at = NOW(), deleted_by = $2
+            WHERE id = ANY($1) AND deleted_at IS NULL
+            RETURNING id
+            "#,
+            &tree,
+            member_id,
+        )
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .map(|r| r.id)
+        .collect();
+
+        tx.commit().await?;
+
+        for id in &deleted {
+            if let Some(task) = deleted_tasks.get(id) {
+                plexo_engine
+                    .subscription_manager
+                    .send_task_event(task.clone())
+                    .await
+                    .ok();
+            }
+        }
+
+        plexo_engine
+            .record_activities(
+                ActivityOperationType::Delete,
+                ActivityResourceType::Task,
+                &deleted,
+                member_id,
+            )
+            .await;
+
+        Ok(BulkTaskResult {
+            count: deleted.len() as i64,
+            ids: deleted,
+        })
+    }
+}
@@ -0,0 +1,202 @@
+use async_graphql::{Context, Object};
+use uuid::Uuid;
+
+use crate::{
+    errors::{definitions::PlexoAppError, extensions::Result},
+    graphql::{
+        auth::extract_context,
+        validation::{InputValidator, Reference, DESCRIPTION_MAX_LENGTH},
+    },
+    sdk::{
+        activity::{ActivityOperationType, ActivityResourceType},
+        comment::Comment,
+        member::MemberRole,
+        utilities::DateTimeBridge,
+    },
+    system::core::Engine,
+};
+
+#[derive(Default)]
+pub struct CommentsMutation;
+
+/// Only the author of a comment or an admin can change it.
+async fn ensure_comment_author_or_admin(
+    plexo_engine: &Engine,
+    comment_id: Uuid,
+    member_id: Uuid,
+) -> Result<()> {
+    let comment = sqlx::query!(
+        r#"
+        SELECT author_id FROM comments
+        WHERE id = $1
+        "#,
+        comment_id,
+    )
+    .fetch_one(&*plexo_engine.pool)
+    .await?;
+
+    if comment.author_id == member_id {
+        return Ok(());
+    }
+
+    match plexo_engine.get_member_by_id(member_id).await {
+        Some(member) if member.role == MemberRole::Admin => Ok(()),
+        _ => Err(PlexoAppError::Forbidden.into()),
+    }
+}
+
+#[Object]
+impl CommentsMutation {
+    async fn create_comment(
+        &self,
+        ctx: &Context<'_>,
+        task_id: Uuid,
+        body: String,
+        parent_id: Option<Uuid>,
+    ) -> Result<Comment> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        let mut validator = InputValidator::new();
+
+        validator.required_text("body", &body, DESCRIPTION_MAX_LENGTH);
+        validator
+            .exists(&plexo_engine, "taskId", Reference::Task, Some(task_id))
+            .await?;
+
+        validator.finish()?;
+
+        if let Some(parent_id) = parent_id {
+            let parent = sqlx::query!(
+                r#"
+                SELECT task_id FROM comments
+                WHERE id = $1
+                "#,
+                parent_id,
+            )
+            .fetch_one(&*plexo_engine.pool)
+            .await?;
+
+            if parent.task_id != task_id {
+                return Err(
+                    PlexoAppError::validation("parentId", "Belongs to another task").into(),
+                );
+            }
+        }
+
+        let comment = sqlx::query!(
+            r#"
+            INSERT INTO comments (task_id, author_id, parent_id, body)
+            VALUES ($1, $2, $3, $4)
+            RETURNING *
+            "#,
+            task_id,
+            member_id,
+            parent_id,
+            body,
+        )
+        .fetch_one(&*plexo_engine.pool)
+        .await?;
+
+        plexo_engine
+            .record_activity(
+                ActivityOperationType::Create,
+                ActivityResourceType::Comment,
+                comment.id,
+                member_id,
+            )
+            .await;
+
+        Ok(Comment {
+            id: comment.id,
+            created_at: DateTimeBridge::from_offset_date_time(comment.created_at),
+            updated_at: DateTimeBridge::from_offset_date_time(comment.updated_at),
+            task_id: comment.task_id,
+            author_id: comment.author_id,
+            parent_id: comment.parent_id,
+            body: comment.body,
+            edited_at: comment.edited_at.map(DateTimeBridge::from_offset_date_time),
+        })
+    }
+
+    async fn update_comment(&self, ctx: &Context<'_>, id: Uuid, body: String) -> Result<Comment> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        let mut validator = InputValidator::new();
+
+        validator.required_text("body", &body, DESCRIPTION_MAX_LENGTH);
+        validator.finish()?;
+
+        ensure_comment_author_or_admin(&plexo_engine, id, member_id).await?;
+
+        let comment = sqlx::query!(
+            r#"
+            UPDATE comments
+            SET body = $1, edited_at = NOW()
+            WHERE id = $2
+            RETURNING *
+            "#,
+            body,
+            id,
+        )
+        .fetch_one(&*plexo_engine.pool)
+        .await?;
+
+        plexo_engine
+            .record_activity(
+                ActivityOperationType::Update,
+                ActivityResourceType::Comment,
+                comment.id,
+                member_id,
+            )
+            .await;
+
+        Ok(Comment {
+            id: comment.id,
+            created_at: DateTimeBridge::from_offset_date_time(comment.created_at),
+            updated_at: DateTimeBridge::from_offset_date_time(comment.updated_at),
+            task_id: comment.task_id,
+            author_id: comment.author_id,
+            parent_id: comment.parent_id,
+            body: comment.body,
+            edited_at: comment.edited_at.map(DateTimeBridge::from_offset_date_time),
+        })
+    }
+
+    async fn delete_comment(&self, ctx: &Context<'_>, id: Uuid) -> Result<Comment> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        ensure_comment_author_or_admin(&plexo_engine, id, member_id).await?;
+
+        // Replies are removed along with their parent by the foreign key cascade.
+        let comment = sqlx::query!(
+            r#"
+            DELETE FROM comments
+            WHERE id = $1
+            RETURNING *
+            "#,
+            id,
+        )
+        .fetch_one(&*plexo_engine.pool)
+        .await?;
+
+        plexo_engine
+            .record_activity(
+                ActivityOperationType::Delete,
+                ActivityResourceType::Comment,
+                comment.id,
+                member_id,
+            )
+            .await;
+
+        Ok(Comment {
+            id: comment.id,
+            created_at: DateTimeBridge::from_offset_date_time(comment.created_at),
+            updated_at: DateTimeBridge::from_offset_date_time(comment.updated_at),
+            task_id: comment.task_id,
+            author_id: comment.author_id,
+            parent_id: comment.parent_id,
+            body: comment.body,
+            edited_at: comment.edited_at.map(DateTimeBridge::from_offset_date_time),
+        })
+    }
+}
@@ -0,0 +1,375 @@
+use std::collections::HashSet;
+
+use async_graphql::{dataloader::DataLoader, Context, Object};
+use serde_json::Value;
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use crate::{
+    errors::{definitions::PlexoAppError, extensions::Result},
+    graphql::{
+        auth::extract_context,
+        validation::{InputValidator, DESCRIPTION_MAX_LENGTH, NAME_MAX_LENGTH},
+    },
+    sdk::{
+        activity::{ActivityOperationType, ActivityResourceType},
+        custom_field::{CustomField, CustomFieldType, CustomFieldValueInput},
+        loaders::CustomFieldLoader,
+        member::MemberRole,
+    },
+    system::core::Engine,
+};
+
+#[derive(Default)]
+pub struct CustomFieldsMutation;
+
+/// Organization fields show up in every project, so only admins can change them.
+async fn ensure_can_edit_field(
+    plexo_engine: &Engine,
+    project_id: Option<Uuid>,
+    member_id: Uuid,
+) -> Result<()> {
+    if project_id.is_some() {
+        return Ok(());
+    }
+
+    match plexo_engine.get_member_by_id(member_id).await {
+        Some(member) if member.role == MemberRole::Admin => Ok(()),
+        _ => Err(PlexoAppError::Forbidden.into()),
+    }
+}
+
+fn normalize_options(field_type: CustomFieldType, options: Vec<String>) -> Result<Vec<String>> {
+    if !field_type.has_options() {
+        return Ok(vec![]);
+    }
+
+    let mut seen = HashSet::new();
+    let options: Vec<String> = options
+        .into_iter()
+        .map(|option| option.trim().to_string())
+        .filter(|option| !option.is_empty() && seen.insert(option.clone()))
+        .collect();
+
+    if options.is_empty() {
+        return Err(PlexoAppError::InvalidCustomFieldValue(
+            "select fields need at least one option".to_string(),
+        )
+        .into());
+    }
+
+    Ok(options)
+}
+
+/// Checks custom field values for a task in `project_id` and returns them in their stored form,
+/// `None` meaning the value is cleared.
+pub async fn validate_custom_field_values(
+    ctx: &Context<'_>,
+    plexo_engine: &Engine,
+    project_id: Option<Uuid>,
+    values: Vec<CustomFieldValueInput>,
+) -> Result<Vec<(Uuid, Option<Value>)>> {
+    if values.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let loader = ctx.data::<DataLoader<CustomFieldLoader>>()?;
+
+    let fields = loader
+        .load_many(values.iter().map(|v| v.field_id).collect::<Vec<Uuid>>())
+        .await?;
+
+    let mut normalized = Vec::with_capacity(values.len());
+    let mut member_ids = vec![];
+
+    for input in values {
+        let field = fields
+            .get(&input.field_id)
+            .ok_or_else(|| PlexoAppError::not_found("CustomField", input.field_id))?;
+
+        if field.project_id.is_some() && field.project_id != project_id {
+            return Err(PlexoAppError::InvalidCustomFieldValue(format!(
+                "{} belongs to another project",
+                field.name
+            ))
+            .into());
+        }
+
+        let value = match input.value.map(|v| v.0) {
+            None | Some(Value::Null) => None,
+            Some(value) => Some(field.normalize_value(value)?),
+        };
+
+        if let (CustomFieldType::Member, Some(Value::String(member_id))) =
+            (field.field_type, &value)
+        {
+            member_ids.push(member_id.parse::<Uuid>()?);
+        }
+
+        normalized.push((field.id, value));
+    }
+
+    if !member_ids.is_empty() {
+        let found = sqlx::query!(
+            r#"
+            SELECT COUNT(DISTINCT id) AS "count!" FROM members
+            WHERE id = ANY($1)
+            "#,
+            &member_ids,
+        )
+        .fetch_one(&*plexo_engine.pool)
+        .await?
+        .count;
+
+        if found as usize != member_ids.iter().collect::<HashSet<_>>().len() {
+            return Err(
+                PlexoAppError::InvalidCustomFieldValue("member not found".to_string()).into(),
+            );
+        }
+    }
+
+    Ok(normalized)
+}
+
+/// Stores values returned by [`validate_custom_field_values`] on a task.
+pub async fn save_custom_field_values(
+    conn: &mut PgConnection,
+    task_id: Uuid,
+    values: Vec<(Uuid, Option<Value>)>,
+) -> Result<()> {
+    for (field_id, value) in values {
+        match value {
+            Some(value) => {
+                sqlx::query!(
+                    r#"
+                    INSERT INTO task_custom_field_values (task_id, field_id, value)
+                    VALUES ($1, $2, $3)
+                    ON CONFLICT (task_id, field_id) DO UPDATE SET value = EXCLUDED.value
+                    "#,
+                    task_id,
+                    field_id,
+                    value,
+                )
+                .execute(&mut *conn)
+                .await?;
+            }
+            None => {
+                sqlx::query!(
+                    r#"
+                    DELETE FROM task_custom_field_values
+                    WHERE task_id = $1 AND field_id = $2
+                    "#,
+                    task_id,
+                    field_id,
+                )
+                .execute(&mut *conn)
+                .await?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn record_field_activity(plexo_engine: &Engine, project_id: Option<Uuid>, member_id: Uuid) {
+    if let Some(project_id) = project_id {
+        plexo_engine
+            .record_activity(
+                ActivityOperationType::Update,
+                ActivityResourceType::Project,
+                project_id,
+                member_id,
+            )
+            .await;
+    }
+}
+
+#[Object]
+impl CustomFieldsMutation {
+    /// Creates a custom field for a project, or for the whole organization when `projectId` is
+    /// empty.
+    async fn create_custom_field(
+        &self,
+        ctx: &Context<'_>,
+        project_id: Option<Uuid>,
+        name: String,
+        description: Option<String>,
+        field_type: CustomFieldType,
+        options: Option<Vec<String>>,
+        position: Option<i32>,
+    ) -> Result<CustomField> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        let mut validator = InputValidator::new();
+
+        validator
+            .required_text("name", &name, NAME_MAX_LENGTH)
+            .text(
+                "description",
+                description.as_deref(),
+                DESCRIPTION_MAX_LENGTH,
+            );
+
+        validator.finish()?;
+
+        ensure_can_edit_field(&plexo_engine, project_id, member_id).await?;
+
+        let options = normalize_options(field_type, options.unwrap_or_default())?;
+
+        let custom_field = sqlx::query!(
+            r#"
+            INSERT INTO custom_fields (project_id, name, description, field_type, options, position)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id
+            "#,
+            project_id,
+            name,
+            description,
+            field_type.to_str(),
+            &options,
+            position.unwrap_or_default(),
+        )
+        .fetch_one(&*plexo_engine.pool)
+        .await?;
+
+        record_field_activity(&plexo_engine, project_id, member_id).await;
+
+        let loader = ctx.data::<DataLoader<CustomFieldLoader>>()?;
+
+        Ok(loader
+            .load_one(custom_field.id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("CustomField", custom_field.id))?)
+    }
+
+    /// Updates a custom field. Removing select options also removes them from task values.
+    async fn update_custom_field(
+        &self,
+        ctx: &Context<'_>,
+        id: Uuid,
+        name: Option<String>,
+        description: Option<String>,
+        options: Option<Vec<String>>,
+        position: Option<i32>,
+    ) -> Result<CustomField> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        let mut validator = InputValidator::new();
+
+        validator
+            .optional_required_text("name", name.as_deref(), NAME_MAX_LENGTH)
+            .text(
+                "description",
+                description.as_deref(),
+                DESCRIPTION_MAX_LENGTH,
+            );
+
+        validator.finish()?;
+
+        let loader = ctx.data::<DataLoader<CustomFieldLoader>>()?;
+
+        let custom_field = loader
+            .load_one(id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("CustomField", id))?;
+
+        ensure_can_edit_field(&plexo_engine, custom_field.project_id, member_id).await?;
+
+        let options = match options {
+            Some(options) => Some(normalize_options(custom_field.field_type, options)?),
+            None => None,
+        };
+
+        let mut tx = plexo_engine.pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE custom_fields
+            SET
+                name = COALESCE($1, name),
+                description = COALESCE($2, description),
+                options = COALESCE($3, options),
+                position = COALESCE($4, position)
+            WHERE id = $5
+            "#,
+            name,
+            description,
+            options.as_deref(),
+            position,
+            id,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if let Some(options) = options.filter(|_| custom_field.field_type.has_options()) {
+            // Drop selections that are no longer options, and values left empty by it.
+            sqlx::query!(
+                r#"
+                UPDATE task_custom_field_values
+                SET value = (
+                    SELECT COALESCE(jsonb_agg(option), '[]'::jsonb)
+                    FROM jsonb_array_elements_text(value) AS option
+                    WHERE option = ANY($2)
+                )
+                WHERE field_id = $1 AND jsonb_typeof(value) = 'array'
+                "#,
+                id,
+                &options,
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query!(
+                r#"
+                DELETE FROM task_custom_field_values
+                WHERE field_id = $1
+                    AND (
+                        value = '[]'::jsonb
+                        OR (jsonb_typeof(value) = 'string' AND NOT (value #>> '{}') = ANY($2))
+                    )
+                "#,
+                id,
+                &options,
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        record_field_activity(&plexo_engine, custom_field.project_id, member_id).await;
+
+        Ok(loader
+            .load_one(id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("CustomField", id))?)
+    }
+
+    /// Deletes a custom field and its values on every task.
+    async fn delete_custom_field(&self, ctx: &Context<'_>, id: Uuid) -> Result<CustomField> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<CustomFieldLoader>>()?;
+
+        let custom_field = loader
+            .load_one(id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("CustomField", id))?;
+
+        ensure_can_edit_field(&plexo_engine, custom_field.project_id, member_id).await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM custom_fields
+            WHERE id = $1
+            "#,
+            id,
+        )
+        .execute(&*plexo_engine.pool)
+        .await?;
+
+        record_field_activity(&plexo_engine, custom_field.project_id, member_id).await;
+
+        Ok(custom_field)
+    }
+}
@@ -0,0 +1,266 @@
+use async_graphql::{dataloader::DataLoader, Context, Object};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{
+    errors::{definitions::PlexoAppError, extensions::Result},
+    graphql::{
+        auth::extract_context,
+        validation::{InputValidator, Reference, NAME_MAX_LENGTH},
+    },
+    sdk::{
+        activity::{ActivityOperationType, ActivityResourceType},
+        cycle::Cycle,
+        loaders::CycleLoader,
+        utilities::DateTimeBridge,
+    },
+};
+
+#[derive(Default)]
+pub struct CyclesMutation;
+
+#[Object]
+impl CyclesMutation {
+    async fn create_cycle(
+        &self,
+        ctx: &Context<'_>,
+        team_id: Uuid,
+        name: String,
+        starts_at: DateTime<Utc>,
+        ends_at: DateTime<Utc>,
+        #[graphql(
+            default = true,
+            desc = "Moves unfinished tasks to the next cycle when this one ends"
+        )]
+        carry_over: bool,
+    ) -> Result<Cycle> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        let mut validator = InputValidator::new();
+
+        validator.required_text("name", &name, NAME_MAX_LENGTH);
+
+        if ends_at <= starts_at {
+            validator.error("endsAt", "must be after startsAt");
+        }
+
+        validator
+            .exists(&plexo_engine, "teamId", Reference::Team, Some(team_id))
+            .await?;
+
+        validator.finish()?;
+
+        let cycle = sqlx::query!(
+            r#"
+            INSERT INTO cycles (team_id, name, starts_at, ends_at, carry_over)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id
+            "#,
+            team_id,
+            name,
+            DateTimeBridge::from_date_time(starts_at),
+            DateTimeBridge::from_date_time(ends_at),
+            carry_over,
+        )
+        .fetch_one(&*plexo_engine.pool)
+        .await?;
+
+        plexo_engine
+            .record_activity(
+                ActivityOperationType::Create,
+                ActivityResourceType::Cycle,
+                cycle.id,
+                member_id,
+            )
+            .await;
+
+        let loader = ctx.data::<DataLoader<CycleLoader>>()?;
+
+        Ok(loader
+            .load_one(cycle.id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("Cycle", cycle.id))?)
+    }
+
+    async fn update_cycle(
+        &self,
+        ctx: &Context<'_>,
+        id: Uuid,
+        name: Option<String>,
+        starts_at: Option<DateTime<Utc>>,
+        ends_at: Option<DateTime<Utc>>,
+        carry_over: Option<bool>,
+    ) -> Result<Cycle> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        let current = sqlx::query!(
+            r#"
+            SELECT starts_at, ends_at FROM cycles
+            WHERE id = $1
+            "#,
+            id,
+        )
+        .fetch_optional(&*plexo_engine.pool)
+        .await?
+        .ok_or_else(|| PlexoAppError::not_found("Cycle", id))?;
+
+        let mut validator = InputValidator::new();
+
+        validator.optional_required_text("name", name.as_deref(), NAME_MAX_LENGTH);
+
+        let next_starts_at =
+            starts_at.unwrap_or_else(|| DateTimeBridge::from_offset_date_time(current.starts_at));
+        let next_ends_at =
+            ends_at.unwrap_or_else(|| DateTimeBridge::from_offset_date_time(current.ends_at));
+
+        if next_ends_at <= next_starts_at {
+            validator.error("endsAt", "must be after startsAt");
+        }
+
+        validator.finish()?;
+
+        sqlx::query!(
+            r#"
+            UPDATE cycles
+            SET
+                name = COALESCE($1, name),
+                starts_at = COALESCE($2, starts_at),
+                ends_at = COALESCE($3, ends_at),
+                carry_over = COALESCE($4, carry_over)
+            WHERE id = $5
+            "#,
+            name,
+            starts_at.map(DateTimeBridge::from_date_time),
+            ends_at.map(DateTimeBridge::from_date_time),
+            carry_over,
+            id,
+        )
+        .execute(&*plexo_engine.pool)
+        .await?;
+
+        plexo_engine
+            .record_activity(
+                ActivityOperationType::Update,
+                ActivityResourceType::Cycle,
+                id,
+                member_id,
+            )
+            .await;
+
+        let loader = ctx.data::<DataLoader<CycleLoader>>()?;
+
+        Ok(loader
+            .load_one(id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("Cycle", id))?)
+    }
+
+    /// Deletes a cycle. Its tasks stay, without a cycle.
+    async fn delete_cycle(&self, ctx: &Context<'_>, id: Uuid) -> Result<Cycle> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<CycleLoader>>()?;
+
+        let cycle = loader
+            .load_one(id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("Cycle", id))?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM cycles
+            WHERE id = $1
+            "#,
+            id,
+        )
+        .execute(&*plexo_engine.pool)
+        .await?;
+
+        plexo_engine
+            .record_activity(
+                ActivityOperationType::Delete,
+                ActivityResourceType::Cycle,
+                id,
+                member_id,
+            )
+            .await;
+
+        Ok(cycle)
+    }
+
+    /// Ends a cycle now. Unfinished tasks move to `carryOverTo` when given, otherwise to the
+    /// team's next cycle if the cycle has `carryOver` enabled. `carryOverTo` must be another
+    /// cycle of the same team.
+    async fn close_cycle(
+        &self,
+        ctx: &Context<'_>,
+        id: Uuid,
+        carry_over_to: Option<Uuid>,
+    ) -> Result<Cycle> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        let cycle = sqlx::query!(
+            r#"
+            SELECT team_id FROM cycles
+            WHERE id = $1
+            "#,
+            id,
+        )
+        .fetch_optional(&*plexo_engine.pool)
+        .await?
+        .ok_or_else(|| PlexoAppError::not_found("Cycle", id))?;
+
+        let mut validator = InputValidator::new();
+
+        validator
+            .exists(
+                &plexo_engine,
+                "carryOverTo",
+                Reference::Cycle,
+                carry_over_to,
+            )
+            .await?;
+
+        if carry_over_to == Some(id) {
+            validator.error("carryOverTo", "can't be the cycle being closed");
+        } else if let Some(target_id) = carry_over_to {
+            let target = sqlx::query!(
+                r#"
+                SELECT team_id FROM cycles
+                WHERE id = $1
+                "#,
+                target_id,
+            )
+            .fetch_optional(&*plexo_engine.pool)
+            .await?;
+
+            if target.is_some_and(|target| target.team_id != cycle.team_id) {
+                validator.error("carryOverTo", "must be a cycle of the same team");
+            }
+        }
+
+        validator.finish()?;
+
+        let moved = plexo_engine.close_cycle(id, carry_over_to).await?;
+
+        plexo_engine
+            .record_activity(
+                ActivityOperationType::Update,
+                ActivityResourceType::Cycle,
+                id,
+                member_id,
+            )
+            .await;
+
+        plexo_engine
+            .publish_carried_over_tasks(&moved, member_id)
+            .await?;
+
+        let loader = ctx.data::<DataLoader<CycleLoader>>()?;
+
+        Ok(loader
+            .load_one(id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("Cycle", id))?)
+    }
+}
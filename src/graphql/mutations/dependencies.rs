@@ -0,0 +1,124 @@
+use async_graphql::{dataloader::DataLoader, Context, Object};
+use uuid::Uuid;
+
+use crate::{
+    errors::{definitions::PlexoAppError, extensions::Result},
+    graphql::auth::extract_context,
+    sdk::{
+        activity::{ActivityOperationType, ActivityResourceType},
+        loaders::TaskLoader,
+        task::Task,
+    },
+};
+
+#[derive(Default)]
+pub struct DependenciesMutation;
+
+#[Object]
+impl DependenciesMutation {
+    /// Marks `blocked_id` as unable to start until `blocker_id` is finished.
+    async fn add_task_dependency(
+        &self,
+        ctx: &Context<'_>,
+        blocker_id: Uuid,
+        blocked_id: Uuid,
+    ) -> Result<Task> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        let mut tx = plexo_engine.pool.begin().await?;
+
+        // Serializes dependency changes so two concurrent inserts can't close a cycle together.
+        sqlx::query!("LOCK TABLE task_dependencies IN SHARE ROW EXCLUSIVE MODE")
+            .execute(&mut *tx)
+            .await?;
+
+        // The new edge closes a cycle if the blocker is already downstream of the blocked task.
+        let creates_cycle = sqlx::query!(
+            r#"
+            WITH RECURSIVE downstream(task_id) AS (
+                SELECT $1::uuid
+                UNION
+                SELECT task_dependencies.blocked_id
+                FROM task_dependencies
+                JOIN downstream ON task_dependencies.blocker_id = downstream.task_id
+            )
+            SELECT EXISTS (SELECT 1 FROM downstream WHERE task_id = $2) AS "creates_cycle!"
+            "#,
+            blocked_id,
+            blocker_id,
+        )
+        .fetch_one(&mut *tx)
+        .await?
+        .creates_cycle;
+
+        if creates_cycle {
+            return Err(PlexoAppError::DependencyCycle.into());
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO task_dependencies (blocker_id, blocked_id)
+            VALUES ($1, $2)
+            ON CONFLICT DO NOTHING
+            "#,
+            blocker_id,
+            blocked_id,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        plexo_engine
+            .record_activity(
+                ActivityOperationType::Update,
+                ActivityResourceType::Task,
+                blocked_id,
+                member_id,
+            )
+            .await;
+
+        let loader = ctx.data::<DataLoader<TaskLoader>>()?;
+
+        Ok(loader
+            .load_one(blocked_id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("Task", blocked_id))?)
+    }
+
+    async fn remove_task_dependency(
+        &self,
+        ctx: &Context<'_>,
+        blocker_id: Uuid,
+        blocked_id: Uuid,
+    ) -> Result<Task> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM task_dependencies
+            WHERE blocker_id = $1 AND blocked_id = $2
+            "#,
+            blocker_id,
+            blocked_id,
+        )
+        .execute(&*plexo_engine.pool)
+        .await?;
+
+        plexo_engine
+            .record_activity(
+                ActivityOperationType::Update,
+                ActivityResourceType::Task,
+                blocked_id,
+                member_id,
+            )
+            .await;
+
+        let loader = ctx.data::<DataLoader<TaskLoader>>()?;
+
+        Ok(loader
+            .load_one(blocked_id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("Task", blocked_id))?)
+    }
+}
@@ -0,0 +1,179 @@
+use async_graphql::{dataloader::DataLoader, Context, Object};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{
+    errors::{definitions::PlexoAppError, extensions::Result},
+    graphql::{
+        auth::extract_context,
+        validation::{InputValidator, Reference, DESCRIPTION_MAX_LENGTH, NAME_MAX_LENGTH},
+    },
+    sdk::{
+        activity::{ActivityOperationType, ActivityResourceType},
+        loaders::MilestoneLoader,
+        milestone::{Milestone, MilestoneStatus},
+        utilities::DateTimeBridge,
+    },
+};
+
+#[derive(Default)]
+pub struct MilestonesMutation;
+
+#[Object]
+impl MilestonesMutation {
+    async fn create_milestone(
+        &self,
+        ctx: &Context<'_>,
+        project_id: Uuid,
+        name: String,
+        description: Option<String>,
+        target_date: Option<DateTime<Utc>>,
+        status: Option<MilestoneStatus>,
+    ) -> Result<Milestone> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        let mut validator = InputValidator::new();
+
+        validator
+            .required_text("name", &name, NAME_MAX_LENGTH)
+            .text(
+                "description",
+                description.as_deref(),
+                DESCRIPTION_MAX_LENGTH,
+            );
+
+        validator
+            .exists(
+                &plexo_engine,
+                "projectId",
+                Reference::Project,
+                Some(project_id),
+            )
+            .await?;
+
+        validator.finish()?;
+
+        let milestone = sqlx::query!(
+            r#"
+            INSERT INTO milestones (project_id, name, description, target_date, status)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id
+            "#,
+            project_id,
+            name,
+            description,
+            target_date.map(DateTimeBridge::from_date_time),
+            status.unwrap_or_default().to_str(),
+        )
+        .fetch_one(&*plexo_engine.pool)
+        .await?;
+
+        plexo_engine
+            .record_activity(
+                ActivityOperationType::Create,
+                ActivityResourceType::Milestone,
+                milestone.id,
+                member_id,
+            )
+            .await;
+
+        let loader = ctx.data::<DataLoader<MilestoneLoader>>()?;
+
+        Ok(loader
+            .load_one(milestone.id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("Milestone", milestone.id))?)
+    }
+
+    async fn update_milestone(
+        &self,
+        ctx: &Context<'_>,
+        id: Uuid,
+        name: Option<String>,
+        description: Option<String>,
+        target_date: Option<DateTime<Utc>>,
+        status: Option<MilestoneStatus>,
+    ) -> Result<Milestone> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        let mut validator = InputValidator::new();
+
+        validator
+            .optional_required_text("name", name.as_deref(), NAME_MAX_LENGTH)
+            .text(
+                "description",
+                description.as_deref(),
+                DESCRIPTION_MAX_LENGTH,
+            );
+
+        validator.finish()?;
+
+        sqlx::query!(
+            r#"
+            UPDATE milestones
+            SET
+                name = COALESCE($1, name),
+                description = COALESCE($2, description),
+                target_date = COALESCE($3, target_date),
+                status = COALESCE($4, status)
+            WHERE id = $5
+            "#,
+            name,
+            description,
+            target_date.map(DateTimeBridge::from_date_time),
+            status.map(|s| s.to_str()),
+            id,
+        )
+        .execute(&*plexo_engine.pool)
+        .await?;
+
+        plexo_engine
+            .record_activity(
+                ActivityOperationType::Update,
+                ActivityResourceType::Milestone,
+                id,
+                member_id,
+            )
+            .await;
+
+        let loader = ctx.data::<DataLoader<MilestoneLoader>>()?;
+
+        Ok(loader
+            .load_one(id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("Milestone", id))?)
+    }
+
+    /// Deletes a milestone. Its tasks stay in the project, without a milestone.
+    async fn delete_milestone(&self, ctx: &Context<'_>, id: Uuid) -> Result<Milestone> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<MilestoneLoader>>()?;
+
+        let milestone = loader
+            .load_one(id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("Milestone", id))?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM milestones
+            WHERE id = $1
+            "#,
+            id,
+        )
+        .execute(&*plexo_engine.pool)
+        .await?;
+
+        plexo_engine
+            .record_activity(
+                ActivityOperationType::Delete,
+                ActivityResourceType::Milestone,
+                id,
+                member_id,
+            )
+            .await;
+
+        Ok(milestone)
+    }
+}
@@ -1,11 +1,52 @@
+pub mod attachments;
 pub mod auth;
+pub mod board;
+pub mod bulk;
+pub mod comments;
+pub mod custom_fields;
+pub mod cycles;
+pub mod dependencies;
+pub mod milestones;
+pub mod notifications;
+pub mod recurrences;
 pub mod resources;
+pub mod templates;
+pub mod time_tracking;
+pub mod trash;
+pub mod webhooks;
+pub mod workflows;
 
 use async_graphql::MergedObject;
 
-use self::{auth::AuthMutation, resources::ResourcesMutation};
+use self::{
+    attachments::AttachmentsMutation, auth::AuthMutation, board::BoardMutation, bulk::BulkMutation,
+    comments::CommentsMutation, custom_fields::CustomFieldsMutation, cycles::CyclesMutation,
+    dependencies::DependenciesMutation, milestones::MilestonesMutation,
+    notifications::NotificationsMutation, recurrences::RecurrencesMutation,
+    resources::ResourcesMutation, templates::TemplatesMutation,
+    time_tracking::TimeTrackingMutation, trash::TrashMutation, webhooks::WebhooksMutation,
+    workflows::WorkflowsMutation,
+};
 
 // use super::{auth_mutation:i:AuthMutation, resources_mutation::ResourcesMutation};
 
 #[derive(MergedObject, Default)]
-pub struct MutationRoot(ResourcesMutation, AuthMutation);
+pub struct MutationRoot(
+    ResourcesMutation,
+    AuthMutation,
+    CommentsMutation,
+    DependenciesMutation,
+    WorkflowsMutation,
+    RecurrencesMutation,
+    TimeTrackingMutation,
+    CyclesMutation,
+    MilestonesMutation,
+    CustomFieldsMutation,
+    AttachmentsMutation,
+    TemplatesMutation,
+    TrashMutation,
+    BoardMutation,
+    BulkMutation,
+    NotificationsMutation,
+    WebhooksMutation,
+);
@@ -0,0 +1,130 @@
+use async_graphql::{dataloader::DataLoader, Context, Object};
+use uuid::Uuid;
+
+use crate::{
+    errors::{definitions::PlexoAppError, extensions::Result},
+    graphql::auth::extract_context,
+    sdk::{
+        activity::ActivityResourceType,
+        loaders::{ProjectLoader, TaskLoader},
+        member::EmailNotificationMode,
+        project::Project,
+        task::Task,
+    },
+};
+
+#[derive(Default)]
+pub struct NotificationsMutation;
+
+#[Object]
+impl NotificationsMutation {
+    /// Marks notifications of the signed-in member as read, all of them without `ids`. Returns
+    /// how many were unread.
+    async fn mark_notifications_read(
+        &self,
+        ctx: &Context<'_>,
+        ids: Option<Vec<Uuid>>,
+    ) -> Result<i64> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        let marked = sqlx::query!(
+            r#"
+            UPDATE notifications SET read_at = NOW()
+            WHERE member_id = $1 AND read_at IS NULL AND ($2::uuid[] IS NULL OR id = ANY($2))
+            "#,
+            member_id,
+            ids.as_deref(),
+        )
+        .execute(&*plexo_engine.pool)
+        .await?
+        .rows_affected();
+
+        Ok(marked as i64)
+    }
+
+    /// Sets how the signed-in member gets emails about assignments, mentions and due dates.
+    async fn update_email_notifications(
+        &self,
+        ctx: &Context<'_>,
+        mode: EmailNotificationMode,
+    ) -> Result<EmailNotificationMode> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        sqlx::query!(
+            r#"
+            UPDATE members SET email_notifications = $2
+            WHERE id = $1
+            "#,
+            member_id,
+            mode.to_str(),
+        )
+        .execute(&*plexo_engine.pool)
+        .await?;
+
+        Ok(mode)
+    }
+
+    async fn watch_task(&self, ctx: &Context<'_>, id: Uuid) -> Result<Task> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<TaskLoader>>()?;
+        let task = loader
+            .load_one(id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("Task", id))?;
+
+        plexo_engine
+            .add_watcher(member_id, ActivityResourceType::Task, id)
+            .await?;
+
+        Ok(task)
+    }
+
+    async fn unwatch_task(&self, ctx: &Context<'_>, id: Uuid) -> Result<Task> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<TaskLoader>>()?;
+        let task = loader
+            .load_one(id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("Task", id))?;
+
+        plexo_engine
+            .remove_watcher(member_id, ActivityResourceType::Task, id)
+            .await?;
+
+        Ok(task)
+    }
+
+    async fn watch_project(&self, ctx: &Context<'_>, id: Uuid) -> Result<Project> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<ProjectLoader>>()?;
+        let project = loader
+            .load_one(id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("Project", id))?;
+
+        plexo_engine
+            .add_watcher(member_id, ActivityResourceType::Project, id)
+            .await?;
+
+        Ok(project)
+    }
+
+    async fn unwatch_project(&self, ctx: &Context<'_>, id: Uuid) -> Result<Project> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<ProjectLoader>>()?;
+        let project = loader
+            .load_one(id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("Project", id))?;
+
+        plexo_engine
+            .remove_watcher(member_id, ActivityResourceType::Project, id)
+            .await?;
+
+        Ok(project)
+    }
+}
@@ -0,0 +1,109 @@
+use std::str::FromStr;
+
+use async_graphql::{dataloader::DataLoader, Context, Object};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{
+    errors::{definitions::PlexoAppError, extensions::Result},
+    graphql::auth::extract_context,
+    sdk::{
+        activity::{ActivityOperationType, ActivityResourceType},
+        loaders::TaskLoader,
+        recurrence::RecurrenceRule,
+        task::Task,
+        utilities::DateTimeBridge,
+    },
+};
+
+#[derive(Default)]
+pub struct RecurrencesMutation;
+
+#[Object]
+impl RecurrencesMutation {
+    /// Makes a task repeat following `rule`, e.g. `FREQ=WEEKLY;INTERVAL=2;BYDAY=MO`.
+    /// The series starts at `startsAt`, or at the task's due date when not given.
+    async fn set_task_recurrence(
+        &self,
+        ctx: &Context<'_>,
+        task_id: Uuid,
+        rule: String,
+        starts_at: Option<DateTime<Utc>>,
+    ) -> Result<Task> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        let rule = RecurrenceRule::from_str(&rule)?;
+
+        let loader = ctx.data::<DataLoader<TaskLoader>>()?;
+
+        let task = loader
+            .load_one(task_id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("Task", task_id))?;
+
+        let starts_at = starts_at.or(task.due_date).unwrap_or_else(Utc::now);
+        let next_occurrence_at = rule.next_after(starts_at, starts_at);
+
+        sqlx::query!(
+            r#"
+            INSERT INTO task_recurrences (task_id, rule, starts_at, current_occurrence_at, next_occurrence_at)
+            VALUES ($1, $2, $3, $3, $4)
+            ON CONFLICT (task_id) DO UPDATE
+            SET
+                rule = EXCLUDED.rule,
+                starts_at = EXCLUDED.starts_at,
+                current_occurrence_at = EXCLUDED.current_occurrence_at,
+                next_occurrence_at = EXCLUDED.next_occurrence_at,
+                occurrences = 1
+            "#,
+            task_id,
+            rule.to_string(),
+            DateTimeBridge::from_date_time(starts_at),
+            next_occurrence_at.map(DateTimeBridge::from_date_time),
+        )
+        .execute(&*plexo_engine.pool)
+        .await?;
+
+        plexo_engine
+            .record_activity(
+                ActivityOperationType::Update,
+                ActivityResourceType::Task,
+                task_id,
+                member_id,
+            )
+            .await;
+
+        Ok(task)
+    }
+
+    /// Stops a task from repeating. Occurrences already created are kept.
+    async fn clear_task_recurrence(&self, ctx: &Context<'_>, task_id: Uuid) -> Result<Task> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM task_recurrences
+            WHERE task_id = $1
+            "#,
+            task_id,
+        )
+        .execute(&*plexo_engine.pool)
+        .await?;
+
+        plexo_engine
+            .record_activity(
+                ActivityOperationType::Update,
+                ActivityResourceType::Task,
+                task_id,
+                member_id,
+            )
+            .await;
+
+        let loader = ctx.data::<DataLoader<TaskLoader>>()?;
+
+        Ok(loader
+            .load_one(task_id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("Task", task_id))?)
+    }
+}
@@ -1,56 +1,367 @@
-use async_graphql::{Context, InputObject, Object, Result};
+use std::collections::HashMap;
+
+use async_graphql::{dataloader::DataLoader, Context, InputObject, Object};
 use chrono::{DateTime, Utc};
-use sqlx;
+use serde_json::Value;
+use sqlx::{self, types::time::OffsetDateTime, PgConnection};
 use uuid::Uuid;
 
+use super::custom_fields::{save_custom_field_values, validate_custom_field_values};
+use super::workflows::resolve_task_transition;
 use crate::{
-    errors::definitions::PlexoAppError,
-    graphql::auth::extract_context,
+    errors::{definitions::PlexoAppError, extensions::Result},
+    graphql::{
+        auth::extract_context,
+        validation::{
+            InputValidator, Reference, DESCRIPTION_MAX_LENGTH, NAME_MAX_LENGTH, TITLE_MAX_LENGTH,
+        },
+    },
     sdk::{
         activity::{ActivityOperationType, ActivityResourceType},
+        custom_field::CustomFieldValueInput,
         labels::Label,
+        loaders::{ProjectLoader, TaskLoader},
         member::{Member, MemberRole},
-        project::Project,
-        task::{Task, TaskPriority, TaskStatus},
+        project::{DuplicateProjectOptions, Project},
+        task::{SubtaskDeletion, Task, TaskPriority, TaskStatus},
         team::{Team, TeamVisibility},
         utilities::DateTimeBridge,
     },
-    system::core::Engine,
+    system::{core::Engine, subtasks::move_subtasks, trash::trash_subtasks},
 };
 
-#[derive(InputObject)]
-struct AssigneesOperation {
-    _append: Option<Vec<Uuid>>,
-    _delete: Option<Vec<Uuid>>,
-}
-
 #[derive(InputObject)]
 struct CreateTaskInput {
     title: String,
     description: Option<String>,
-    status: Option<String>,
-    priority: Option<String>,
+    status: Option<TaskStatus>,
+    priority: Option<TaskPriority>,
     due_date: Option<DateTime<Utc>>,
     project_id: Option<Uuid>,
     lead_id: Option<Uuid>,
     labels: Option<Vec<Uuid>>,
     assignees: Option<Vec<Uuid>>,
     parent_id: Option<Uuid>,
+    estimate: Option<i32>,
+    cycle_id: Option<Uuid>,
+    milestone_id: Option<Uuid>,
+    custom_fields: Option<Vec<CustomFieldValueInput>>,
     subtasks: Option<Vec<CreateTaskInput>>,
 }
 
+/// Checks the fields of a task input, but not its subtasks. `path` prefixes the field names.
+async fn validate_task_fields(
+    validator: &mut InputValidator,
+    plexo_engine: &Engine,
+    input: &CreateTaskInput,
+    path: &str,
+) -> Result<(), sqlx::Error> {
+    let field = |name: &str| format!("{}{}", path, name);
+
+    validator
+        .required_text(field("title"), &input.title, TITLE_MAX_LENGTH)
+        .text(
+            field("description"),
+            input.description.as_deref(),
+            DESCRIPTION_MAX_LENGTH,
+        )
+        .non_negative(field("estimate"), input.estimate);
+
+    validator
+        .exists(
+            plexo_engine,
+            field("projectId"),
+            Reference::Project,
+            input.project_id,
+        )
+        .await?;
+    validator
+        .exists(
+            plexo_engine,
+            field("leadId"),
+            Reference::Member,
+            input.lead_id,
+        )
+        .await?;
+    validator
+        .exists(
+            plexo_engine,
+            field("assignees"),
+            Reference::Member,
+            input.assignees.iter().flatten().copied(),
+        )
+        .await?;
+    validator
+        .exists(
+            plexo_engine,
+            field("labels"),
+            Reference::Label,
+            input.labels.iter().flatten().copied(),
+        )
+        .await?;
+    validator
+        .exists(
+            plexo_engine,
+            field("cycleId"),
+            Reference::Cycle,
+            input.cycle_id,
+        )
+        .await?;
+    validator
+        .exists(
+            plexo_engine,
+            field("milestoneId"),
+            Reference::Milestone,
+            input.milestone_id,
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Checks a task input and its subtasks, whose fields are named `subtasks.<index>.<field>`.
+async fn validate_task_input(
+    plexo_engine: &Engine,
+    input: &CreateTaskInput,
+    path: &str,
+) -> Result<(), PlexoAppError> {
+    let mut validator = InputValidator::new();
+
+    validate_task_fields(&mut validator, plexo_engine, input, path).await?;
+
+    validator
+        .exists(
+            plexo_engine,
+            format!("{}parentId", path),
+            Reference::Task,
+            input.parent_id,
+        )
+        .await?;
+
+    // Subtasks always hang from the new task, so their own parent is ignored. Only one level is
+    // created, so anything a subtask would pass further down is refused rather than dropped.
+    for (i, subtask) in input.subtasks.iter().flatten().enumerate() {
+        let subtask_path = format!("{}subtasks.{}.", path, i);
+
+        validate_task_fields(&mut validator, plexo_engine, subtask, &subtask_path).await?;
+
+        if subtask.subtasks.is_some() {
+            validator.error(
+                format!("{}subtasks", subtask_path),
+                "can't be set on a subtask, create it first",
+            );
+        }
+
+        if subtask.custom_fields.is_some() {
+            validator.error(
+                format!("{}customFields", subtask_path),
+                "can't be set on a subtask, create it first",
+            );
+        }
+    }
+
+    validator.finish()
+}
+
+/// Inserts a task row with its assignees and labels.
+async fn insert_task_row(
+    conn: &mut PgConnection,
+    owner_id: Uuid,
+    input: &CreateTaskInput,
+    parent_id: Option<Uuid>,
+) -> Result<Uuid> {
+    let task = sqlx::query!(r#"
+        INSERT INTO tasks (title, description, owner_id, status, priority, due_date, project_id, lead_id, parent_id, estimate, cycle_id, milestone_id)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        RETURNING id
+        "#,
+        input.title,
+        input.description,
+        owner_id,
+        input.status.map(|s| s.to_str()),
+        input.priority.map(|p| p.to_str()),
+        input.due_date.map(DateTimeBridge::from_date_time),
+        input.project_id,
+        input.lead_id,
+        parent_id,
+        input.estimate,
+        input.cycle_id,
+        input.milestone_id,
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    if let Some(assignees) = &input.assignees {
+        sqlx::query!(
+            r#"
+            INSERT INTO tasks_by_assignees (task_id, assignee_id)
+            SELECT $1, assignee_id FROM unnest($2::uuid[]) AS assignee_id
+            ON CONFLICT DO NOTHING
+            "#,
+            task.id,
+            assignees,
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    if let Some(labels) = &input.labels {
+        sqlx::query!(
+            r#"
+            INSERT INTO labels_by_tasks (task_id, label_id)
+            SELECT $1, label_id FROM unnest($2::uuid[]) AS label_id
+            ON CONFLICT DO NOTHING
+            "#,
+            task.id,
+            labels,
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(task.id)
+}
+
+/// Inserts a task with its custom field values and its subtasks. Returns the ids of the created
+/// tasks, the task first.
+async fn insert_task(
+    conn: &mut PgConnection,
+    owner_id: Uuid,
+    input: CreateTaskInput,
+    custom_fields: Vec<(Uuid, Option<Value>)>,
+) -> Result<Vec<Uuid>> {
+    let task_id = insert_task_row(conn, owner_id, &input, input.parent_id).await?;
+
+    save_custom_field_values(conn, task_id, custom_fields).await?;
+
+    let mut created = vec![task_id];
+
+    for subtask in input.subtasks.unwrap_or_default() {
+        created.push(insert_task_row(conn, owner_id, &subtask, Some(task_id)).await?);
+    }
+
+    Ok(created)
+}
+
+/// Sends the events and records the activity of committed tasks, and returns them.
+async fn publish_created_tasks(
+    ctx: &Context<'_>,
+    plexo_engine: &Engine,
+    member_id: Uuid,
+    ids: &[Uuid],
+) -> Result<HashMap<Uuid, Task>> {
+    let loader = ctx.data::<DataLoader<TaskLoader>>()?;
+    let tasks = loader.load_many(ids.iter().copied()).await?;
+
+    for id in ids {
+        if let Some(task) = tasks.get(id) {
+            plexo_engine
+                .subscription_manager
+                .send_task_event(task.clone())
+                .await
+                .ok();
+        }
+    }
+
+    plexo_engine
+        .record_activities(
+            ActivityOperationType::Create,
+            ActivityResourceType::Task,
+            ids,
+            member_id,
+        )
+        .await;
+
+    Ok(tasks)
+}
+
+/// Checks the lead, members and teams given to a project.
+async fn validate_project_references(
+    validator: &mut InputValidator,
+    plexo_engine: &Engine,
+    lead_id: Option<Uuid>,
+    members: &Option<Vec<Uuid>>,
+    teams: &Option<Vec<Uuid>>,
+) -> Result<(), sqlx::Error> {
+    validator
+        .exists(plexo_engine, "leadId", Reference::Member, lead_id)
+        .await?;
+    validator
+        .exists(
+            plexo_engine,
+            "members",
+            Reference::Member,
+            members.iter().flatten().copied(),
+        )
+        .await?;
+    validator
+        .exists(
+            plexo_engine,
+            "teams",
+            Reference::Team,
+            teams.iter().flatten().copied(),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Checks the members and projects given to a team.
+async fn validate_team_references(
+    validator: &mut InputValidator,
+    plexo_engine: &Engine,
+    members: &Option<Vec<Uuid>>,
+    projects: &Option<Vec<Uuid>>,
+) -> Result<(), sqlx::Error> {
+    validator
+        .exists(
+            plexo_engine,
+            "members",
+            Reference::Member,
+            members.iter().flatten().copied(),
+        )
+        .await?;
+    validator
+        .exists(
+            plexo_engine,
+            "projects",
+            Reference::Project,
+            projects.iter().flatten().copied(),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Checks the fields of a label. Names are required on creation only.
+fn validate_label(
+    name: Option<&str>,
+    description: Option<&str>,
+    color: Option<&str>,
+) -> Result<(), PlexoAppError> {
+    let mut validator = InputValidator::new();
+
+    validator
+        .optional_required_text("name", name, NAME_MAX_LENGTH)
+        .text("description", description, DESCRIPTION_MAX_LENGTH)
+        .hex_color("color", color);
+
+    validator.finish()
+}
+
 #[derive(Default)]
 pub struct ResourcesMutation;
 
 #[Object]
 impl ResourcesMutation {
+    #[allow(clippy::too_many_arguments)]
     async fn create_task(
         &self,
         ctx: &Context<'_>,
         title: String,
         description: Option<String>,
-        status: Option<String>,
-        priority: Option<String>,
+        status: Option<TaskStatus>,
+        priority: Option<TaskPriority>,
         due_date: Option<DateTime<Utc>>,
         project_id: Option<Uuid>,
         lead_id: Option<Uuid>,
@@ -58,315 +369,266 @@ impl ResourcesMutation {
         assignees: Option<Vec<Uuid>>,
         parent_id: Option<Uuid>,
         subtasks: Option<Vec<CreateTaskInput>>,
+        #[graphql(desc = "Estimate in points")] estimate: Option<i32>,
+        cycle_id: Option<Uuid>,
+        milestone_id: Option<Uuid>,
+        custom_fields: Option<Vec<CustomFieldValueInput>>,
     ) -> Result<Task> {
         let (plexo_engine, member_id) = extract_context(ctx)?;
 
-        let task_final_info = sqlx::query!(r#"
-            INSERT INTO tasks (title, description, owner_id, status, priority, due_date, project_id, lead_id, parent_id)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
-            RETURNING * 
-            "#,
+        let mut input = CreateTaskInput {
             title,
             description,
-            member_id,
             status,
             priority,
-            due_date.map(|d| DateTimeBridge::from_date_time(d)),
+            due_date,
             project_id,
             lead_id,
+            labels,
+            assignees,
             parent_id,
-        ).fetch_one(&*plexo_engine.pool)
-        .await
-        .unwrap();
-
-        if let Some(assignees) = assignees {
-            let _delete_assignees = sqlx::query!(
-                // TODO: Update this bad implementation
-                r#"
-                DELETE FROM tasks_by_assignees
-                WHERE task_id = $1
-                "#,
-                task_final_info.id,
-            )
-            .execute(&*plexo_engine.pool)
-            .await
-            .unwrap();
-
-            for assignee in assignees {
-                let _add_assignee = sqlx::query!(
-                    r#"
-                    INSERT INTO tasks_by_assignees (task_id, assignee_id)
-                    VALUES ($1, $2)
-                    "#,
-                    task_final_info.id,
-                    assignee,
-                )
-                .execute(&*plexo_engine.pool)
-                .await
-                .unwrap();
-            }
-        }
-
-        if let Some(labels) = labels {
-            let _delete_labels = sqlx::query!(
-                r#"
-                DELETE FROM labels_by_tasks
-                WHERE task_id = $1
-                "#,
-                task_final_info.id,
-            )
-            .execute(&*plexo_engine.pool)
-            .await
-            .unwrap();
-
-            for label in labels {
-                let _add_label = sqlx::query!(
-                    r#"
-                    INSERT INTO labels_by_tasks (task_id, label_id)
-                    VALUES ($1, $2)
-                    "#,
-                    task_final_info.id,
-                    label,
-                )
-                .execute(&*plexo_engine.pool)
-                .await
-                .unwrap();
-            }
-        }
+            estimate,
+            cycle_id,
+            milestone_id,
+            custom_fields,
+            subtasks,
+        };
 
-        let subscription_manager: &crate::system::subscriptions::SubscriptionManager =
-            &ctx.data::<Engine>().unwrap().subscription_manager;
+        validate_task_input(&plexo_engine, &input, "").await?;
 
-        if let Some(subtasks_to_create) = subtasks {
-            for subtask in subtasks_to_create {
-                let _ = sqlx::query!(
-                    r#"
-                    INSERT INTO tasks (title, description, owner_id, status, priority, due_date, project_id, lead_id, parent_id)
-                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
-                    RETURNING * 
-                    "#,
-                    subtask.title,
-                    subtask.description,
-                    member_id,
-                    subtask.status,
-                    subtask.priority,
-                    subtask.due_date.map(|d| DateTimeBridge::from_date_time(d)),
-                    subtask.project_id,
-                    subtask.lead_id,
-                    task_final_info.id,
-                ).fetch_one(&*plexo_engine.pool)
-                .await
-                .unwrap();
-            }
+        let custom_fields = validate_custom_field_values(
+            ctx,
+            &plexo_engine,
+            input.project_id,
+            input.custom_fields.take().unwrap_or_default(),
+        )
+        .await?;
 
-            // TODO: Implement subscription signal for subtasks
-        }
+        let mut tx = plexo_engine.pool.begin().await?;
 
-        let task = Task {
-            id: task_final_info.id,
-            created_at: DateTimeBridge::from_offset_date_time(task_final_info.created_at),
-            updated_at: DateTimeBridge::from_offset_date_time(task_final_info.updated_at),
-            title: task_final_info.title,
-            description: task_final_info.description,
-            status: TaskStatus::from_optional_str(&task_final_info.status),
-            priority: TaskPriority::from_optional_str(&task_final_info.priority),
-            due_date: task_final_info
-                .due_date
-                .map(DateTimeBridge::from_offset_date_time),
-            project_id: task_final_info.project_id,
-            lead_id: task_final_info.lead_id,
-            owner_id: task_final_info.owner_id,
-            count: task_final_info.count,
-            parent_id: task_final_info.parent_id,
-        };
+        let created = insert_task(&mut tx, member_id, input, custom_fields).await?;
 
-        subscription_manager
-            .send_task_event(task.clone())
-            .await
-            .unwrap();
+        tx.commit().await?;
 
-        plexo_engine
-            .record_activity(
-                ActivityOperationType::Create,
-                ActivityResourceType::Task,
-                task.id,
-                member_id,
-            )
-            .await
-            .unwrap();
+        let tasks = publish_created_tasks(ctx, &plexo_engine, member_id, &created).await?;
 
-        Ok(task)
+        Ok(tasks
+            .get(&created[0])
+            .cloned()
+            .ok_or_else(|| PlexoAppError::not_found("Task", created[0]))?)
     }
 
+    /// Creates tasks with their subtasks. Each task is created atomically and the batch stops at
+    /// the first failure, unless `atomic` is set, in which case nothing is created.
     async fn create_tasks(
         &self,
         ctx: &Context<'_>,
         tasks: Vec<CreateTaskInput>,
+        #[graphql(desc = "Rolls back the whole batch if any task fails")] atomic: Option<bool>,
     ) -> Result<Vec<Task>> {
         let (plexo_engine, member_id) = extract_context(ctx)?;
 
-        let mut tasks_to_return = Vec::new();
-
-        for task in tasks {
-            let task_final_info = sqlx::query!(r#"
-                INSERT INTO tasks (title, description, owner_id, status, priority, due_date, project_id, lead_id, parent_id)
-                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
-                RETURNING * 
-                "#,
-                task.title,
-                task.description,
-                member_id,
-                task.status,
-                task.priority,
-                task.due_date.map(|d| DateTimeBridge::from_date_time(d)),
-                task.project_id,
-                task.lead_id,
-                task.parent_id,
-            ).fetch_one(&*plexo_engine.pool)
-            .await
-            .unwrap();
+        let mut roots = Vec::with_capacity(tasks.len());
+        let mut created = Vec::new();
+        let mut failure = None;
 
-            if let Some(assignees) = task.assignees {
-                let _delete_assignees = sqlx::query!(
-                    // TODO: Update this bad implementation
-                    r#"
-                    DELETE FROM tasks_by_assignees
-                    WHERE task_id = $1
-                    "#,
-                    task_final_info.id,
-                )
-                .execute(&*plexo_engine.pool)
-                .await
-                .unwrap();
+        if atomic.unwrap_or(false) {
+            let mut validated = Vec::with_capacity(tasks.len());
 
-                for assignee in assignees {
-                    let _add_assignee = sqlx::query!(
-                        r#"
-                        INSERT INTO tasks_by_assignees (task_id, assignee_id)
-                        VALUES ($1, $2)
-                        "#,
-                        task_final_info.id,
-                        assignee,
-                    )
-                    .execute(&*plexo_engine.pool)
-                    .await
-                    .unwrap();
-                }
-            }
+            // Everything is checked before the transaction, which then only holds the inserts.
+            for (i, mut input) in tasks.into_iter().enumerate() {
+                validate_task_input(&plexo_engine, &input, &format!("tasks.{}.", i)).await?;
 
-            if let Some(labels) = task.labels {
-                let _delete_labels = sqlx::query!(
-                    r#"
-                    DELETE FROM labels_by_tasks
-                    WHERE task_id = $1
-                    "#,
-                    task_final_info.id,
+                let custom_fields = validate_custom_field_values(
+                    ctx,
+                    &plexo_engine,
+                    input.project_id,
+                    input.custom_fields.take().unwrap_or_default(),
                 )
-                .execute(&*plexo_engine.pool)
-                .await
-                .unwrap();
+                .await?;
 
-                for label in labels {
-                    let _add_label = sqlx::query!(
-                        r#"
-                        INSERT INTO labels_by_tasks (task_id, label_id)
-                        VALUES ($1, $2)
-                        "#,
-                        task_final_info.id,
-                        label,
-                    )
-                    .execute(&*plexo_engine.pool)
-                    .await
-                    .unwrap();
-                }
+                validated.push((input, custom_fields));
             }
 
-            let subscription_manager: &crate::system::subscriptions::SubscriptionManager =
-                &ctx.data::<Engine>().unwrap().subscription_manager;
+            let mut tx = plexo_engine.pool.begin().await?;
 
-            if let Some(subtasks_to_create) = task.subtasks {
-                for subtask in subtasks_to_create {
-                    let _ = sqlx::query!(
-                    r#"
-                    INSERT INTO tasks (title, description, owner_id, status, priority, due_date, project_id, lead_id, parent_id)
-                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
-                    RETURNING * 
-                    "#,
-                    subtask.title,
-                    subtask.description,
-                    member_id,
-                    subtask.status,
-                    subtask.priority,
-                    subtask.due_date.map(|d| DateTimeBridge::from_date_time(d)),
-                    subtask.project_id,
-                    subtask.lead_id,
-                    task_final_info.id,
-                ).fetch_one(&*plexo_engine.pool)
-                .await
-                .unwrap();
-                }
+            for (input, custom_fields) in validated {
+                let ids = insert_task(&mut tx, member_id, input, custom_fields).await?;
 
-                // TODO: Implement subscription signal for subtasks
+                roots.push(ids[0]);
+                created.extend(ids);
             }
 
-            let task = Task {
-                id: task_final_info.id,
-                created_at: DateTimeBridge::from_offset_date_time(task_final_info.created_at),
-                updated_at: DateTimeBridge::from_offset_date_time(task_final_info.updated_at),
-                title: task_final_info.title,
-                description: task_final_info.description,
-                status: TaskStatus::from_optional_str(&task_final_info.status),
-                priority: TaskPriority::from_optional_str(&task_final_info.priority),
-                due_date: task_final_info
-                    .due_date
-                    .map(DateTimeBridge::from_offset_date_time),
-                project_id: task_final_info.project_id,
-                lead_id: task_final_info.lead_id,
-                owner_id: task_final_info.owner_id,
-                count: task_final_info.count,
-                parent_id: task_final_info.parent_id,
-            };
-
-            subscription_manager
-                .send_task_event(task.clone())
-                .await
-                .unwrap();
+            tx.commit().await?;
+        } else {
+            for (i, mut input) in tasks.into_iter().enumerate() {
+                if let Err(e) =
+                    validate_task_input(&plexo_engine, &input, &format!("tasks.{}.", i)).await
+                {
+                    failure = Some(e.into());
+                    break;
+                }
 
-            plexo_engine
-                .record_activity(
-                    ActivityOperationType::Create,
-                    ActivityResourceType::Task,
-                    task.id,
-                    member_id,
+                let custom_fields = match validate_custom_field_values(
+                    ctx,
+                    &plexo_engine,
+                    input.project_id,
+                    input.custom_fields.take().unwrap_or_default(),
                 )
                 .await
-                .unwrap();
-
-            tasks_to_return.push(task);
+                {
+                    Ok(custom_fields) => custom_fields,
+                    Err(e) => {
+                        failure = Some(e);
+                        break;
+                    }
+                };
+
+                let mut tx = plexo_engine.pool.begin().await?;
+
+                match insert_task(&mut tx, member_id, input, custom_fields).await {
+                    Ok(ids) => {
+                        tx.commit().await?;
+
+                        roots.push(ids[0]);
+                        created.extend(ids);
+                    }
+                    Err(e) => {
+                        failure = Some(e);
+                        break;
+                    }
+                }
+            }
         }
 
-        Ok(tasks_to_return)
+        // Tasks created before a failure are kept, so they are still announced.
+        let tasks = publish_created_tasks(ctx, &plexo_engine, member_id, &created).await?;
 
-        // if let Some(assignees) = task.assignees {
-        // let _delete_assignees = sqlx::query!(
+        if let Some(e) = failure {
+            return Err(e);
+        }
+
+        Ok(roots
+            .into_iter()
+            .filter_map(|id| tasks.get(&id).cloned())
+            .collect())
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn update_task(
         &self,
         ctx: &Context<'_>,
         id: Uuid,
         title: Option<String>,
         description: Option<String>,
-        status: Option<String>,
-        priority: Option<String>,
+        status: Option<TaskStatus>,
+        priority: Option<TaskPriority>,
         due_date: Option<DateTime<Utc>>,
         project_id: Option<Uuid>,
         lead_id: Option<Uuid>,
         labels: Option<Vec<Uuid>>,
         assignees: Option<Vec<Uuid>>,
+        #[graphql(desc = "Allows starting or finishing a task that still has open blockers")]
+        force: Option<bool>,
+        #[graphql(desc = "Workflow state of the task, takes precedence over status")]
+        state_id: Option<Uuid>,
+        #[graphql(desc = "Estimate in points")] estimate: Option<i32>,
+        cycle_id: Option<Uuid>,
+        milestone_id: Option<Uuid>,
+        #[graphql(desc = "Values to set, other custom fields are left as they are")]
+        custom_fields: Option<Vec<CustomFieldValueInput>>,
     ) -> Result<Task> {
         let (plexo_engine, member_id) = extract_context(ctx)?;
 
+        let current = sqlx::query!(
+            r#"
+            SELECT project_id, state_id FROM tasks
+            WHERE id = $1 AND deleted_at IS NULL
+            "#,
+            id,
+        )
+        .fetch_optional(&*plexo_engine.pool)
+        .await?
+        .ok_or_else(|| PlexoAppError::not_found("Task", id))?;
+
+        let mut validator = InputValidator::new();
+
+        validator
+            .optional_required_text("title", title.as_deref(), TITLE_MAX_LENGTH)
+            .text(
+                "description",
+                description.as_deref(),
+                DESCRIPTION_MAX_LENGTH,
+            )
+            .non_negative("estimate", estimate);
+
+        validator
+            .exists(&plexo_engine, "projectId", Reference::Project, project_id)
+            .await?;
+        validator
+            .exists(&plexo_engine, "leadId", Reference::Member, lead_id)
+            .await?;
+        validator
+            .exists(
+                &plexo_engine,
+                "assignees",
+                Reference::Member,
+                assignees.iter().flatten().copied(),
+            )
+            .await?;
+        validator
+            .exists(
+                &plexo_engine,
+                "labels",
+                Reference::Label,
+                labels.iter().flatten().copied(),
+            )
+            .await?;
+        validator
+            .exists(&plexo_engine, "cycleId", Reference::Cycle, cycle_id)
+            .await?;
+        validator
+            .exists(
+                &plexo_engine,
+                "milestoneId",
+                Reference::Milestone,
+                milestone_id,
+            )
+            .await?;
+
+        validator.finish()?;
+
+        let next_project_id = project_id.or(current.project_id);
+
+        let custom_fields = validate_custom_field_values(
+            ctx,
+            &plexo_engine,
+            next_project_id,
+            custom_fields.unwrap_or_default(),
+        )
+        .await?;
+
+        let next_state_id = resolve_task_transition(
+            &mut *plexo_engine.pool.acquire().await?,
+            id,
+            current.project_id,
+            current.state_id,
+            next_project_id,
+            state_id,
+            status.map(|s| s.to_str()),
+            force.unwrap_or(false),
+        )
+        .await?;
+
+        // The status is derived from the workflow state by the database when one is set.
+        let status = if next_state_id.is_some() {
+            None
+        } else {
+            status
+        };
+
+        let mut tx = plexo_engine.pool.begin().await?;
+
         let task_final_info = sqlx::query!(
             r#"
             UPDATE tasks
@@ -377,22 +639,49 @@ impl ResourcesMutation {
                 priority = COALESCE($4, priority),
                 due_date = COALESCE($5, due_date),
                 project_id = COALESCE($6, project_id),
-                lead_id = COALESCE($7, lead_id)
+                lead_id = COALESCE($7, lead_id),
+                state_id = COALESCE($9, state_id),
+                estimate = COALESCE($10, estimate),
+                cycle_id = COALESCE($11, cycle_id),
+                milestone_id = COALESCE($12, milestone_id)
             WHERE id = $8
             RETURNING * 
             "#,
             title,
             description,
-            status,
-            priority,
+            status.map(|s| s.to_str()),
+            priority.map(|p| p.to_str()),
             due_date.map(|d| DateTimeBridge::from_date_time(d)),
             project_id,
             lead_id,
             id,
+            next_state_id,
+            estimate,
+            cycle_id,
+            milestone_id,
         )
-        .fetch_one(&*plexo_engine.pool)
-        .await
-        .unwrap();
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if next_project_id != current.project_id {
+            // Fields of the previous project don't apply anymore.
+            sqlx::query!(
+                r#"
+                DELETE FROM task_custom_field_values
+                USING custom_fields
+                WHERE custom_fields.id = task_custom_field_values.field_id
+                    AND task_custom_field_values.task_id = $1
+                    AND custom_fields.project_id IS NOT NULL
+                    AND custom_fields.project_id IS DISTINCT FROM $2
+                "#,
+                id,
+                next_project_id,
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        save_custom_field_values(&mut tx, id, custom_fields).await?;
 
         if let Some(assignees) = assignees {
             let _delete_assignees = sqlx::query!(
@@ -402,9 +691,8 @@ impl ResourcesMutation {
                     "#,
                 task_final_info.id,
             )
-            .execute(&*plexo_engine.pool)
-            .await
-            .unwrap();
+            .execute(&mut *tx)
+            .await?;
 
             for assignee in assignees {
                 let _add_assignee = sqlx::query!(
@@ -415,9 +703,8 @@ impl ResourcesMutation {
                     task_final_info.id,
                     assignee,
                 )
-                .execute(&*plexo_engine.pool)
-                .await
-                .unwrap();
+                .execute(&mut *tx)
+                .await?;
             }
         }
 
@@ -429,9 +716,8 @@ impl ResourcesMutation {
                 "#,
                 task_final_info.id,
             )
-            .execute(&*plexo_engine.pool)
-            .await
-            .unwrap();
+            .execute(&mut *tx)
+            .await?;
 
             for label in labels {
                 let _add_label = sqlx::query!(
@@ -442,14 +728,12 @@ impl ResourcesMutation {
                     task_final_info.id,
                     label,
                 )
-                .execute(&*plexo_engine.pool)
-                .await
-                .unwrap();
+                .execute(&mut *tx)
+                .await?;
             }
         }
 
-        let subscription_manager: &crate::system::subscriptions::SubscriptionManager =
-            &ctx.data::<Engine>().unwrap().subscription_manager;
+        tx.commit().await?;
 
         let task = Task {
             id: task_final_info.id,
@@ -467,12 +751,19 @@ impl ResourcesMutation {
             owner_id: task_final_info.owner_id,
             count: task_final_info.count,
             parent_id: task_final_info.parent_id,
+            rank: task_final_info.rank,
+            milestone_id: task_final_info.milestone_id,
+            cycle_id: task_final_info.cycle_id,
+            estimate: task_final_info.estimate,
+            key: task_final_info.key,
+            state_id: task_final_info.state_id,
         };
 
-        subscription_manager
+        plexo_engine
+            .subscription_manager
             .send_task_event(task.clone())
             .await
-            .unwrap();
+            .ok();
 
         plexo_engine
             .record_activity(
@@ -481,51 +772,56 @@ impl ResourcesMutation {
                 task.id,
                 member_id,
             )
-            .await
-            .unwrap();
+            .await;
 
         Ok(task)
     }
 
-    async fn delete_task(&self, ctx: &Context<'_>, id: Uuid) -> Result<Task> {
+    async fn delete_task(
+        &self,
+        ctx: &Context<'_>,
+        id: Uuid,
+        #[graphql(desc = "What happens to the subtasks, which go to the trash by default")]
+        subtasks: Option<SubtaskDeletion>,
+    ) -> Result<Task> {
         let (plexo_engine, member_id) = extract_context(ctx)?;
 
+        let mut tx = plexo_engine.pool.begin().await?;
+
+        // Deleted tasks go to the trash with their relations, so they can be restored.
         let task_final_info = sqlx::query!(
             r#"
-            DELETE FROM tasks
-            WHERE id = $1
+            UPDATE tasks
+            SET deleted_at = NOW(), deleted_by = $2
+            WHERE id = $1 AND deleted_at IS NULL
             RETURNING *
             "#,
             id,
+            member_id,
         )
-        .fetch_one(&*plexo_engine.pool)
-        .await
-        .unwrap();
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| PlexoAppError::not_found("Task", id))?;
+
+        let moved_subtasks = match subtasks.unwrap_or_default() {
+            SubtaskDeletion::Delete => Vec::new(),
+            SubtaskDeletion::Reparent => {
+                move_subtasks(&mut tx, task_final_info.id, task_final_info.parent_id).await?
+            }
+            SubtaskDeletion::Detach => move_subtasks(&mut tx, task_final_info.id, None).await?,
+        };
 
-        let _deleted_assignees = sqlx::query!(
-            r#"
-            DELETE FROM tasks_by_assignees
-            WHERE task_id = $1
-            "#,
-            id,
-        )
-        .execute(&*plexo_engine.pool)
-        .await
-        .unwrap();
+        let deleted_at = task_final_info
+            .deleted_at
+            .unwrap_or_else(OffsetDateTime::now_utc);
 
-        let _deleted_labels = sqlx::query!(
-            r#"
-            DELETE FROM labels_by_tasks
-            WHERE task_id = $1
-            "#,
-            id,
-        )
-        .execute(&*plexo_engine.pool)
-        .await
-        .unwrap();
+        // Nothing is left under the task once its subtasks have moved.
+        let subtasks = trash_subtasks(&mut tx, task_final_info.id, deleted_at, member_id).await?;
+
+        tx.commit().await?;
 
         let subscription_manager: &crate::system::subscriptions::SubscriptionManager =
-            &ctx.data::<Engine>().unwrap().subscription_manager;
+            &ctx.data::<Engine>()?.subscription_manager;
 
         let task = Task {
             id: task_final_info.id,
@@ -543,12 +839,18 @@ impl ResourcesMutation {
             owner_id: task_final_info.owner_id,
             count: task_final_info.count,
             parent_id: task_final_info.parent_id,
+            rank: task_final_info.rank,
+            milestone_id: task_final_info.milestone_id,
+            cycle_id: task_final_info.cycle_id,
+            estimate: task_final_info.estimate,
+            key: task_final_info.key,
+            state_id: task_final_info.state_id,
         };
 
         subscription_manager
             .send_task_event(task.clone())
             .await
-            .unwrap();
+            .ok();
 
         plexo_engine
             .record_activity(
@@ -557,8 +859,39 @@ impl ResourcesMutation {
                 task.id,
                 member_id,
             )
-            .await
-            .unwrap();
+            .await;
+
+        for subtask_id in subtasks {
+            plexo_engine
+                .record_activity(
+                    ActivityOperationType::Delete,
+                    ActivityResourceType::Task,
+                    subtask_id,
+                    member_id,
+                )
+                .await;
+        }
+
+        let loader = ctx.data::<DataLoader<TaskLoader>>()?;
+        let moved_tasks = loader.load_many(moved_subtasks.clone()).await?;
+
+        for subtask_id in moved_subtasks {
+            if let Some(subtask) = moved_tasks.get(&subtask_id) {
+                subscription_manager
+                    .send_task_event(subtask.clone())
+                    .await
+                    .ok();
+            }
+
+            plexo_engine
+                .record_activity(
+                    ActivityOperationType::Update,
+                    ActivityResourceType::Task,
+                    subtask_id,
+                    member_id,
+                )
+                .await;
+        }
 
         Ok(task)
     }
@@ -569,27 +902,38 @@ impl ResourcesMutation {
         id: Uuid,
         email: Option<String>,
         name: Option<String>,
-        role: Option<String>,
+        role: Option<MemberRole>,
         // projects: Option<Vec<Uuid>>,
         // teams: Option<Vec<Uuid>>,
     ) -> Result<Member> {
         let (plexo_engine, member_id) = extract_context(ctx)?;
 
+        let mut validator = InputValidator::new();
+
+        validator
+            .email("email", email.as_deref())
+            .optional_required_text("name", name.as_deref(), NAME_MAX_LENGTH);
+
+        validator.finish()?;
+
         let member = sqlx::query!(
             r#"
             UPDATE members
-            SET email = $1, name = $2, role = $3
+            SET
+                email = COALESCE($1, email),
+                name = COALESCE($2, name),
+                role = COALESCE($3, role)
             WHERE id = $4
             RETURNING id, created_at, updated_at, email, name, github_id, google_id, photo_url, role
             "#,
             email,
             name,
-            role,
+            role.map(|r| r.to_str()),
             id,
         )
-        .fetch_one(&*plexo_engine.pool)
-        .await
-        .unwrap();
+        .fetch_optional(&*plexo_engine.pool)
+        .await?
+        .ok_or_else(|| PlexoAppError::not_found("Member", id))?;
 
         // if let Some(projects) = projects {
         //     let _deleted_projects = sqlx::query!(
@@ -652,8 +996,7 @@ impl ResourcesMutation {
                 member.id,
                 member_id,
             )
-            .await
-            .unwrap();
+            .await;
 
         Ok(Member {
             id: member.id,
@@ -683,6 +1026,25 @@ impl ResourcesMutation {
     ) -> Result<Project> {
         let (plexo_engine, member_id) = extract_context(ctx)?;
 
+        let mut validator = InputValidator::new();
+
+        validator
+            .required_text("name", &name, NAME_MAX_LENGTH)
+            .prefix("prefix", prefix.as_deref())
+            .text(
+                "description",
+                description.as_deref(),
+                DESCRIPTION_MAX_LENGTH,
+            )
+            .date_order("startDate", start_date, "dueDate", due_date);
+
+        validate_project_references(&mut validator, &plexo_engine, lead_id, &members, &teams)
+            .await?;
+
+        validator.finish()?;
+
+        let mut tx = plexo_engine.pool.begin().await?;
+
         let project = sqlx::query!(
             r#"
             INSERT INTO projects (name, prefix, owner_id, description, lead_id, start_date, due_date)
@@ -697,7 +1059,8 @@ impl ResourcesMutation {
             start_date.map(|d| DateTimeBridge::from_date_time(d)),
             due_date.map(|d| DateTimeBridge::from_date_time(d)),
         )
-        .fetch_one(&*plexo_engine.pool).await.unwrap();
+        .fetch_one(&mut *tx)
+        .await?;
 
         if let Some(members) = members {
             for member in members {
@@ -709,9 +1072,8 @@ impl ResourcesMutation {
                     member,
                     project.id,
                 )
-                .execute(&*plexo_engine.pool)
-                .await
-                .unwrap();
+                .execute(&mut *tx)
+                .await?;
             }
         }
 
@@ -725,14 +1087,12 @@ impl ResourcesMutation {
                     team,
                     project.id,
                 )
-                .execute(&*plexo_engine.pool)
-                .await
-                .unwrap();
+                .execute(&mut *tx)
+                .await?;
             }
         }
 
-        let subscription_manager: &crate::system::subscriptions::SubscriptionManager =
-            &ctx.data::<Engine>().unwrap().subscription_manager;
+        tx.commit().await?;
 
         let project = Project {
             id: project.id,
@@ -749,10 +1109,11 @@ impl ResourcesMutation {
             due_date: project.due_date.map(DateTimeBridge::from_offset_date_time),
         };
 
-        subscription_manager
+        plexo_engine
+            .subscription_manager
             .send_project_event(project.clone())
             .await
-            .unwrap();
+            .ok();
 
         plexo_engine
             .record_activity(
@@ -761,12 +1122,40 @@ impl ResourcesMutation {
                 project.id,
                 member_id,
             )
-            .await
-            .unwrap();
+            .await;
 
         Ok(project)
     }
 
+    /// Copies a project with its workflow and task tree in a single transaction. Dates move
+    /// with `startDate`, members and teams are copied on request.
+    async fn duplicate_project(
+        &self,
+        ctx: &Context<'_>,
+        id: Uuid,
+        name: String,
+        start_date: Option<DateTime<Utc>>,
+        options: Option<DuplicateProjectOptions>,
+    ) -> Result<Project> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        let mut validator = InputValidator::new();
+
+        validator.required_text("name", &name, NAME_MAX_LENGTH);
+        validator.finish()?;
+
+        let project_id = plexo_engine
+            .duplicate_project(id, name, start_date, options.unwrap_or_default(), member_id)
+            .await?;
+
+        let loader = ctx.data::<DataLoader<ProjectLoader>>()?;
+
+        Ok(loader
+            .load_one(project_id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("Project", project_id))?)
+    }
+
     async fn update_project(
         &self,
         ctx: &Context<'_>,
@@ -783,6 +1172,47 @@ impl ResourcesMutation {
     ) -> Result<Project> {
         let (plexo_engine, member_id) = extract_context(ctx)?;
 
+        let current = sqlx::query!(
+            r#"
+            SELECT start_date, due_date FROM projects
+            WHERE id = $1 AND deleted_at IS NULL
+            "#,
+            id,
+        )
+        .fetch_optional(&*plexo_engine.pool)
+        .await?
+        .ok_or_else(|| PlexoAppError::not_found("Project", id))?;
+
+        let mut validator = InputValidator::new();
+
+        validator
+            .optional_required_text("name", name.as_deref(), NAME_MAX_LENGTH)
+            .prefix("prefix", prefix.as_deref())
+            .text(
+                "description",
+                description.as_deref(),
+                DESCRIPTION_MAX_LENGTH,
+            )
+            .date_order(
+                "startDate",
+                start_date.or(current
+                    .start_date
+                    .map(DateTimeBridge::from_offset_date_time)),
+                "dueDate",
+                due_date.or(current.due_date.map(DateTimeBridge::from_offset_date_time)),
+            );
+
+        validator
+            .exists(&plexo_engine, "ownerId", Reference::Member, owner_id)
+            .await?;
+
+        validate_project_references(&mut validator, &plexo_engine, lead_id, &members, &teams)
+            .await?;
+
+        validator.finish()?;
+
+        let mut tx = plexo_engine.pool.begin().await?;
+
         let project = sqlx::query!(
             r#"
             UPDATE projects
@@ -806,9 +1236,9 @@ impl ResourcesMutation {
             due_date.map(|d| DateTimeBridge::from_date_time(d)),
             id,
         )
-        .fetch_one(&*plexo_engine.pool)
-        .await
-        .unwrap();
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| PlexoAppError::not_found("Project", id))?;
 
         if let Some(members) = members {
             let _deleted_members = sqlx::query!(
@@ -818,9 +1248,8 @@ impl ResourcesMutation {
                     "#,
                 id,
             )
-            .execute(&*plexo_engine.pool)
-            .await
-            .unwrap();
+            .execute(&mut *tx)
+            .await?;
 
             for member in members {
                 let _inserted_members = sqlx::query!(
@@ -831,9 +1260,8 @@ impl ResourcesMutation {
                     member,
                     project.id,
                 )
-                .execute(&*plexo_engine.pool)
-                .await
-                .unwrap();
+                .execute(&mut *tx)
+                .await?;
             }
         }
 
@@ -845,9 +1273,8 @@ impl ResourcesMutation {
                     "#,
                 id,
             )
-            .execute(&*plexo_engine.pool)
-            .await
-            .unwrap();
+            .execute(&mut *tx)
+            .await?;
 
             for team in teams {
                 let _inserted_teams = sqlx::query!(
@@ -858,14 +1285,15 @@ impl ResourcesMutation {
                     team,
                     project.id,
                 )
-                .execute(&*plexo_engine.pool)
-                .await
-                .unwrap();
+                .execute(&mut *tx)
+                .await?;
             }
         }
 
+        tx.commit().await?;
+
         let subscription_manager: &crate::system::subscriptions::SubscriptionManager =
-            &ctx.data::<Engine>().unwrap().subscription_manager;
+            &ctx.data::<Engine>()?.subscription_manager;
 
         let project = Project {
             id: project.id,
@@ -885,7 +1313,7 @@ impl ResourcesMutation {
         subscription_manager
             .send_project_event(project.clone())
             .await
-            .unwrap();
+            .ok();
 
         plexo_engine
             .record_activity(
@@ -894,8 +1322,7 @@ impl ResourcesMutation {
                 project.id,
                 member_id,
             )
-            .await
-            .unwrap();
+            .await;
 
         Ok(project)
     }
@@ -903,54 +1330,23 @@ impl ResourcesMutation {
     async fn delete_project(&self, ctx: &Context<'_>, id: Uuid) -> Result<Project> {
         let (plexo_engine, member_id) = extract_context(ctx)?;
 
+        // Members, teams and tasks stay linked until the project is purged from the trash.
         let project = sqlx::query!(
             r#"
-            DELETE FROM projects
-            WHERE id = $1
+            UPDATE projects
+            SET deleted_at = NOW(), deleted_by = $2
+            WHERE id = $1 AND deleted_at IS NULL
             RETURNING *
             "#,
             id,
+            member_id,
         )
-        .fetch_one(&*plexo_engine.pool)
-        .await
-        .unwrap();
-
-        let _deleted_members = sqlx::query!(
-            r#"
-            DELETE FROM members_by_projects
-            WHERE project_id = $1
-            "#,
-            id,
-        )
-        .execute(&*plexo_engine.pool)
-        .await
-        .unwrap();
-
-        let _deleted_teams = sqlx::query!(
-            r#"
-            DELETE FROM teams_by_projects
-            WHERE project_id = $1
-            "#,
-            id,
-        )
-        .execute(&*plexo_engine.pool)
-        .await
-        .unwrap();
-
-        let _deleted_tasks = sqlx::query!(
-            r#"
-            UPDATE tasks
-            SET project_id = NULL
-            WHERE project_id = $1
-            "#,
-            id,
-        )
-        .execute(&*plexo_engine.pool)
-        .await
-        .unwrap();
+        .fetch_optional(&*plexo_engine.pool)
+        .await?
+        .ok_or_else(|| PlexoAppError::not_found("Project", id))?;
 
         let subscription_manager: &crate::system::subscriptions::SubscriptionManager =
-            &ctx.data::<Engine>().unwrap().subscription_manager;
+            &ctx.data::<Engine>()?.subscription_manager;
 
         let project = Project {
             id: project.id,
@@ -970,7 +1366,7 @@ impl ResourcesMutation {
         subscription_manager
             .send_project_event(project.clone())
             .await
-            .unwrap();
+            .ok();
 
         plexo_engine
             .record_activity(
@@ -979,8 +1375,7 @@ impl ResourcesMutation {
                 project.id,
                 member_id,
             )
-            .await
-            .unwrap();
+            .await;
 
         Ok(project)
     }
@@ -989,13 +1384,25 @@ impl ResourcesMutation {
         &self,
         ctx: &Context<'_>,
         name: String,
-        visibility: Option<String>,
+        visibility: Option<TeamVisibility>,
         prefix: Option<String>,
         members: Option<Vec<Uuid>>,
         projects: Option<Vec<Uuid>>,
     ) -> Result<Team> {
         let (plexo_engine, member_id) = extract_context(ctx)?;
 
+        let mut validator = InputValidator::new();
+
+        validator
+            .required_text("name", &name, NAME_MAX_LENGTH)
+            .prefix("prefix", prefix.as_deref());
+
+        validate_team_references(&mut validator, &plexo_engine, &members, &projects).await?;
+
+        validator.finish()?;
+
+        let mut tx = plexo_engine.pool.begin().await?;
+
         let team = sqlx::query!(
             r#"
             INSERT INTO teams (name, owner_id, visibility, prefix)
@@ -1004,12 +1411,11 @@ impl ResourcesMutation {
             "#,
             name,
             member_id,
-            visibility,
+            visibility.map(|v| v.to_str()),
             prefix,
         )
-        .fetch_one(&*plexo_engine.pool)
-        .await
-        .unwrap();
+        .fetch_one(&mut *tx)
+        .await?;
 
         if let Some(members) = members {
             for member in members {
@@ -1021,9 +1427,8 @@ impl ResourcesMutation {
                     member,
                     team.id,
                 )
-                .execute(&*plexo_engine.pool)
-                .await
-                .unwrap();
+                .execute(&mut *tx)
+                .await?;
             }
         }
 
@@ -1037,14 +1442,15 @@ impl ResourcesMutation {
                     team.id,
                     project,
                 )
-                .execute(&*plexo_engine.pool)
-                .await
-                .unwrap();
+                .execute(&mut *tx)
+                .await?;
             }
         }
 
+        tx.commit().await?;
+
         let subscription_manager: &crate::system::subscriptions::SubscriptionManager =
-            &ctx.data::<Engine>().unwrap().subscription_manager;
+            &ctx.data::<Engine>()?.subscription_manager;
 
         let team = Team {
             id: team.id,
@@ -1059,7 +1465,7 @@ impl ResourcesMutation {
         subscription_manager
             .send_team_event(team.clone())
             .await
-            .unwrap();
+            .ok();
 
         plexo_engine
             .record_activity(
@@ -1068,8 +1474,7 @@ impl ResourcesMutation {
                 team.id,
                 member_id,
             )
-            .await
-            .unwrap();
+            .await;
 
         Ok(team)
     }
@@ -1080,13 +1485,29 @@ impl ResourcesMutation {
         id: Uuid,
         name: Option<String>,
         owner_id: Option<Uuid>,
-        visibility: Option<String>,
+        visibility: Option<TeamVisibility>,
         prefix: Option<String>,
         members: Option<Vec<Uuid>>,
         projects: Option<Vec<Uuid>>,
     ) -> Result<Team> {
         let (plexo_engine, member_id) = extract_context(ctx)?;
 
+        let mut validator = InputValidator::new();
+
+        validator
+            .optional_required_text("name", name.as_deref(), NAME_MAX_LENGTH)
+            .prefix("prefix", prefix.as_deref());
+
+        validator
+            .exists(&plexo_engine, "ownerId", Reference::Member, owner_id)
+            .await?;
+
+        validate_team_references(&mut validator, &plexo_engine, &members, &projects).await?;
+
+        validator.finish()?;
+
+        let mut tx = plexo_engine.pool.begin().await?;
+
         let team = sqlx::query!(
             r#"
             UPDATE teams
@@ -1100,13 +1521,13 @@ impl ResourcesMutation {
             "#,
             name,
             owner_id,
-            visibility,
+            visibility.map(|v| v.to_str()),
             prefix,
             id,
         )
-        .fetch_one(&*plexo_engine.pool)
-        .await
-        .unwrap();
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| PlexoAppError::not_found("Team", id))?;
 
         if let Some(members) = members {
             let _deleted_members = sqlx::query!(
@@ -1116,9 +1537,8 @@ impl ResourcesMutation {
                     "#,
                 id,
             )
-            .execute(&*plexo_engine.pool)
-            .await
-            .unwrap();
+            .execute(&mut *tx)
+            .await?;
 
             for member in members {
                 let _inserted_members = sqlx::query!(
@@ -1129,9 +1549,8 @@ impl ResourcesMutation {
                     member,
                     team.id,
                 )
-                .execute(&*plexo_engine.pool)
-                .await
-                .unwrap();
+                .execute(&mut *tx)
+                .await?;
             }
         }
 
@@ -1143,9 +1562,8 @@ impl ResourcesMutation {
                     "#,
                 id,
             )
-            .execute(&*plexo_engine.pool)
-            .await
-            .unwrap();
+            .execute(&mut *tx)
+            .await?;
 
             for project in projects {
                 let _inserted_projects = sqlx::query!(
@@ -1156,14 +1574,12 @@ impl ResourcesMutation {
                     team.id,
                     project,
                 )
-                .execute(&*plexo_engine.pool)
-                .await
-                .unwrap();
+                .execute(&mut *tx)
+                .await?;
             }
         }
 
-        let subscription_manager: &crate::system::subscriptions::SubscriptionManager =
-            &ctx.data::<Engine>().unwrap().subscription_manager;
+        tx.commit().await?;
 
         let team = Team {
             id: team.id,
@@ -1175,10 +1591,11 @@ impl ResourcesMutation {
             prefix: team.prefix.clone(),
         };
 
-        subscription_manager
+        plexo_engine
+            .subscription_manager
             .send_team_event(team.clone())
             .await
-            .unwrap();
+            .ok();
 
         plexo_engine
             .record_activity(
@@ -1187,8 +1604,7 @@ impl ResourcesMutation {
                 team.id,
                 member_id,
             )
-            .await
-            .unwrap();
+            .await;
 
         Ok(team)
     }
@@ -1196,42 +1612,23 @@ impl ResourcesMutation {
     async fn delete_team(&self, ctx: &Context<'_>, id: Uuid) -> Result<Team> {
         let (plexo_engine, member_id) = extract_context(ctx)?;
 
+        // Members and projects stay linked until the team is purged from the trash.
         let team = sqlx::query!(
             r#"
-            DELETE FROM teams
-            WHERE id = $1
+            UPDATE teams
+            SET deleted_at = NOW(), deleted_by = $2
+            WHERE id = $1 AND deleted_at IS NULL
             RETURNING *
             "#,
             id,
+            member_id,
         )
-        .fetch_one(&*plexo_engine.pool)
-        .await
-        .unwrap();
-
-        let _delete_team_members = sqlx::query!(
-            r#"
-            DELETE FROM members_by_teams
-            WHERE team_id = $1
-            "#,
-            id,
-        )
-        .execute(&*plexo_engine.pool)
-        .await
-        .unwrap();
-
-        let _delete_team_projects = sqlx::query!(
-            r#"
-            DELETE FROM teams_by_projects
-            WHERE team_id = $1
-            "#,
-            id,
-        )
-        .execute(&*plexo_engine.pool)
-        .await
-        .unwrap();
+        .fetch_optional(&*plexo_engine.pool)
+        .await?
+        .ok_or_else(|| PlexoAppError::not_found("Team", id))?;
 
         let subscription_manager: &crate::system::subscriptions::SubscriptionManager =
-            &ctx.data::<Engine>().unwrap().subscription_manager;
+            &ctx.data::<Engine>()?.subscription_manager;
 
         let team = Team {
             id: team.id,
@@ -1246,7 +1643,7 @@ impl ResourcesMutation {
         subscription_manager
             .send_team_event(team.clone())
             .await
-            .unwrap();
+            .ok();
 
         plexo_engine
             .record_activity(
@@ -1255,8 +1652,7 @@ impl ResourcesMutation {
                 team.id,
                 member_id,
             )
-            .await
-            .unwrap();
+            .await;
 
         Ok(team)
     }
@@ -1270,6 +1666,8 @@ impl ResourcesMutation {
     ) -> Result<Label> {
         let (plexo_engine, member_id) = extract_context(ctx)?;
 
+        validate_label(Some(&name), description.as_deref(), color.as_deref())?;
+
         let label = sqlx::query!(
             r#"
             INSERT INTO labels (name, description, color)
@@ -1281,8 +1679,7 @@ impl ResourcesMutation {
             color,
         )
         .fetch_one(&*plexo_engine.pool)
-        .await
-        .unwrap();
+        .await?;
 
         plexo_engine
             .record_activity(
@@ -1291,8 +1688,7 @@ impl ResourcesMutation {
                 label.id,
                 member_id,
             )
-            .await
-            .unwrap();
+            .await;
 
         Ok(Label {
             id: label.id,
@@ -1314,10 +1710,15 @@ impl ResourcesMutation {
     ) -> Result<Label> {
         let (plexo_engine, member_id) = extract_context(ctx)?;
 
+        validate_label(name.as_deref(), description.as_deref(), color.as_deref())?;
+
         let label = sqlx::query!(
             r#"
             UPDATE labels
-            SET name = $1, description = $2, color = $3
+            SET
+                name = COALESCE($1, name),
+                description = COALESCE($2, description),
+                color = COALESCE($3, color)
             WHERE id = $4
             RETURNING *
             "#,
@@ -1326,9 +1727,9 @@ impl ResourcesMutation {
             color,
             id,
         )
-        .fetch_one(&*plexo_engine.pool)
-        .await
-        .unwrap();
+        .fetch_optional(&*plexo_engine.pool)
+        .await?
+        .ok_or_else(|| PlexoAppError::not_found("Label", id))?;
 
         plexo_engine
             .record_activity(
@@ -1337,8 +1738,7 @@ impl ResourcesMutation {
                 label.id,
                 member_id,
             )
-            .await
-            .unwrap();
+            .await;
 
         Ok(Label {
             id: label.id,
@@ -1361,9 +1761,9 @@ impl ResourcesMutation {
             "#,
             id,
         )
-        .fetch_one(&*plexo_engine.pool)
-        .await
-        .unwrap();
+        .fetch_optional(&*plexo_engine.pool)
+        .await?
+        .ok_or_else(|| PlexoAppError::not_found("Label", id))?;
 
         let _deleted_labels = sqlx::query!(
             r#"
@@ -1373,8 +1773,7 @@ impl ResourcesMutation {
             id,
         )
         .execute(&*plexo_engine.pool)
-        .await
-        .unwrap();
+        .await?;
 
         plexo_engine
             .record_activity(
@@ -1383,8 +1782,7 @@ impl ResourcesMutation {
                 label.id,
                 member_id,
             )
-            .await
-            .unwrap();
+            .await;
 
         Ok(Label {
             id: label.id,
@@ -1405,13 +1803,23 @@ impl ResourcesMutation {
     ) -> Result<Member> {
         let (plexo_engine, member_id) = extract_context(ctx)?;
 
-        if email.is_some()
-            && plexo_engine
-                .get_member_by_email(email.clone().unwrap())
+        let mut validator = InputValidator::new();
+
+        validator
+            .optional_required_text("name", name.as_deref(), NAME_MAX_LENGTH)
+            .email("email", email.as_deref())
+            .text("photoUrl", photo_url.as_deref(), DESCRIPTION_MAX_LENGTH);
+
+        validator.finish()?;
+
+        if let Some(email) = &email {
+            if plexo_engine
+                .get_member_by_email(email.clone())
                 .await
                 .is_some()
-        {
-            return Err(PlexoAppError::EmailAlreadyInUse.into());
+            {
+                return Err(PlexoAppError::EmailAlreadyInUse.into());
+            }
         }
 
         let profile = sqlx::query!(
@@ -1432,8 +1840,7 @@ impl ResourcesMutation {
             member_id,
         )
         .fetch_one(&*plexo_engine.pool)
-        .await
-        .unwrap();
+        .await?;
 
         plexo_engine
             .record_activity(
@@ -1442,8 +1849,7 @@ impl ResourcesMutation {
                 profile.id,
                 member_id,
             )
-            .await
-            .unwrap();
+            .await;
 
         Ok(Member {
             id: profile.id,
@@ -1475,8 +1881,7 @@ impl ResourcesMutation {
             member_id,
         )
         .fetch_one(&*plexo_engine.pool)
-        .await
-        .unwrap();
+        .await?;
 
         let password_hash = member.password_hash.unwrap_or("".to_string());
 
@@ -1492,7 +1897,10 @@ impl ResourcesMutation {
             return Err(PlexoAppError::InvalidPassword.into());
         }
 
-        let new_password_hash = plexo_engine.auth.hash_password(&new_password);
+        let new_password_hash = plexo_engine
+            .auth
+            .hash_password(&new_password)
+            .map_err(|e| PlexoAppError::Internal(e.to_string()))?;
 
         let r = sqlx::query!(
             r#"
@@ -1505,8 +1913,7 @@ impl ResourcesMutation {
             member_id,
         )
         .fetch_one(&*plexo_engine.pool)
-        .await
-        .unwrap();
+        .await?;
 
         plexo_engine
             .record_activity(
@@ -1515,8 +1922,7 @@ impl ResourcesMutation {
                 member.id,
                 member_id,
             )
-            .await
-            .unwrap();
+            .await;
 
         Ok(Member {
             id: r.id,
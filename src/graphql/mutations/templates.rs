@@ -0,0 +1,291 @@
+use async_graphql::{dataloader::DataLoader, Context, Object};
+use uuid::Uuid;
+
+use crate::{
+    errors::{definitions::PlexoAppError, extensions::Result},
+    graphql::{
+        auth::extract_context,
+        validation::{
+            InputValidator, Reference, DESCRIPTION_MAX_LENGTH, NAME_MAX_LENGTH, TITLE_MAX_LENGTH,
+        },
+    },
+    sdk::{
+        activity::{ActivityOperationType, ActivityResourceType},
+        loaders::{TaskLoader, TaskTemplateLoader},
+        member::MemberRole,
+        task::Task,
+        task_template::{TaskTemplate, TaskTemplateOverrides},
+    },
+    system::core::Engine,
+};
+
+#[derive(Default)]
+pub struct TemplatesMutation;
+
+/// Templates can be changed by their owner and by admins.
+async fn ensure_can_edit_template(
+    plexo_engine: &Engine,
+    template: &TaskTemplate,
+    member_id: Uuid,
+) -> Result<()> {
+    if template.owner_id == member_id {
+        return Ok(());
+    }
+
+    match plexo_engine.get_member_by_id(member_id).await {
+        Some(member) if member.role == MemberRole::Admin => Ok(()),
+        _ => Err(PlexoAppError::Forbidden.into()),
+    }
+}
+
+#[Object]
+impl TemplatesMutation {
+    /// Saves a task with its subtasks, labels, assignees and description as a template. The
+    /// template uses the task's project unless `projectId` is given.
+    async fn create_task_template(
+        &self,
+        ctx: &Context<'_>,
+        task_id: Uuid,
+        name: String,
+        description: Option<String>,
+        project_id: Option<Uuid>,
+    ) -> Result<TaskTemplate> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        let mut validator = InputValidator::new();
+
+        validator
+            .required_text("name", &name, NAME_MAX_LENGTH)
+            .text(
+                "description",
+                description.as_deref(),
+                DESCRIPTION_MAX_LENGTH,
+            );
+
+        validator.finish()?;
+
+        let task_loader = ctx.data::<DataLoader<TaskLoader>>()?;
+        let task = task_loader
+            .load_one(task_id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("Task", task_id))?;
+
+        let tree = plexo_engine.build_template_tree(task_id).await?;
+
+        let template = sqlx::query!(
+            r#"
+            INSERT INTO task_templates (owner_id, project_id, name, description, tree)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id
+            "#,
+            member_id,
+            project_id.or(task.project_id),
+            name,
+            description,
+            serde_json::to_value(tree)?,
+        )
+        .fetch_one(&*plexo_engine.pool)
+        .await?;
+
+        plexo_engine
+            .record_activity(
+                ActivityOperationType::Create,
+                ActivityResourceType::TaskTemplate,
+                template.id,
+                member_id,
+            )
+            .await;
+
+        let loader = ctx.data::<DataLoader<TaskTemplateLoader>>()?;
+
+        Ok(loader
+            .load_one(template.id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("TaskTemplate", template.id))?)
+    }
+
+    async fn update_task_template(
+        &self,
+        ctx: &Context<'_>,
+        id: Uuid,
+        name: Option<String>,
+        description: Option<String>,
+        project_id: Option<Uuid>,
+    ) -> Result<TaskTemplate> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        let mut validator = InputValidator::new();
+
+        validator
+            .optional_required_text("name", name.as_deref(), NAME_MAX_LENGTH)
+            .text(
+                "description",
+                description.as_deref(),
+                DESCRIPTION_MAX_LENGTH,
+            );
+
+        validator.finish()?;
+
+        let loader = ctx.data::<DataLoader<TaskTemplateLoader>>()?;
+        let template = loader
+            .load_one(id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("TaskTemplate", id))?;
+
+        ensure_can_edit_template(&plexo_engine, &template, member_id).await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE task_templates
+            SET
+                name = COALESCE($1, name),
+                description = COALESCE($2, description),
+                project_id = COALESCE($3, project_id)
+            WHERE id = $4
+            "#,
+            name,
+            description,
+            project_id,
+            id,
+        )
+        .execute(&*plexo_engine.pool)
+        .await?;
+
+        plexo_engine
+            .record_activity(
+                ActivityOperationType::Update,
+                ActivityResourceType::TaskTemplate,
+                id,
+                member_id,
+            )
+            .await;
+
+        Ok(loader
+            .load_one(id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("TaskTemplate", id))?)
+    }
+
+    async fn delete_task_template(&self, ctx: &Context<'_>, id: Uuid) -> Result<TaskTemplate> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<TaskTemplateLoader>>()?;
+        let template = loader
+            .load_one(id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("TaskTemplate", id))?;
+
+        ensure_can_edit_template(&plexo_engine, &template, member_id).await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM task_templates
+            WHERE id = $1
+            "#,
+            id,
+        )
+        .execute(&*plexo_engine.pool)
+        .await?;
+
+        plexo_engine
+            .record_activity(
+                ActivityOperationType::Delete,
+                ActivityResourceType::TaskTemplate,
+                id,
+                member_id,
+            )
+            .await;
+
+        Ok(template)
+    }
+
+    /// Creates the task tree of a template in a single transaction and returns the root task.
+    async fn create_task_from_template(
+        &self,
+        ctx: &Context<'_>,
+        template_id: Uuid,
+        overrides: Option<TaskTemplateOverrides>,
+    ) -> Result<Task> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<TaskTemplateLoader>>()?;
+        let template = loader
+            .load_one(template_id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("TaskTemplate", template_id))?;
+
+        let overrides = overrides.unwrap_or_default();
+
+        let mut validator = InputValidator::new();
+
+        validator
+            .optional_required_text(
+                "overrides.title",
+                overrides.title.as_deref(),
+                TITLE_MAX_LENGTH,
+            )
+            .text(
+                "overrides.description",
+                overrides.description.as_deref(),
+                DESCRIPTION_MAX_LENGTH,
+            );
+
+        validator
+            .exists(
+                &plexo_engine,
+                "overrides.projectId",
+                Reference::Project,
+                overrides.project_id,
+            )
+            .await?;
+        validator
+            .exists(
+                &plexo_engine,
+                "overrides.leadId",
+                Reference::Member,
+                overrides.lead_id,
+            )
+            .await?;
+        validator
+            .exists(
+                &plexo_engine,
+                "overrides.parentId",
+                Reference::Task,
+                overrides.parent_id,
+            )
+            .await?;
+        validator
+            .exists(
+                &plexo_engine,
+                "overrides.labelIds",
+                Reference::Label,
+                overrides.label_ids.iter().flatten().copied(),
+            )
+            .await?;
+        validator
+            .exists(
+                &plexo_engine,
+                "overrides.assigneeIds",
+                Reference::Member,
+                overrides.assignee_ids.iter().flatten().copied(),
+            )
+            .await?;
+
+        validator.finish()?;
+
+        let created = plexo_engine
+            .instantiate_task_template(&template, overrides, member_id)
+            .await?;
+
+        let root_id = *created
+            .first()
+            .ok_or_else(|| PlexoAppError::validation("templateId", "Template has no tasks"))?;
+
+        let task_loader = ctx.data::<DataLoader<TaskLoader>>()?;
+
+        Ok(task_loader
+            .load_one(root_id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("Task", root_id))?)
+    }
+}
@@ -0,0 +1,169 @@
+use async_graphql::{dataloader::DataLoader, Context, Object};
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+use crate::{
+    errors::{definitions::PlexoAppError, extensions::Result},
+    graphql::{
+        auth::extract_context,
+        validation::{InputValidator, DESCRIPTION_MAX_LENGTH},
+    },
+    sdk::{
+        activity::{ActivityOperationType, ActivityResourceType},
+        loaders::TimeEntryLoader,
+        time_entry::TimeEntry,
+        utilities::DateTimeBridge,
+    },
+};
+
+#[derive(Default)]
+pub struct TimeTrackingMutation;
+
+#[Object]
+impl TimeTrackingMutation {
+    /// Starts a timer on a task for the signed-in member.
+    async fn start_timer(
+        &self,
+        ctx: &Context<'_>,
+        task_id: Uuid,
+        note: Option<String>,
+    ) -> Result<TimeEntry> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        let mut validator = InputValidator::new();
+
+        validator.text("note", note.as_deref(), DESCRIPTION_MAX_LENGTH);
+        validator.finish()?;
+
+        // The partial unique index on running timers turns a concurrent start into a conflict.
+        let entry = sqlx::query!(
+            r#"
+            INSERT INTO time_entries (task_id, member_id, started_at, note)
+            VALUES ($1, $2, NOW(), $3)
+            ON CONFLICT (member_id) WHERE ended_at IS NULL DO NOTHING
+            RETURNING id
+            "#,
+            task_id,
+            member_id,
+            note,
+        )
+        .fetch_optional(&*plexo_engine.pool)
+        .await?
+        .ok_or(PlexoAppError::TimerAlreadyRunning)?;
+
+        plexo_engine
+            .record_activity(
+                ActivityOperationType::Create,
+                ActivityResourceType::TimeEntry,
+                entry.id,
+                member_id,
+            )
+            .await;
+
+        let loader = ctx.data::<DataLoader<TimeEntryLoader>>()?;
+
+        Ok(loader
+            .load_one(entry.id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("TimeEntry", entry.id))?)
+    }
+
+    /// Stops the running timer of the signed-in member.
+    async fn stop_timer(&self, ctx: &Context<'_>, note: Option<String>) -> Result<TimeEntry> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        let mut validator = InputValidator::new();
+
+        validator.text("note", note.as_deref(), DESCRIPTION_MAX_LENGTH);
+        validator.finish()?;
+
+        let entry = sqlx::query!(
+            r#"
+            UPDATE time_entries
+            SET
+                ended_at = NOW(),
+                note = COALESCE($2, note)
+            WHERE member_id = $1 AND ended_at IS NULL
+            RETURNING id
+            "#,
+            member_id,
+            note,
+        )
+        .fetch_optional(&*plexo_engine.pool)
+        .await?
+        .ok_or(PlexoAppError::NoRunningTimer)?;
+
+        plexo_engine
+            .record_activity(
+                ActivityOperationType::Update,
+                ActivityResourceType::TimeEntry,
+                entry.id,
+                member_id,
+            )
+            .await;
+
+        let loader = ctx.data::<DataLoader<TimeEntryLoader>>()?;
+
+        Ok(loader
+            .load_one(entry.id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("TimeEntry", entry.id))?)
+    }
+
+    /// Records time spent on a task without running a timer. `duration` is in seconds and the
+    /// entry ends now unless `startedAt` is given.
+    async fn log_time(
+        &self,
+        ctx: &Context<'_>,
+        task_id: Uuid,
+        duration: i32,
+        started_at: Option<DateTime<Utc>>,
+        note: Option<String>,
+    ) -> Result<TimeEntry> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        let mut validator = InputValidator::new();
+
+        if duration <= 0 {
+            validator.error("duration", "must be positive");
+        }
+
+        validator.text("note", note.as_deref(), DESCRIPTION_MAX_LENGTH);
+        validator.finish()?;
+
+        let started_at =
+            started_at.unwrap_or_else(|| Utc::now() - Duration::seconds(duration as i64));
+        let ended_at = started_at + Duration::seconds(duration as i64);
+
+        let entry = sqlx::query!(
+            r#"
+            INSERT INTO time_entries (task_id, member_id, started_at, ended_at, note)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id
+            "#,
+            task_id,
+            member_id,
+            DateTimeBridge::from_date_time(started_at),
+            DateTimeBridge::from_date_time(ended_at),
+            note,
+        )
+        .fetch_one(&*plexo_engine.pool)
+        .await?;
+
+        plexo_engine
+            .record_activity(
+                ActivityOperationType::Create,
+                ActivityResourceType::TimeEntry,
+                entry.id,
+                member_id,
+            )
+            .await;
+
+        let loader = ctx.data::<DataLoader<TimeEntryLoader>>()?;
+
+        Ok(loader
+            .load_one(entry.id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("TimeEntry", entry.id))?)
+    }
+}
@@ -0,0 +1,140 @@
+use async_graphql::{dataloader::DataLoader, Context, Object};
+use uuid::Uuid;
+
+use crate::{
+    errors::{definitions::PlexoAppError, extensions::Result},
+    graphql::auth::extract_context,
+    sdk::{
+        activity::{ActivityOperationType, ActivityResourceType},
+        loaders::{ProjectLoader, TaskLoader, TeamLoader},
+        project::Project,
+        task::Task,
+        team::Team,
+    },
+};
+
+#[derive(Default)]
+pub struct TrashMutation;
+
+#[Object]
+impl TrashMutation {
+    /// Restores a deleted task with its labels, assignees and the subtasks deleted with it.
+    async fn restore_task(&self, ctx: &Context<'_>, id: Uuid) -> Result<Task> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        let restored = plexo_engine.restore_task(id).await?;
+
+        if restored.is_empty() {
+            return Err(PlexoAppError::not_found("Task", id).into());
+        }
+
+        let loader = ctx.data::<DataLoader<TaskLoader>>()?;
+        let tasks = loader.load_many(restored.clone()).await?;
+
+        for task_id in restored {
+            if let Some(task) = tasks.get(&task_id) {
+                plexo_engine
+                    .subscription_manager
+                    .send_task_event(task.clone())
+                    .await
+                    .ok();
+            }
+
+            plexo_engine
+                .record_activity(
+                    ActivityOperationType::Restore,
+                    ActivityResourceType::Task,
+                    task_id,
+                    member_id,
+                )
+                .await;
+        }
+
+        Ok(tasks
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| PlexoAppError::not_found("Task", id))?)
+    }
+
+    /// Restores a deleted project with its members, teams and tasks.
+    async fn restore_project(&self, ctx: &Context<'_>, id: Uuid) -> Result<Project> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        sqlx::query!(
+            r#"
+            UPDATE projects
+            SET deleted_at = NULL, deleted_by = NULL
+            WHERE id = $1 AND deleted_at IS NOT NULL
+            RETURNING id
+            "#,
+            id,
+        )
+        .fetch_optional(&*plexo_engine.pool)
+        .await?
+        .ok_or_else(|| PlexoAppError::not_found("Project", id))?;
+
+        let loader = ctx.data::<DataLoader<ProjectLoader>>()?;
+        let project = loader
+            .load_one(id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("Project", id))?;
+
+        plexo_engine
+            .subscription_manager
+            .send_project_event(project.clone())
+            .await
+            .ok();
+
+        plexo_engine
+            .record_activity(
+                ActivityOperationType::Restore,
+                ActivityResourceType::Project,
+                project.id,
+                member_id,
+            )
+            .await;
+
+        Ok(project)
+    }
+
+    /// Restores a deleted team with its members and projects.
+    async fn restore_team(&self, ctx: &Context<'_>, id: Uuid) -> Result<Team> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        sqlx::query!(
+            r#"
+            UPDATE teams
+            SET deleted_at = NULL, deleted_by = NULL
+            WHERE id = $1 AND deleted_at IS NOT NULL
+            RETURNING id
+            "#,
+            id,
+        )
+        .fetch_optional(&*plexo_engine.pool)
+        .await?
+        .ok_or_else(|| PlexoAppError::not_found("Team", id))?;
+
+        let loader = ctx.data::<DataLoader<TeamLoader>>()?;
+        let team = loader
+            .load_one(id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("Team", id))?;
+
+        plexo_engine
+            .subscription_manager
+            .send_team_event(team.clone())
+            .await
+            .ok();
+
+        plexo_engine
+            .record_activity(
+                ActivityOperationType::Restore,
+                ActivityResourceType::Team,
+                team.id,
+                member_id,
+            )
+            .await;
+
+        Ok(team)
+    }
+}
@@ -0,0 +1,194 @@
+use async_graphql::{dataloader::DataLoader, Context, Object};
+use uuid::Uuid;
+
+use crate::{
+    errors::{definitions::PlexoAppError, extensions::Result},
+    graphql::{
+        auth::extract_context,
+        validation::{InputValidator, NAME_MAX_LENGTH},
+    },
+    sdk::{
+        loaders::{WebhookDeliveryLoader, WebhookLoader},
+        member::MemberRole,
+        webhook::{Webhook, WebhookDelivery},
+    },
+    system::{core::Engine, webhooks::is_event_filter},
+};
+
+#[derive(Default)]
+pub struct WebhooksMutation;
+
+/// Webhooks are managed by admins only, since they see all the activity.
+pub async fn ensure_can_manage_webhooks(plexo_engine: &Engine, member_id: Uuid) -> Result<()> {
+    match plexo_engine.get_member_by_id(member_id).await {
+        Some(member) if member.role == MemberRole::Admin => Ok(()),
+        _ => Err(PlexoAppError::Forbidden.into()),
+    }
+}
+
+fn validate_events(validator: &mut InputValidator, events: &[String]) {
+    if events.is_empty() {
+        validator.error("events", "can't be empty");
+    }
+
+    for (i, event) in events.iter().enumerate() {
+        if !is_event_filter(event) {
+            validator.error(
+                format!("events.{}", i),
+                "must be an event such as task.created, a wildcard such as task.* or *",
+            );
+        }
+    }
+}
+
+#[Object]
+impl WebhooksMutation {
+    /// Subscribes a URL to the events matching `events`. Deliveries are signed with `secret`.
+    async fn create_webhook(
+        &self,
+        ctx: &Context<'_>,
+        url: String,
+        secret: String,
+        events: Vec<String>,
+        active: Option<bool>,
+    ) -> Result<Webhook> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        ensure_can_manage_webhooks(&plexo_engine, member_id).await?;
+
+        let mut validator = InputValidator::new();
+
+        validator
+            .url("url", Some(&url))
+            .required_text("secret", &secret, NAME_MAX_LENGTH);
+        validate_events(&mut validator, &events);
+
+        validator.finish()?;
+
+        let webhook = sqlx::query!(
+            r#"
+            INSERT INTO webhooks (owner_id, url, secret, events, active)
+            VALUES ($1, $2, $3, $4, COALESCE($5, true))
+            RETURNING id
+            "#,
+            member_id,
+            url,
+            secret,
+            &events,
+            active,
+        )
+        .fetch_one(&*plexo_engine.pool)
+        .await?;
+
+        let loader = ctx.data::<DataLoader<WebhookLoader>>()?;
+
+        Ok(loader
+            .load_one(webhook.id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("Webhook", webhook.id))?)
+    }
+
+    async fn update_webhook(
+        &self,
+        ctx: &Context<'_>,
+        id: Uuid,
+        url: Option<String>,
+        secret: Option<String>,
+        events: Option<Vec<String>>,
+        active: Option<bool>,
+    ) -> Result<Webhook> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        ensure_can_manage_webhooks(&plexo_engine, member_id).await?;
+
+        let mut validator = InputValidator::new();
+
+        validator.url("url", url.as_deref()).optional_required_text(
+            "secret",
+            secret.as_deref(),
+            NAME_MAX_LENGTH,
+        );
+
+        if let Some(events) = &events {
+            validate_events(&mut validator, events);
+        }
+
+        validator.finish()?;
+
+        sqlx::query!(
+            r#"
+            UPDATE webhooks
+            SET
+                url = COALESCE($2, url),
+                secret = COALESCE($3, secret),
+                events = COALESCE($4, events),
+                active = COALESCE($5, active)
+            WHERE id = $1
+            RETURNING id
+            "#,
+            id,
+            url,
+            secret,
+            events.as_deref(),
+            active,
+        )
+        .fetch_optional(&*plexo_engine.pool)
+        .await?
+        .ok_or_else(|| PlexoAppError::not_found("Webhook", id))?;
+
+        let loader = ctx.data::<DataLoader<WebhookLoader>>()?;
+
+        Ok(loader
+            .load_one(id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("Webhook", id))?)
+    }
+
+    /// Deletes a webhook with its delivery log.
+    async fn delete_webhook(&self, ctx: &Context<'_>, id: Uuid) -> Result<Webhook> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        ensure_can_manage_webhooks(&plexo_engine, member_id).await?;
+
+        let loader = ctx.data::<DataLoader<WebhookLoader>>()?;
+        let webhook = loader
+            .load_one(id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("Webhook", id))?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM webhooks
+            WHERE id = $1
+            "#,
+            id,
+        )
+        .execute(&*plexo_engine.pool)
+        .await?;
+
+        Ok(webhook)
+    }
+
+    /// Sends the payload of a delivery again as a new delivery, with its own attempts.
+    async fn redeliver_webhook_delivery(
+        &self,
+        ctx: &Context<'_>,
+        id: Uuid,
+    ) -> Result<WebhookDelivery> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        ensure_can_manage_webhooks(&plexo_engine, member_id).await?;
+
+        let delivery_id = plexo_engine
+            .redeliver_webhook_delivery(id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("WebhookDelivery", id))?;
+
+        let loader = ctx.data::<DataLoader<WebhookDeliveryLoader>>()?;
+
+        Ok(loader
+            .load_one(delivery_id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("WebhookDelivery", delivery_id))?)
+    }
+}
@@ -0,0 +1,371 @@
+use std::collections::HashSet;
+
+use async_graphql::{dataloader::DataLoader, Context, Object};
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use crate::{
+    errors::{definitions::PlexoAppError, extensions::Result},
+    graphql::{
+        auth::extract_context,
+        validation::{InputValidator, NAME_MAX_LENGTH},
+    },
+    sdk::{
+        activity::{ActivityOperationType, ActivityResourceType},
+        loaders::WorkflowStateLoader,
+        member::MemberRole,
+        workflow::{WorkflowState, WorkflowStateCategory},
+    },
+    system::{
+        core::Engine,
+        workflows::{
+            count_open_blockers, get_workflow_state_category, is_state_in_project_workflow,
+            is_transition_allowed, resolve_workflow_state,
+        },
+    },
+};
+
+#[derive(Default)]
+pub struct WorkflowsMutation;
+
+/// The default workflow is shared by every project, so only admins can change it.
+async fn ensure_can_edit_workflow(
+    plexo_engine: &Engine,
+    project_id: Option<Uuid>,
+    member_id: Uuid,
+) -> Result<()> {
+    if project_id.is_some() {
+        return Ok(());
+    }
+
+    match plexo_engine.get_member_by_id(member_id).await {
+        Some(member) if member.role == MemberRole::Admin => Ok(()),
+        _ => Err(PlexoAppError::Forbidden.into()),
+    }
+}
+
+async fn get_state_project_id(plexo_engine: &Engine, state_id: Uuid) -> Result<Option<Uuid>> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT project_id FROM workflow_states
+        WHERE id = $1
+        "#,
+        state_id,
+    )
+    .fetch_one(&*plexo_engine.pool)
+    .await?
+    .project_id)
+}
+
+async fn record_workflow_activity(
+    plexo_engine: &Engine,
+    project_id: Option<Uuid>,
+    member_id: Uuid,
+) {
+    if let Some(project_id) = project_id {
+        plexo_engine
+            .record_activity(
+                ActivityOperationType::Update,
+                ActivityResourceType::Project,
+                project_id,
+                member_id,
+            )
+            .await;
+    }
+}
+
+/// Resolves the workflow state a task moves to, from `state_id` or else from the legacy
+/// `status`, and checks that the move is allowed. Started and completed states need the task's
+/// blockers to be finished unless `force` is set. Runs on `conn` so the checks see the rows
+/// locked by the caller's transaction.
+#[allow(clippy::too_many_arguments)]
+pub async fn resolve_task_transition(
+    conn: &mut PgConnection,
+    task_id: Uuid,
+    current_project_id: Option<Uuid>,
+    current_state_id: Option<Uuid>,
+    next_project_id: Option<Uuid>,
+    state_id: Option<Uuid>,
+    status: Option<&str>,
+    force: bool,
+) -> Result<Option<Uuid>> {
+    let next_state_id = match (state_id, status) {
+        (Some(state_id), _) => Some(state_id),
+        (None, Some(status)) => resolve_workflow_state(conn, next_project_id, status).await?,
+        (None, None) => None,
+    };
+
+    let Some(next_state_id) = next_state_id else {
+        return Ok(None);
+    };
+
+    if !is_state_in_project_workflow(conn, next_state_id, next_project_id).await? {
+        return Err(PlexoAppError::InvalidWorkflowState.into());
+    }
+
+    // Transitions are only enforced inside a workflow, moving projects resets the state.
+    if let Some(current_state_id) = current_state_id {
+        if next_project_id == current_project_id
+            && current_state_id != next_state_id
+            && !is_transition_allowed(conn, current_state_id, next_state_id).await?
+        {
+            return Err(PlexoAppError::TransitionNotAllowed.into());
+        }
+    }
+
+    let next_category = get_workflow_state_category(conn, next_state_id).await?;
+
+    if matches!(
+        next_category,
+        Some(WorkflowStateCategory::Started | WorkflowStateCategory::Completed)
+    ) && !force
+        && count_open_blockers(conn, task_id).await? > 0
+    {
+        return Err(PlexoAppError::TaskBlocked.into());
+    }
+
+    Ok(Some(next_state_id))
+}
+
+#[Object]
+impl WorkflowsMutation {
+    /// Adds a state to a project workflow, or to the default workflow when no project is given.
+    /// The first custom state of a project copies the default workflow into it.
+    async fn create_workflow_state(
+        &self,
+        ctx: &Context<'_>,
+        project_id: Option<Uuid>,
+        name: String,
+        category: WorkflowStateCategory,
+        position: Option<i32>,
+    ) -> Result<WorkflowState> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        let mut validator = InputValidator::new();
+
+        validator.required_text("name", &name, NAME_MAX_LENGTH);
+        validator.finish()?;
+
+        ensure_can_edit_workflow(&plexo_engine, project_id, member_id).await?;
+
+        if let Some(project_id) = project_id {
+            plexo_engine.initialize_project_workflow(project_id).await?;
+        }
+
+        let state = sqlx::query!(
+            r#"
+            INSERT INTO workflow_states (project_id, name, category, position)
+            VALUES (
+                $1,
+                $2,
+                $3,
+                COALESCE(
+                    $4,
+                    (SELECT COALESCE(MAX(position) + 1, 0) FROM workflow_states WHERE project_id IS NOT DISTINCT FROM $1)
+                )
+            )
+            RETURNING id
+            "#,
+            project_id as Option<Uuid>,
+            name,
+            category.to_str(),
+            position,
+        )
+        .fetch_one(&*plexo_engine.pool)
+        .await?;
+
+        record_workflow_activity(&plexo_engine, project_id, member_id).await;
+
+        let loader = ctx.data::<DataLoader<WorkflowStateLoader>>()?;
+
+        Ok(loader
+            .load_one(state.id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("WorkflowState", state.id))?)
+    }
+
+    async fn update_workflow_state(
+        &self,
+        ctx: &Context<'_>,
+        id: Uuid,
+        name: Option<String>,
+        category: Option<WorkflowStateCategory>,
+        position: Option<i32>,
+    ) -> Result<WorkflowState> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        let mut validator = InputValidator::new();
+
+        validator.optional_required_text("name", name.as_deref(), NAME_MAX_LENGTH);
+        validator.finish()?;
+
+        let project_id = get_state_project_id(&plexo_engine, id).await?;
+
+        ensure_can_edit_workflow(&plexo_engine, project_id, member_id).await?;
+
+        let mut tx = plexo_engine.pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE workflow_states
+            SET
+                name = COALESCE($1, name),
+                category = COALESCE($2, category),
+                position = COALESCE($3, position)
+            WHERE id = $4
+            "#,
+            name,
+            category.map(|c| c.to_str()),
+            position,
+            id,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if category.is_some() {
+            // Keeps the legacy status of the tasks in this state in line with the new category.
+            sqlx::query!(
+                r#"
+                UPDATE tasks
+                SET status = workflow_state_task_status(state_id)
+                WHERE state_id = $1
+                "#,
+                id,
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        record_workflow_activity(&plexo_engine, project_id, member_id).await;
+
+        let loader = ctx.data::<DataLoader<WorkflowStateLoader>>()?;
+
+        Ok(loader
+            .load_one(id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("WorkflowState", id))?)
+    }
+
+    /// Removes a state, moving its tasks to `replacement_state_id` from the same workflow.
+    async fn delete_workflow_state(
+        &self,
+        ctx: &Context<'_>,
+        id: Uuid,
+        replacement_state_id: Uuid,
+    ) -> Result<WorkflowState> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        let project_id = get_state_project_id(&plexo_engine, id).await?;
+
+        ensure_can_edit_workflow(&plexo_engine, project_id, member_id).await?;
+
+        if replacement_state_id == id
+            || get_state_project_id(&plexo_engine, replacement_state_id).await? != project_id
+        {
+            return Err(PlexoAppError::InvalidWorkflowState.into());
+        }
+
+        let loader = ctx.data::<DataLoader<WorkflowStateLoader>>()?;
+
+        let state = loader
+            .load_one(id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("WorkflowState", id))?;
+
+        let mut tx = plexo_engine.pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE tasks
+            SET state_id = $1
+            WHERE state_id = $2
+            "#,
+            replacement_state_id,
+            id,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM workflow_states
+            WHERE id = $1
+            "#,
+            id,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        record_workflow_activity(&plexo_engine, project_id, member_id).await;
+
+        Ok(state)
+    }
+
+    /// Replaces the states a task in `from_state_id` is allowed to move to.
+    async fn set_workflow_transitions(
+        &self,
+        ctx: &Context<'_>,
+        from_state_id: Uuid,
+        to_state_ids: Vec<Uuid>,
+    ) -> Result<WorkflowState> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        let project_id = get_state_project_id(&plexo_engine, from_state_id).await?;
+
+        ensure_can_edit_workflow(&plexo_engine, project_id, member_id).await?;
+
+        let mut tx = plexo_engine.pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM workflow_transitions
+            WHERE from_state_id = $1
+            "#,
+            from_state_id,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let inserted = sqlx::query!(
+            r#"
+            INSERT INTO workflow_transitions (from_state_id, to_state_id)
+            SELECT $1, id FROM workflow_states
+            WHERE
+                id = ANY($2)
+                AND id <> $1
+                AND project_id IS NOT DISTINCT FROM $3
+            "#,
+            from_state_id,
+            &to_state_ids,
+            project_id as Option<Uuid>,
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        let expected = to_state_ids
+            .iter()
+            .filter(|id| **id != from_state_id)
+            .collect::<HashSet<_>>()
+            .len();
+
+        if inserted as usize != expected {
+            return Err(PlexoAppError::InvalidWorkflowState.into());
+        }
+
+        tx.commit().await?;
+
+        record_workflow_activity(&plexo_engine, project_id, member_id).await;
+
+        let loader = ctx.data::<DataLoader<WorkflowStateLoader>>()?;
+
+        Ok(loader
+            .load_one(from_state_id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("WorkflowState", from_state_id))?)
+    }
+}
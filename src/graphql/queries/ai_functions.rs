@@ -1,6 +1,7 @@
-use async_graphql::{Context, Object, Result};
+use async_graphql::{Context, Object};
 
 use crate::{
+    errors::{definitions::PlexoAppError, extensions::Result},
     graphql::auth::extract_context,
     llm::suggestions::{TaskSuggestionInput, TaskSuggestionResult},
 };
@@ -17,15 +18,10 @@ impl AIFunctionsQuery {
     ) -> Result<TaskSuggestionResult> {
         let (plexo_engine, _member_id) = extract_context(ctx)?;
 
-        let Ok(raw_suggestion) = plexo_engine
+        Ok(plexo_engine
             .auto_suggestions_engine
             .get_suggestions(task, None)
-            .await
-        else {
-            return Err("Failed to get suggestions".into());
-        };
-
-        Ok(raw_suggestion)
+            .await?)
     }
 
     async fn subdivide_task(
@@ -36,13 +32,14 @@ impl AIFunctionsQuery {
     ) -> Result<Vec<TaskSuggestionResult>> {
         let (plexo_engine, _member_id) = extract_context(ctx)?;
 
-        let task_id = task_id.parse::<uuid::Uuid>()?;
+        let task_id = task_id
+            .parse::<uuid::Uuid>()
+            .map_err(|_| PlexoAppError::validation("taskId", "Not a valid id"))?;
 
         let suggestions = plexo_engine
             .auto_suggestions_engine
             .subdivide_task(task_id, subtasks)
-            .await
-            .unwrap();
+            .await?;
 
         // let raw_suggestion = plexo_engine
         //     .auto_suggestions_engine
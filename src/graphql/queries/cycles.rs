@@ -0,0 +1,85 @@
+use async_graphql::{dataloader::DataLoader, Context, Object};
+use uuid::Uuid;
+
+use crate::{
+    errors::extensions::Result,
+    graphql::auth::extract_context,
+    sdk::{
+        cycle::{Cycle, CycleVelocity, TeamVelocity},
+        loaders::CycleLoader,
+    },
+};
+
+#[derive(Default)]
+pub struct CyclesQuery;
+
+#[Object]
+impl CyclesQuery {
+    async fn cycle_by_id(&self, ctx: &Context<'_>, id: Uuid) -> Result<Option<Cycle>> {
+        let (_plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<CycleLoader>>()?;
+
+        Ok(loader.load_one(id).await?)
+    }
+
+    /// Completed points of the team's last `lastN` closed cycles and their average.
+    async fn team_velocity(
+        &self,
+        ctx: &Context<'_>,
+        team_id: Uuid,
+        #[graphql(default = 3)] last_n: i32,
+    ) -> Result<TeamVelocity> {
+        let (plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                cycles.id,
+                COALESCE(SUM(tasks.estimate) FILTER (WHERE workflow_states.category = 'Completed'), 0)::bigint AS "completed_points!"
+            FROM cycles
+            LEFT JOIN tasks ON tasks.cycle_id = cycles.id AND tasks.deleted_at IS NULL
+            LEFT JOIN workflow_states ON workflow_states.id = tasks.state_id
+            WHERE cycles.team_id = $1 AND cycles.closed_at IS NOT NULL
+            GROUP BY cycles.id
+            ORDER BY MAX(cycles.ends_at) DESC
+            LIMIT $2
+            "#,
+            team_id,
+            last_n.clamp(1, 50) as i64,
+        )
+        .fetch_all(&*plexo_engine.pool)
+        .await?;
+
+        let loader = ctx.data::<DataLoader<CycleLoader>>()?;
+
+        let cycles_map = loader
+            .load_many(rows.iter().map(|r| r.id).collect::<Vec<Uuid>>())
+            .await?;
+
+        let cycles: Vec<CycleVelocity> = rows
+            .into_iter()
+            .filter_map(|r| {
+                Some(CycleVelocity {
+                    cycle: cycles_map.get(&r.id)?.clone(),
+                    completed_points: r.completed_points,
+                })
+            })
+            .collect();
+
+        let average_points = if cycles.is_empty() {
+            0.0
+        } else {
+            cycles
+                .iter()
+                .map(|c| c.completed_points as f64)
+                .sum::<f64>()
+                / cycles.len() as f64
+        };
+
+        Ok(TeamVelocity {
+            average_points,
+            cycles,
+        })
+    }
+}
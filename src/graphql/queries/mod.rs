@@ -1,12 +1,33 @@
 use async_graphql::MergedObject;
 
-use self::{ai_functions::AIFunctionsQuery, resources::ResourcesQuery};
+use self::{
+    ai_functions::AIFunctionsQuery, cycles::CyclesQuery, notifications::NotificationsQuery,
+    resources::ResourcesQuery, search::SearchQuery, templates::TemplatesQuery,
+    time_tracking::TimeTrackingQuery, trash::TrashQuery, webhooks::WebhooksQuery,
+};
 
 pub mod ai_functions;
+pub mod cycles;
+pub mod notifications;
 pub mod resources;
+pub mod search;
+pub mod templates;
+pub mod time_tracking;
+pub mod trash;
+pub mod webhooks;
 
 // use self::{auth::AuthMutation, resources::ResourcesMutation};
 
 // use super::{auth_mutation:i:AuthMutation, resources_mutation::ResourcesMutation};
 #[derive(MergedObject, Default)]
-pub struct QueryRoot(ResourcesQuery, AIFunctionsQuery);
+pub struct QueryRoot(
+    ResourcesQuery,
+    AIFunctionsQuery,
+    SearchQuery,
+    TimeTrackingQuery,
+    CyclesQuery,
+    TemplatesQuery,
+    TrashQuery,
+    NotificationsQuery,
+    WebhooksQuery,
+);
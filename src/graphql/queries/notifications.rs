@@ -0,0 +1,86 @@
+use std::str::FromStr;
+
+use async_graphql::{dataloader::DataLoader, Context, Object};
+
+use crate::{
+    errors::{definitions::PlexoAppError, extensions::Result},
+    graphql::auth::extract_context,
+    sdk::{
+        connections::{paginate, PlexoConnection},
+        loaders::NotificationLoader,
+        member::EmailNotificationMode,
+        notification::Notification,
+    },
+};
+
+#[derive(Default)]
+pub struct NotificationsQuery;
+
+#[Object]
+impl NotificationsQuery {
+    /// Notifications of the signed-in member, oldest first. Use `last` to read the newest ones.
+    async fn notifications(
+        &self,
+        ctx: &Context<'_>,
+        unread_only: Option<bool>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> Result<PlexoConnection<Notification>> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<NotificationLoader>>()?;
+
+        paginate(
+            &plexo_engine.pool,
+            loader,
+            "notifications",
+            |query| {
+                query.push("member_id = ").push_bind(member_id);
+
+                if unread_only.unwrap_or(false) {
+                    query.push(" AND read_at IS NULL");
+                }
+            },
+            after,
+            before,
+            first,
+            last,
+        )
+        .await
+    }
+
+    async fn unread_notifications_count(&self, ctx: &Context<'_>) -> Result<i64> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        Ok(sqlx::query!(
+            r#"
+            SELECT COUNT(*) AS "count!" FROM notifications
+            WHERE member_id = $1 AND read_at IS NULL
+            "#,
+            member_id,
+        )
+        .fetch_one(&*plexo_engine.pool)
+        .await?
+        .count)
+    }
+
+    /// How the signed-in member gets emails about assignments, mentions and due dates.
+    async fn email_notifications(&self, ctx: &Context<'_>) -> Result<EmailNotificationMode> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        let member = sqlx::query!(
+            r#"
+            SELECT email_notifications FROM members
+            WHERE id = $1
+            "#,
+            member_id,
+        )
+        .fetch_optional(&*plexo_engine.pool)
+        .await?
+        .ok_or_else(|| PlexoAppError::not_found("Member", member_id))?;
+
+        Ok(EmailNotificationMode::from_str(&member.email_notifications).unwrap_or_default())
+    }
+}
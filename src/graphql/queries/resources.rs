@@ -1,19 +1,28 @@
-use std::str::FromStr;
-
-use async_graphql::{Context, InputObject, Object, Result};
+use async_graphql::{dataloader::DataLoader, Context, Enum, InputObject, Json, Object};
 use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::{Postgres, QueryBuilder};
 use uuid::Uuid;
 
 use crate::{
+    errors::{definitions::PlexoAppError, extensions::Result},
     graphql::auth::extract_context,
     sdk::{
         activity::{Activity, ActivityOperationType, ActivityResourceType},
+        attachment::AttachmentLimits,
+        connections::{paginate, paginate_ordered, PlexoConnection},
+        custom_field::{CustomField, CustomFieldType},
         labels::Label,
+        loaders::{
+            ActivityLoader, CustomFieldLoader, LabelLoader, MemberLoader, ProjectLoader,
+            TaskLoader, TeamLoader, WorkflowStateLoader,
+        },
         member::{Member, MemberRole},
         project::Project,
         task::{Task, TaskPriority, TaskStatus},
         team::{Team, TeamVisibility},
         utilities::DateTimeBridge,
+        workflow::{WorkflowState, WorkflowStateCategory},
     },
 };
 
@@ -22,17 +31,300 @@ use crate::{
 #[derive(Default)]
 pub struct ResourcesQuery;
 
-#[derive(InputObject)]
+#[derive(InputObject, Default)]
 pub struct TaskFilter {
     pub project_id: Option<Uuid>,
     pub lead_id: Option<Uuid>,
+    pub owner_id: Option<Uuid>,
+    pub assignee_id: Option<Uuid>,
+    pub label_id: Option<Uuid>,
+    pub parent_id: Option<Uuid>,
+    pub cycle_id: Option<Uuid>,
+    pub milestone_id: Option<Uuid>,
+    pub top_level_only: Option<bool>,
     pub status: Option<TaskStatus>,
+    pub state_id: Option<Uuid>,
+    pub state_category: Option<WorkflowStateCategory>,
     pub priority: Option<TaskPriority>,
     pub due_date_from: Option<DateTime<Utc>>,
     pub due_date_to: Option<DateTime<Utc>>,
+    pub created_at_from: Option<DateTime<Utc>>,
+    pub created_at_to: Option<DateTime<Utc>>,
+    pub updated_at_from: Option<DateTime<Utc>>,
+    pub updated_at_to: Option<DateTime<Utc>>,
+    /// Every custom field condition must match.
+    pub custom_fields: Option<Vec<CustomFieldFilter>>,
+    /// Every nested filter must match.
+    pub and: Option<Vec<TaskFilter>>,
+    /// At least one nested filter must match.
+    pub or: Option<Vec<TaskFilter>>,
+}
+
+impl TaskFilter {
+    /// Pushes the filter as a single parenthesized SQL predicate over the `tasks` table.
+    pub fn push_sql(&self, query: &mut QueryBuilder<'_, Postgres>) {
+        query.push("(TRUE");
+
+        if let Some(project_id) = self.project_id {
+            query.push(" AND project_id = ").push_bind(project_id);
+        }
+
+        if let Some(lead_id) = self.lead_id {
+            query.push(" AND lead_id = ").push_bind(lead_id);
+        }
+
+        if let Some(owner_id) = self.owner_id {
+            query.push(" AND owner_id = ").push_bind(owner_id);
+        }
+
+        if let Some(assignee_id) = self.assignee_id {
+            query
+                .push(" AND id IN (SELECT task_id FROM tasks_by_assignees WHERE assignee_id = ")
+                .push_bind(assignee_id)
+                .push(")");
+        }
+
+        if let Some(label_id) = self.label_id {
+            query
+                .push(" AND id IN (SELECT task_id FROM labels_by_tasks WHERE label_id = ")
+                .push_bind(label_id)
+                .push(")");
+        }
+
+        if let Some(parent_id) = self.parent_id {
+            query.push(" AND parent_id = ").push_bind(parent_id);
+        }
+
+        if let Some(cycle_id) = self.cycle_id {
+            query.push(" AND cycle_id = ").push_bind(cycle_id);
+        }
+
+        if let Some(milestone_id) = self.milestone_id {
+            query.push(" AND milestone_id = ").push_bind(milestone_id);
+        }
+
+        if self.top_level_only.unwrap_or(false) {
+            query.push(" AND parent_id IS NULL");
+        }
+
+        // Rows without a status or priority are exposed as `None`, so they match it too.
+        if let Some(status) = self.status {
+            query
+                .push(" AND COALESCE(status, 'None') = ")
+                .push_bind(status.to_str());
+        }
+
+        if let Some(state_id) = self.state_id {
+            query.push(" AND state_id = ").push_bind(state_id);
+        }
+
+        if let Some(state_category) = self.state_category {
+            query
+                .push(" AND state_id IN (SELECT id FROM workflow_states WHERE category = ")
+                .push_bind(state_category.to_str())
+                .push(")");
+        }
+
+        if let Some(priority) = self.priority {
+            query
+                .push(" AND COALESCE(priority, 'None') = ")
+                .push_bind(priority.to_str());
+        }
+
+        if let Some(due_date_from) = self.due_date_from {
+            query
+                .push(" AND due_date >= ")
+                .push_bind(DateTimeBridge::from_date_time(due_date_from));
+        }
+
+        if let Some(due_date_to) = self.due_date_to {
+            query
+                .push(" AND due_date <= ")
+                .push_bind(DateTimeBridge::from_date_time(due_date_to));
+        }
+
+        if let Some(created_at_from) = self.created_at_from {
+            query
+                .push(" AND created_at >= ")
+                .push_bind(DateTimeBridge::from_date_time(created_at_from));
+        }
+
+        if let Some(created_at_to) = self.created_at_to {
+            query
+                .push(" AND created_at <= ")
+                .push_bind(DateTimeBridge::from_date_time(created_at_to));
+        }
+
+        if let Some(updated_at_from) = self.updated_at_from {
+            query
+                .push(" AND updated_at >= ")
+                .push_bind(DateTimeBridge::from_date_time(updated_at_from));
+        }
+
+        if let Some(updated_at_to) = self.updated_at_to {
+            query
+                .push(" AND updated_at <= ")
+                .push_bind(DateTimeBridge::from_date_time(updated_at_to));
+        }
+
+        if let Some(custom_fields) = &self.custom_fields {
+            for filter in custom_fields {
+                query.push(" AND ");
+                filter.push_sql(query);
+            }
+        }
+
+        if let Some(and) = &self.and {
+            for filter in and {
+                query.push(" AND ");
+                filter.push_sql(query);
+            }
+        }
+
+        if let Some(or) = self.or.as_ref().filter(|or| !or.is_empty()) {
+            query.push(" AND (");
+
+            for (i, filter) in or.iter().enumerate() {
+                if i > 0 {
+                    query.push(" OR ");
+                }
+
+                filter.push_sql(query);
+            }
+
+            query.push(")");
+        }
+
+        query.push(")");
+    }
+}
+
+#[derive(InputObject)]
+pub struct CustomFieldFilter {
+    pub field_id: Uuid,
+    /// Matches the value, or one of the selected options of multi select fields.
+    pub equals: Option<Json<Value>>,
+    pub is_set: Option<bool>,
+    /// Lower bound of number and date fields, inclusive.
+    pub from: Option<Json<Value>>,
+    /// Upper bound of number and date fields, inclusive.
+    pub to: Option<Json<Value>>,
+}
+
+impl CustomFieldFilter {
+    pub fn push_sql(&self, query: &mut QueryBuilder<'_, Postgres>) {
+        if self.is_set == Some(false) {
+            query
+                .push("id NOT IN (SELECT task_id FROM task_custom_field_values WHERE field_id = ")
+                .push_bind(self.field_id)
+                .push(")");
+
+            return;
+        }
+
+        query
+            .push("id IN (SELECT task_id FROM task_custom_field_values WHERE field_id = ")
+            .push_bind(self.field_id);
+
+        if let Some(Json(equals)) = &self.equals {
+            query
+                .push(" AND (value = ")
+                .push_bind(equals.clone())
+                .push(" OR (jsonb_typeof(value) = 'array' AND value @> jsonb_build_array(")
+                .push_bind(equals.clone())
+                .push(")))");
+        }
+
+        for (bound, operator) in [(&self.from, " >= "), (&self.to, " <= ")] {
+            match bound.as_ref().map(|b| &b.0) {
+                Some(Value::Number(n)) => {
+                    query
+                        .push(" AND jsonb_typeof(value) = 'number' AND (value #>> '{}')::float8")
+                        .push(operator)
+                        .push_bind(n.as_f64().unwrap_or_default());
+                }
+                Some(Value::String(s)) => {
+                    query
+                        .push(" AND jsonb_typeof(value) = 'string' AND value #>> '{}'")
+                        .push(operator)
+                        .push_bind(s.clone());
+                }
+                _ => {}
+            }
+        }
+
+        query.push(")");
+    }
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq, Debug)]
+pub enum TaskOrderField {
+    CreatedAt,
+    UpdatedAt,
+    DueDate,
+    Title,
+    /// Manual order inside board columns, meant to be used with a project and state filter.
+    Rank,
+    /// Orders by the value of `customFieldId`, tasks without a value go last.
+    CustomField,
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub enum OrderDirection {
+    #[default]
+    Asc,
+    Desc,
 }
 
 #[derive(InputObject)]
+pub struct TaskOrder {
+    pub field: TaskOrderField,
+    pub custom_field_id: Option<Uuid>,
+    pub direction: Option<OrderDirection>,
+}
+
+impl TaskOrder {
+    /// SQL `ORDER BY` list over the `tasks` table. Custom fields are needed to pick how their
+    /// values compare.
+    pub fn to_sql(&self, custom_field: Option<&CustomField>) -> Result<String> {
+        let direction = match self.direction.unwrap_or_default() {
+            OrderDirection::Asc => "ASC",
+            OrderDirection::Desc => "DESC",
+        };
+
+        let expression = match self.field {
+            TaskOrderField::CreatedAt => "created_at".to_string(),
+            TaskOrderField::UpdatedAt => "updated_at".to_string(),
+            TaskOrderField::DueDate => "due_date".to_string(),
+            TaskOrderField::Title => "lower(title)".to_string(),
+            TaskOrderField::Rank => "rank".to_string(),
+            TaskOrderField::CustomField => {
+                let field = custom_field.ok_or_else(|| match self.custom_field_id {
+                    Some(id) => PlexoAppError::not_found("CustomField", id),
+                    None => PlexoAppError::validation(
+                        "customFieldId",
+                        "Ordering by a custom field needs its id",
+                    ),
+                })?;
+
+                // Dates are stored in a single format, so they sort as text.
+                let value = match field.field_type {
+                    CustomFieldType::Number => "(value #>> '{}')::float8",
+                    _ => "lower(value #>> '{}')",
+                };
+
+                format!(
+                    "(SELECT {value} FROM task_custom_field_values WHERE task_id = tasks.id AND field_id = '{}')",
+                    field.id
+                )
+            }
+        };
+
+        Ok(format!("{expression} {direction} NULLS LAST"))
+    }
+}
+
+#[derive(InputObject, Default)]
 pub struct MemberFilter {
     pub name: Option<String>,
     pub email: Option<String>,
@@ -40,50 +332,131 @@ pub struct MemberFilter {
     pub role: Option<String>,
 }
 
-#[derive(InputObject)]
+impl MemberFilter {
+    pub fn push_sql(&self, query: &mut QueryBuilder<'_, Postgres>) {
+        query.push("(TRUE");
+
+        if let Some(name) = &self.name {
+            query
+                .push(" AND name ILIKE ")
+                .push_bind(format!("%{name}%"));
+        }
+
+        if let Some(email) = &self.email {
+            query
+                .push(" AND email ILIKE ")
+                .push_bind(format!("%{email}%"));
+        }
+
+        if let Some(github_id) = &self.github_id {
+            query.push(" AND github_id = ").push_bind(github_id.clone());
+        }
+
+        if let Some(role) = &self.role {
+            query.push(" AND role = ").push_bind(role.clone());
+        }
+
+        query.push(")");
+    }
+}
+
+#[derive(InputObject, Default)]
 pub struct TeamFilter {
     pub visibility: Option<String>,
     pub name: Option<String>,
 }
 
-#[derive(InputObject)]
+impl TeamFilter {
+    pub fn push_sql(&self, query: &mut QueryBuilder<'_, Postgres>) {
+        query.push("(TRUE");
+
+        if let Some(visibility) = &self.visibility {
+            query
+                .push(" AND visibility = ")
+                .push_bind(visibility.clone());
+        }
+
+        if let Some(name) = &self.name {
+            query
+                .push(" AND name ILIKE ")
+                .push_bind(format!("%{name}%"));
+        }
+
+        query.push(")");
+    }
+}
+
+#[derive(InputObject, Default)]
 pub struct ProjectFilter {
     pub title: Option<String>,
     pub description: Option<String>,
 }
 
+impl ProjectFilter {
+    pub fn push_sql(&self, query: &mut QueryBuilder<'_, Postgres>) {
+        query.push("(TRUE");
+
+        if let Some(title) = &self.title {
+            query
+                .push(" AND name ILIKE ")
+                .push_bind(format!("%{title}%"));
+        }
+
+        if let Some(description) = &self.description {
+            query
+                .push(" AND description ILIKE ")
+                .push_bind(format!("%{description}%"));
+        }
+
+        query.push(")");
+    }
+}
+
 #[Object]
 impl ResourcesQuery {
-    async fn tasks(&self, ctx: &Context<'_>, _filter: Option<TaskFilter>) -> Result<Vec<Task>> {
+    async fn tasks(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<TaskFilter>,
+        order_by: Option<TaskOrder>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> Result<PlexoConnection<Task>> {
         let (plexo_engine, _member_id) = extract_context(ctx)?;
 
-        let tasks = sqlx::query!(
-            r#"
-            SELECT * FROM tasks
-            "#
+        let loader = ctx.data::<DataLoader<TaskLoader>>()?;
+        let filter = filter.unwrap_or_default();
+
+        let order_by = match order_by {
+            Some(order_by) => {
+                let custom_field = match order_by.custom_field_id {
+                    Some(field_id) => {
+                        ctx.data::<DataLoader<CustomFieldLoader>>()?
+                            .load_one(field_id)
+                            .await?
+                    }
+                    None => None,
+                };
+
+                Some(order_by.to_sql(custom_field.as_ref())?)
+            }
+            None => None,
+        };
+
+        paginate_ordered(
+            &plexo_engine.pool,
+            loader,
+            "tasks",
+            |query| filter.push_sql(query),
+            order_by,
+            after,
+            before,
+            first,
+            last,
         )
-        .fetch_all(&*plexo_engine.pool)
         .await
-        .unwrap();
-
-        Ok(tasks
-            .iter()
-            .map(|r| Task {
-                id: r.id,
-                created_at: DateTimeBridge::from_offset_date_time(r.created_at),
-                updated_at: DateTimeBridge::from_offset_date_time(r.updated_at),
-                title: r.title.clone(),
-                description: r.description.clone(),
-                status: TaskStatus::from_optional_str(&r.status),
-                priority: TaskPriority::from_optional_str(&r.priority),
-                due_date: r.due_date.map(DateTimeBridge::from_offset_date_time),
-                project_id: r.project_id,
-                lead_id: r.lead_id,
-                owner_id: r.owner_id,
-                count: r.count,
-                parent_id: r.parent_id,
-            })
-            .collect())
     }
 
     async fn task_by_id(&self, ctx: &Context<'_>, id: Uuid) -> Result<Task> {
@@ -92,13 +465,13 @@ impl ResourcesQuery {
         let task = sqlx::query!(
             r#"
             SELECT * FROM tasks
-            WHERE id = $1
+            WHERE id = $1 AND deleted_at IS NULL
             "#,
             id
         )
-        .fetch_one(&*plexo_engine.pool)
-        .await
-        .unwrap();
+        .fetch_optional(&*plexo_engine.pool)
+        .await?
+        .ok_or_else(|| PlexoAppError::not_found("Task", id))?;
 
         Ok(Task {
             id: task.id,
@@ -114,40 +487,62 @@ impl ResourcesQuery {
             owner_id: task.owner_id,
             count: task.count,
             parent_id: task.parent_id,
+            rank: task.rank,
+            milestone_id: task.milestone_id,
+            cycle_id: task.cycle_id,
+            estimate: task.estimate,
+            key: task.key.clone(),
+            state_id: task.state_id,
+        })
+    }
+
+    /// Finds a task by its key, such as `API-42`. Keys the task had before moving projects also match.
+    async fn task_by_key(&self, ctx: &Context<'_>, key: String) -> Result<Option<Task>> {
+        let (plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<TaskLoader>>()?;
+
+        let task_id = sqlx::query!(
+            r#"
+            SELECT task_id FROM task_keys
+            WHERE key = upper(btrim($1))
+            "#,
+            key,
+        )
+        .fetch_optional(&*plexo_engine.pool)
+        .await?;
+
+        Ok(match task_id {
+            Some(r) => loader.load_one(r.task_id).await?,
+            None => None,
         })
     }
 
     async fn members(
         &self,
         ctx: &Context<'_>,
-        _filter: Option<MemberFilter>,
-    ) -> Result<Vec<Member>> {
+        filter: Option<MemberFilter>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> Result<PlexoConnection<Member>> {
         let (plexo_engine, _member_id) = extract_context(ctx)?;
 
-        let members = sqlx::query!(
-            r#"
-            SELECT * FROM members
-            "#
+        let loader = ctx.data::<DataLoader<MemberLoader>>()?;
+        let filter = filter.unwrap_or_default();
+
+        paginate(
+            &plexo_engine.pool,
+            loader,
+            "members",
+            |query| filter.push_sql(query),
+            after,
+            before,
+            first,
+            last,
         )
-        .fetch_all(&*plexo_engine.pool)
         .await
-        .unwrap();
-
-        Ok(members
-            .iter()
-            .map(|r| Member {
-                id: r.id,
-                created_at: DateTimeBridge::from_offset_date_time(r.created_at),
-                updated_at: DateTimeBridge::from_offset_date_time(r.updated_at),
-                name: r.name.clone(),
-                email: r.email.clone(),
-                github_id: r.github_id.clone(),
-                google_id: r.google_id.clone(),
-                photo_url: r.photo_url.clone(),
-                role: MemberRole::from_optional_str(&r.role),
-                password_hash: None,
-            })
-            .collect())
     }
 
     async fn member_by_id(&self, ctx: &Context<'_>, id: Uuid) -> Result<Member> {
@@ -160,9 +555,9 @@ impl ResourcesQuery {
             "#,
             id
         )
-        .fetch_one(&*plexo_engine.pool)
-        .await
-        .unwrap();
+        .fetch_optional(&*plexo_engine.pool)
+        .await?
+        .ok_or_else(|| PlexoAppError::not_found("Member", id))?;
 
         Ok(Member {
             id: member.id,
@@ -188,9 +583,9 @@ impl ResourcesQuery {
             "#,
             email
         )
-        .fetch_one(&*plexo_engine.pool)
-        .await
-        .unwrap();
+        .fetch_optional(&*plexo_engine.pool)
+        .await?
+        .ok_or_else(|| PlexoAppError::not_found("Member", &email))?;
 
         Ok(Member {
             id: member.id,
@@ -209,34 +604,28 @@ impl ResourcesQuery {
     async fn projects(
         &self,
         ctx: &Context<'_>,
-        _filter: Option<ProjectFilter>,
-    ) -> Result<Vec<Project>> {
+        filter: Option<ProjectFilter>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> Result<PlexoConnection<Project>> {
         let (plexo_engine, _member_id) = extract_context(ctx)?;
 
-        let projects = sqlx::query!(
-            r#"
-            SELECT * FROM projects
-            "#
+        let loader = ctx.data::<DataLoader<ProjectLoader>>()?;
+        let filter = filter.unwrap_or_default();
+
+        paginate(
+            &plexo_engine.pool,
+            loader,
+            "projects",
+            |query| filter.push_sql(query),
+            after,
+            before,
+            first,
+            last,
         )
-        .fetch_all(&*plexo_engine.pool)
         .await
-        .unwrap();
-
-        Ok(projects
-            .iter()
-            .map(|r| Project {
-                id: r.id,
-                created_at: DateTimeBridge::from_offset_date_time(r.created_at),
-                updated_at: DateTimeBridge::from_offset_date_time(r.updated_at),
-                name: r.name.clone(),
-                prefix: r.prefix.clone(),
-                owner_id: r.owner_id,
-                description: r.description.clone(),
-                lead_id: r.lead_id,
-                start_date: r.start_date.map(DateTimeBridge::from_offset_date_time),
-                due_date: r.due_date.map(DateTimeBridge::from_offset_date_time),
-            })
-            .collect())
     }
 
     async fn project_by_id(&self, ctx: &Context<'_>, id: Uuid) -> Result<Project> {
@@ -245,13 +634,13 @@ impl ResourcesQuery {
         let project = sqlx::query!(
             r#"
             SELECT * FROM projects
-            WHERE id = $1
+            WHERE id = $1 AND deleted_at IS NULL
             "#,
             id
         )
-        .fetch_one(&*plexo_engine.pool)
-        .await
-        .unwrap();
+        .fetch_optional(&*plexo_engine.pool)
+        .await?
+        .ok_or_else(|| PlexoAppError::not_found("Project", id))?;
 
         Ok(Project {
             id: project.id,
@@ -269,31 +658,31 @@ impl ResourcesQuery {
         })
     }
 
-    async fn teams(&self, ctx: &Context<'_>, _filter: Option<TeamFilter>) -> Result<Vec<Team>> {
+    async fn teams(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<TeamFilter>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> Result<PlexoConnection<Team>> {
         let (plexo_engine, _member_id) = extract_context(ctx)?;
 
-        let teams = sqlx::query!(
-            r#"
-            SELECT *
-            FROM teams
-            "#
+        let loader = ctx.data::<DataLoader<TeamLoader>>()?;
+        let filter = filter.unwrap_or_default();
+
+        paginate(
+            &plexo_engine.pool,
+            loader,
+            "teams",
+            |query| filter.push_sql(query),
+            after,
+            before,
+            first,
+            last,
         )
-        .fetch_all(&*plexo_engine.pool)
         .await
-        .unwrap();
-
-        Ok(teams
-            .iter()
-            .map(|r| Team {
-                id: r.id,
-                created_at: DateTimeBridge::from_offset_date_time(r.created_at),
-                updated_at: DateTimeBridge::from_offset_date_time(r.updated_at),
-                name: r.name.clone(),
-                owner_id: r.owner_id,
-                visibility: TeamVisibility::from_optional_str(&r.visibility),
-                prefix: r.prefix.clone(),
-            })
-            .collect())
     }
 
     async fn team_by_id(&self, ctx: &Context<'_>, id: Uuid) -> Result<Team> {
@@ -302,13 +691,13 @@ impl ResourcesQuery {
         let team = sqlx::query!(
             r#"
             SELECT * FROM teams
-            WHERE id = $1
+            WHERE id = $1 AND deleted_at IS NULL
             "#,
             id
         )
-        .fetch_one(&*plexo_engine.pool)
-        .await
-        .unwrap();
+        .fetch_optional(&*plexo_engine.pool)
+        .await?
+        .ok_or_else(|| PlexoAppError::not_found("Team", id))?;
 
         Ok(Team {
             id: team.id,
@@ -321,28 +710,88 @@ impl ResourcesQuery {
         })
     }
 
-    async fn labels(&self, ctx: &Context<'_>) -> Result<Vec<Label>> {
+    async fn labels(
+        &self,
+        ctx: &Context<'_>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> Result<PlexoConnection<Label>> {
         let (plexo_engine, _member_id) = extract_context(ctx)?;
 
-        let labels = sqlx::query!(
+        let loader = ctx.data::<DataLoader<LabelLoader>>()?;
+
+        paginate(
+            &plexo_engine.pool,
+            loader,
+            "labels",
+            |query| {
+                query.push("TRUE");
+            },
+            after,
+            before,
+            first,
+            last,
+        )
+        .await
+    }
+
+    async fn attachment_limits(&self, ctx: &Context<'_>) -> Result<AttachmentLimits> {
+        let (plexo_engine, _member_id) = extract_context(ctx)?;
+
+        Ok(plexo_engine.get_attachment_limits().await?)
+    }
+
+    /// Custom fields of a project, organization fields included, or only the organization fields.
+    async fn custom_fields(
+        &self,
+        ctx: &Context<'_>,
+        project_id: Option<Uuid>,
+    ) -> Result<Vec<CustomField>> {
+        let (plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<CustomFieldLoader>>()?;
+
+        let ids: Vec<Uuid> = sqlx::query!(
             r#"
-            SELECT * FROM labels
-            "#
+            SELECT id FROM custom_fields
+            WHERE project_id IS NULL OR project_id = $1
+            ORDER BY project_id NULLS FIRST, position, created_at
+            "#,
+            project_id,
         )
         .fetch_all(&*plexo_engine.pool)
-        .await
-        .unwrap();
-
-        Ok(labels
-            .iter()
-            .map(|r| Label {
-                id: r.id,
-                created_at: DateTimeBridge::from_offset_date_time(r.created_at),
-                updated_at: DateTimeBridge::from_offset_date_time(r.updated_at),
-                name: r.name.clone(),
-                color: r.color.clone(),
-                description: r.description.clone(),
-            })
+        .await?
+        .into_iter()
+        .map(|r| r.id)
+        .collect();
+
+        let fields_map = loader.load_many(ids.clone()).await?;
+
+        Ok(ids
+            .into_iter()
+            .filter_map(|id| fields_map.get(&id).cloned())
+            .collect())
+    }
+
+    /// States of the workflow used by a project, or of the default workflow.
+    async fn workflow_states(
+        &self,
+        ctx: &Context<'_>,
+        project_id: Option<Uuid>,
+    ) -> Result<Vec<WorkflowState>> {
+        let (plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<WorkflowStateLoader>>()?;
+
+        let ids = plexo_engine.get_workflow_state_ids(project_id).await?;
+
+        let states_map = loader.load_many(ids.clone()).await?;
+
+        Ok(ids
+            .into_iter()
+            .filter_map(|id| states_map.get(&id).cloned())
             .collect())
     }
 
@@ -356,9 +805,9 @@ impl ResourcesQuery {
             "#,
             member_id
         )
-        .fetch_one(&*plexo_engine.pool)
-        .await
-        .unwrap();
+        .fetch_optional(&*plexo_engine.pool)
+        .await?
+        .ok_or_else(|| PlexoAppError::not_found("Member", member_id))?;
 
         Ok(Member {
             id: r.id,
@@ -381,38 +830,47 @@ impl ResourcesQuery {
         resource_id: Option<Uuid>,
         operation_type: Option<ActivityOperationType>,
         member_id: Option<Uuid>,
-    ) -> Result<Vec<Activity>> {
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> Result<PlexoConnection<Activity>> {
         let (plexo_engine, _member_id) = extract_context(ctx)?;
 
-        let activities = sqlx::query!(
-            r#"
-            SELECT * FROM activity
-            WHERE
-                resource_type = COALESCE($1, resource_type)
-                AND resource_id = COALESCE($2, resource_id)
-                AND operation = COALESCE($3, operation)
-                AND member_id = COALESCE($4, member_id)
-            "#,
-            resource_type.map(|r| r.to_string()),
-            resource_id,
-            operation_type.map(|r| r.to_string()),
-            member_id
+        let loader = ctx.data::<DataLoader<ActivityLoader>>()?;
+
+        paginate(
+            &plexo_engine.pool,
+            loader,
+            "activity",
+            |query| {
+                query.push("TRUE");
+
+                if let Some(resource_type) = resource_type {
+                    query
+                        .push(" AND resource_type = ")
+                        .push_bind(resource_type.to_string());
+                }
+
+                if let Some(resource_id) = resource_id {
+                    query.push(" AND resource_id = ").push_bind(resource_id);
+                }
+
+                if let Some(operation_type) = operation_type {
+                    query
+                        .push(" AND operation = ")
+                        .push_bind(operation_type.to_string());
+                }
+
+                if let Some(member_id) = member_id {
+                    query.push(" AND member_id = ").push_bind(member_id);
+                }
+            },
+            after,
+            before,
+            first,
+            last,
         )
-        .fetch_all(&*plexo_engine.pool)
         .await
-        .unwrap();
-
-        Ok(activities
-            .iter()
-            .map(|r| Activity {
-                id: r.id,
-                created_at: DateTimeBridge::from_offset_date_time(r.created_at),
-                updated_at: DateTimeBridge::from_offset_date_time(r.updated_at),
-                resource_type: ActivityResourceType::from_str(&r.resource_type).unwrap(),
-                operation: ActivityOperationType::from_str(&r.operation).unwrap(),
-                resource_id: r.resource_id,
-                member_id: r.member_id,
-            })
-            .collect())
     }
 }
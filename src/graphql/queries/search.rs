@@ -0,0 +1,74 @@
+use std::str::FromStr;
+
+use async_graphql::{Context, Object};
+
+use crate::{
+    errors::extensions::Result,
+    graphql::auth::extract_context,
+    sdk::search::{SearchResourceType, SearchResult},
+};
+
+#[derive(Default)]
+pub struct SearchQuery;
+
+#[Object]
+impl SearchQuery {
+    async fn search(
+        &self,
+        ctx: &Context<'_>,
+        query: String,
+        types: Option<Vec<SearchResourceType>>,
+        #[graphql(default = 20)] limit: i32,
+    ) -> Result<Vec<SearchResult>> {
+        let (plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let types: Vec<String> = types
+            .unwrap_or_else(SearchResourceType::all)
+            .iter()
+            .map(|t| t.to_str().to_string())
+            .collect();
+
+        let results = sqlx::query!(
+            r#"
+            SELECT
+                resource_type,
+                resource_id,
+                title,
+                ts_rank(document, search_query) AS "rank!",
+                -- Snippets are HTML, the indexed text is escaped before being highlighted.
+                ts_headline(
+                    'simple',
+                    replace(replace(replace(replace(replace(
+                        concat_ws(' ', title, body),
+                        '&', '&amp;'), '<', '&lt;'), '>', '&gt;'), '"', '&quot;'), '''', '&#39;'),
+                    search_query,
+                    'StartSel=<mark>, StopSel=</mark>, MaxFragments=2'
+                ) AS "snippet!"
+            FROM search_documents, websearch_to_tsquery('simple', $1) AS search_query
+            WHERE
+                document @@ search_query
+                AND resource_type = ANY($2)
+            ORDER BY ts_rank(document, search_query) DESC
+            LIMIT $3
+            "#,
+            query,
+            &types,
+            limit.clamp(1, 100) as i64,
+        )
+        .fetch_all(&*plexo_engine.pool)
+        .await?;
+
+        Ok(results
+            .into_iter()
+            .filter_map(|r| {
+                Some(SearchResult {
+                    resource_type: SearchResourceType::from_str(&r.resource_type).ok()?,
+                    resource_id: r.resource_id,
+                    title: r.title,
+                    snippet: r.snippet,
+                    rank: r.rank,
+                })
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,57 @@
+use async_graphql::{dataloader::DataLoader, Context, Object};
+use uuid::Uuid;
+
+use crate::{
+    errors::{definitions::PlexoAppError, extensions::Result},
+    graphql::auth::extract_context,
+    sdk::{loaders::TaskTemplateLoader, task_template::TaskTemplate},
+};
+
+#[derive(Default)]
+pub struct TemplatesQuery;
+
+#[Object]
+impl TemplatesQuery {
+    /// Templates of a project and the ones without a project, or every template.
+    async fn task_templates(
+        &self,
+        ctx: &Context<'_>,
+        project_id: Option<Uuid>,
+    ) -> Result<Vec<TaskTemplate>> {
+        let (plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<TaskTemplateLoader>>()?;
+
+        let ids: Vec<Uuid> = sqlx::query!(
+            r#"
+            SELECT id FROM task_templates
+            WHERE $1::uuid IS NULL OR project_id IS NULL OR project_id = $1
+            ORDER BY name, created_at
+            "#,
+            project_id,
+        )
+        .fetch_all(&*plexo_engine.pool)
+        .await?
+        .into_iter()
+        .map(|r| r.id)
+        .collect();
+
+        let templates_map = loader.load_many(ids.clone()).await?;
+
+        Ok(ids
+            .into_iter()
+            .filter_map(|id| templates_map.get(&id).cloned())
+            .collect())
+    }
+
+    async fn task_template_by_id(&self, ctx: &Context<'_>, id: Uuid) -> Result<TaskTemplate> {
+        let (_plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<TaskTemplateLoader>>()?;
+
+        Ok(loader
+            .load_one(id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("TaskTemplate", id))?)
+    }
+}
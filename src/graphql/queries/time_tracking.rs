@@ -0,0 +1,127 @@
+use async_graphql::{dataloader::DataLoader, Context, Object};
+use chrono::{DateTime, Utc};
+use sqlx::{types::time::OffsetDateTime, Postgres, QueryBuilder};
+use uuid::Uuid;
+
+use crate::{
+    errors::extensions::Result,
+    graphql::auth::extract_context,
+    sdk::{
+        loaders::TimeEntryLoader,
+        time_entry::{TimeEntry, TimeReportGrouping, TimeReportRow},
+        utilities::DateTimeBridge,
+    },
+};
+
+#[derive(Default)]
+pub struct TimeTrackingQuery;
+
+/// A row of the time report as it comes out of the database.
+#[derive(sqlx::FromRow)]
+struct TimeReportRecord {
+    project_id: Option<Uuid>,
+    member_id: Option<Uuid>,
+    day: Option<OffsetDateTime>,
+    total_time_spent: i64,
+    entries: i64,
+}
+
+#[Object]
+impl TimeTrackingQuery {
+    /// The running timer of the signed-in member, if any.
+    async fn running_timer(&self, ctx: &Context<'_>) -> Result<Option<TimeEntry>> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        let entry = sqlx::query!(
+            r#"
+            SELECT id FROM time_entries
+            WHERE member_id = $1 AND ended_at IS NULL
+            "#,
+            member_id,
+        )
+        .fetch_optional(&*plexo_engine.pool)
+        .await?;
+
+        let loader = ctx.data::<DataLoader<TimeEntryLoader>>()?;
+
+        Ok(match entry {
+            Some(entry) => loader.load_one(entry.id).await?,
+            None => None,
+        })
+    }
+
+    /// Tracked time grouped by project, member or day. Entries are picked by their start, within
+    /// `[from, to)`.
+    async fn time_report(
+        &self,
+        ctx: &Context<'_>,
+        group_by: TimeReportGrouping,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        project_id: Option<Uuid>,
+        member_id: Option<Uuid>,
+    ) -> Result<Vec<TimeReportRow>> {
+        let (plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let mut query: QueryBuilder<'_, Postgres> = QueryBuilder::new(match group_by {
+            TimeReportGrouping::Project => {
+                "SELECT tasks.project_id, NULL::uuid AS member_id, NULL::timestamptz AS day, "
+            }
+            TimeReportGrouping::Member => {
+                "SELECT NULL::uuid AS project_id, time_entries.member_id, NULL::timestamptz AS day, "
+            }
+            TimeReportGrouping::Day => {
+                "SELECT NULL::uuid AS project_id, NULL::uuid AS member_id, \
+                 date_trunc('day', time_entries.started_at) AS day, "
+            }
+        });
+
+        query.push(
+            "COALESCE(SUM(EXTRACT(EPOCH FROM (COALESCE(time_entries.ended_at, NOW()) - time_entries.started_at))), 0)::bigint AS total_time_spent, \
+             COUNT(*) AS entries \
+             FROM time_entries \
+             JOIN tasks ON tasks.id = time_entries.task_id \
+             WHERE TRUE",
+        );
+
+        if let Some(from) = from {
+            query
+                .push(" AND time_entries.started_at >= ")
+                .push_bind(DateTimeBridge::from_date_time(from));
+        }
+
+        if let Some(to) = to {
+            query
+                .push(" AND time_entries.started_at < ")
+                .push_bind(DateTimeBridge::from_date_time(to));
+        }
+
+        if let Some(project_id) = project_id {
+            query.push(" AND tasks.project_id = ").push_bind(project_id);
+        }
+
+        if let Some(member_id) = member_id {
+            query
+                .push(" AND time_entries.member_id = ")
+                .push_bind(member_id);
+        }
+
+        query.push(" GROUP BY 1, 2, 3 ORDER BY 3, 1, 2");
+
+        let rows: Vec<TimeReportRecord> = query
+            .build_query_as()
+            .fetch_all(&*plexo_engine.pool)
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| TimeReportRow {
+                project_id: r.project_id,
+                member_id: r.member_id,
+                day: r.day.map(DateTimeBridge::from_offset_date_time),
+                total_time_spent: r.total_time_spent,
+                entries: r.entries,
+            })
+            .collect())
+    }
+}
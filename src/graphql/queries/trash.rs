@@ -0,0 +1,81 @@
+use std::str::FromStr;
+
+use async_graphql::{Context, Object};
+
+use crate::{
+    config::TRASH_RETENTION_DAYS,
+    errors::extensions::Result,
+    graphql::auth::extract_context,
+    sdk::{
+        trash::{TrashItem, TrashResourceType},
+        utilities::DateTimeBridge,
+    },
+};
+
+#[derive(Default)]
+pub struct TrashQuery;
+
+#[Object]
+impl TrashQuery {
+    /// Deleted tasks, projects and teams, most recently deleted first. Subtasks deleted with
+    /// their parent are restored with it, so only the parent is listed.
+    async fn trash(
+        &self,
+        ctx: &Context<'_>,
+        types: Option<Vec<TrashResourceType>>,
+        #[graphql(default = 50)] limit: i32,
+    ) -> Result<Vec<TrashItem>> {
+        let (plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let types: Vec<String> = types
+            .unwrap_or_else(TrashResourceType::all)
+            .iter()
+            .map(|t| t.to_str().to_string())
+            .collect();
+
+        let items = sqlx::query!(
+            r#"
+            SELECT resource_type AS "resource_type!", resource_id AS "resource_id!", title AS "title!",
+                deleted_at AS "deleted_at!", deleted_by,
+                deleted_at + make_interval(days => $3) AS "purge_at!"
+            FROM (
+                SELECT 'Task' AS resource_type, tasks.id AS resource_id, tasks.title,
+                    tasks.deleted_at, tasks.deleted_by
+                FROM tasks
+                LEFT JOIN tasks AS parent ON parent.id = tasks.parent_id
+                WHERE
+                    tasks.deleted_at IS NOT NULL
+                    AND parent.deleted_at IS DISTINCT FROM tasks.deleted_at
+                UNION ALL
+                SELECT 'Project', id, name, deleted_at, deleted_by FROM projects
+                WHERE deleted_at IS NOT NULL
+                UNION ALL
+                SELECT 'Team', id, name, deleted_at, deleted_by FROM teams
+                WHERE deleted_at IS NOT NULL
+            ) AS trash
+            WHERE resource_type = ANY($1)
+            ORDER BY deleted_at DESC
+            LIMIT $2
+            "#,
+            &types,
+            limit.clamp(1, 500) as i64,
+            *TRASH_RETENTION_DAYS,
+        )
+        .fetch_all(&*plexo_engine.pool)
+        .await?;
+
+        Ok(items
+            .into_iter()
+            .filter_map(|r| {
+                Some(TrashItem {
+                    resource_type: TrashResourceType::from_str(&r.resource_type).ok()?,
+                    resource_id: r.resource_id,
+                    title: r.title,
+                    deleted_at: DateTimeBridge::from_offset_date_time(r.deleted_at),
+                    deleted_by_id: r.deleted_by,
+                    purge_at: DateTimeBridge::from_offset_date_time(r.purge_at),
+                })
+            })
+            .collect())
+    }
+}
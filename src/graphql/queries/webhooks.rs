@@ -0,0 +1,94 @@
+use async_graphql::{dataloader::DataLoader, Context, Object};
+use uuid::Uuid;
+
+use crate::{
+    errors::{definitions::PlexoAppError, extensions::Result},
+    graphql::{auth::extract_context, mutations::webhooks::ensure_can_manage_webhooks},
+    sdk::{
+        connections::{paginate, PlexoConnection},
+        loaders::{WebhookDeliveryLoader, WebhookLoader},
+        webhook::{Webhook, WebhookDelivery, WebhookDeliveryStatus},
+    },
+};
+
+#[derive(Default)]
+pub struct WebhooksQuery;
+
+#[Object]
+impl WebhooksQuery {
+    async fn webhooks(&self, ctx: &Context<'_>) -> Result<Vec<Webhook>> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        ensure_can_manage_webhooks(&plexo_engine, member_id).await?;
+
+        let ids: Vec<Uuid> = sqlx::query!(
+            r#"
+            SELECT id FROM webhooks
+            ORDER BY created_at
+            "#,
+        )
+        .fetch_all(&*plexo_engine.pool)
+        .await?
+        .into_iter()
+        .map(|r| r.id)
+        .collect();
+
+        let loader = ctx.data::<DataLoader<WebhookLoader>>()?;
+        let webhooks = loader.load_many(ids.clone()).await?;
+
+        Ok(ids
+            .iter()
+            .filter_map(|id| webhooks.get(id).cloned())
+            .collect())
+    }
+
+    async fn webhook(&self, ctx: &Context<'_>, id: Uuid) -> Result<Webhook> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        ensure_can_manage_webhooks(&plexo_engine, member_id).await?;
+
+        let loader = ctx.data::<DataLoader<WebhookLoader>>()?;
+
+        Ok(loader
+            .load_one(id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("Webhook", id))?)
+    }
+
+    /// Delivery log of a webhook, oldest first. Use `last` to read the newest deliveries.
+    #[allow(clippy::too_many_arguments)]
+    async fn webhook_deliveries(
+        &self,
+        ctx: &Context<'_>,
+        webhook_id: Uuid,
+        status: Option<WebhookDeliveryStatus>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> Result<PlexoConnection<WebhookDelivery>> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
+
+        ensure_can_manage_webhooks(&plexo_engine, member_id).await?;
+
+        let loader = ctx.data::<DataLoader<WebhookDeliveryLoader>>()?;
+
+        paginate(
+            &plexo_engine.pool,
+            loader,
+            "webhook_deliveries",
+            |query| {
+                query.push("webhook_id = ").push_bind(webhook_id);
+
+                if let Some(status) = status {
+                    query.push(" AND status = ").push_bind(status.to_str());
+                }
+            },
+            after,
+            before,
+            first,
+            last,
+        )
+        .await
+    }
+}
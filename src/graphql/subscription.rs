@@ -5,13 +5,15 @@ use async_graphql::{
 };
 use chrono::Utc;
 use std::pin::Pin;
-use tokio::sync::mpsc::channel;
+use tokio::sync::{broadcast::error::RecvError, mpsc::channel};
 use tokio_stream::Stream;
 use uuid::Uuid;
 
 use crate::system::subscriptions::DataContainer;
 use crate::{
+    graphql::auth::extract_context,
     sdk::{
+        notification::Notification,
         project::Project,
         task::{Task, TaskPriority, TaskStatus},
         team::{Team, TeamVisibility},
@@ -29,7 +31,7 @@ impl SubscriptionRoot {
         ctx: &Context<'_>,
     ) -> FieldResult<Pin<Box<dyn Stream<Item = Option<Task>> + Send>>> {
         let (sender, mut receiver) = channel(100);
-        let subscription_manager = &ctx.data::<Engine>().unwrap().subscription_manager;
+        let subscription_manager = &ctx.data::<Engine>()?.subscription_manager;
         let new_uuid = Uuid::new_v4().to_string();
 
         let suscription_added = subscription_manager.add_subscription(sender, 1).await?;
@@ -68,7 +70,7 @@ impl SubscriptionRoot {
         ctx: &Context<'_>,
     ) -> FieldResult<Pin<Box<dyn Stream<Item = Option<Project>> + Send>>> {
         let (sender, mut receiver) = channel(100);
-        let subscription_manager = &ctx.data::<Engine>().unwrap().subscription_manager;
+        let subscription_manager = &ctx.data::<Engine>()?.subscription_manager;
         let new_uuid = Uuid::new_v4().to_string();
 
         let suscription_added = subscription_manager.add_subscription(sender, 2).await?;
@@ -107,7 +109,7 @@ impl SubscriptionRoot {
         ctx: &Context<'_>,
     ) -> FieldResult<Pin<Box<dyn Stream<Item = Option<Team>> + Send>>> {
         let (sender, mut receiver) = channel(100);
-        let subscription_manager = &ctx.data::<Engine>().unwrap().subscription_manager;
+        let subscription_manager = &ctx.data::<Engine>()?.subscription_manager;
         let new_uuid = Uuid::new_v4().to_string();
 
         let suscription_added = subscription_manager.add_subscription(sender, 3).await?;
@@ -141,30 +143,62 @@ impl SubscriptionRoot {
         Ok(Box::pin(mapped_stream))
     }
 
-    async fn tasks(&self, ctx: &Context<'_>) -> impl Stream<Item = Task> {
-        let _auth_token = ctx.data::<String>().unwrap();
-
-        tokio_stream::wrappers::IntervalStream::new(tokio::time::interval(Duration::from_secs(1)))
-            .map(|_| Task {
-                id: Uuid::new_v4(),
-                title: "Task 1".to_string(),
-                created_at: Utc::now(),
-                updated_at: Utc::now(),
-                description: None,
+    /// New notifications of the signed-in member.
+    async fn notifications(
+        &self,
+        ctx: &Context<'_>,
+    ) -> FieldResult<impl Stream<Item = Notification>> {
+        let (plexo_engine, member_id) = extract_context(ctx)?;
 
-                status: TaskStatus::Backlog,
-                priority: TaskPriority::High,
+        let mut receiver = plexo_engine.subscription_manager.subscribe_notifications();
 
-                owner_id: Uuid::new_v4(),
+        let stream = stream! {
+            loop {
+                match receiver.recv().await {
+                    Ok(notification) if notification.member_id == member_id => yield notification,
+                    Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        };
 
-                // labels: vec![],
-                lead_id: None,
-                project_id: None,
+        Ok(stream)
+    }
 
-                due_date: None,
-                count: 0,
-                parent_id: None,
-            })
+    async fn tasks(&self, ctx: &Context<'_>) -> FieldResult<impl Stream<Item = Task>> {
+        let _auth_token = ctx.data::<String>()?;
+
+        let stream = tokio_stream::wrappers::IntervalStream::new(tokio::time::interval(
+            Duration::from_secs(1),
+        ))
+        .map(|_| Task {
+            id: Uuid::new_v4(),
+            title: "Task 1".to_string(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            description: None,
+
+            status: TaskStatus::Backlog,
+            priority: TaskPriority::High,
+
+            owner_id: Uuid::new_v4(),
+
+            // labels: vec![],
+            lead_id: None,
+            project_id: None,
+
+            due_date: None,
+            count: 0,
+            parent_id: None,
+            rank: None,
+            milestone_id: None,
+            cycle_id: None,
+            estimate: None,
+            key: "TASK-0".to_string(),
+            state_id: None,
+        });
+
+        Ok(stream)
     }
 
     async fn task_by_id(&self, id: Uuid) -> impl Stream<Item = Task> {
@@ -188,39 +222,53 @@ impl SubscriptionRoot {
                 due_date: None,
                 count: 0,
                 parent_id: None,
+                rank: None,
+                milestone_id: None,
+                cycle_id: None,
+                estimate: None,
+                key: "TASK-0".to_string(),
+                state_id: None,
             })
     }
 
-    async fn projects(&self, ctx: &Context<'_>) -> impl Stream<Item = Project> {
-        let _auth_token = ctx.data::<String>().unwrap();
-
-        tokio_stream::wrappers::IntervalStream::new(tokio::time::interval(Duration::from_secs(1)))
-            .map(|_| Project {
-                id: Uuid::new_v4(),
-                created_at: Utc::now(),
-                updated_at: Utc::now(),
-                name: "Project X".to_string(),
-                description: None,
-                owner_id: Uuid::new_v4(),
-                prefix: None,
-                lead_id: None,
-                start_date: None,
-                due_date: None,
-            })
+    async fn projects(&self, ctx: &Context<'_>) -> FieldResult<impl Stream<Item = Project>> {
+        let _auth_token = ctx.data::<String>()?;
+
+        let stream = tokio_stream::wrappers::IntervalStream::new(tokio::time::interval(
+            Duration::from_secs(1),
+        ))
+        .map(|_| Project {
+            id: Uuid::new_v4(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            name: "Project X".to_string(),
+            description: None,
+            owner_id: Uuid::new_v4(),
+            prefix: None,
+            lead_id: None,
+            start_date: None,
+            due_date: None,
+        });
+
+        Ok(stream)
     }
 
-    async fn teams(&self, ctx: &Context<'_>) -> impl Stream<Item = Team> {
-        let _auth_token = ctx.data::<String>().unwrap();
-
-        tokio_stream::wrappers::IntervalStream::new(tokio::time::interval(Duration::from_secs(1)))
-            .map(|_| Team {
-                id: Uuid::new_v4(),
-                name: "Team X".to_string(),
-                created_at: Utc::now(),
-                updated_at: Utc::now(),
-                owner_id: Uuid::new_v4(),
-                visibility: TeamVisibility::Public,
-                prefix: None,
-            })
+    async fn teams(&self, ctx: &Context<'_>) -> FieldResult<impl Stream<Item = Team>> {
+        let _auth_token = ctx.data::<String>()?;
+
+        let stream = tokio_stream::wrappers::IntervalStream::new(tokio::time::interval(
+            Duration::from_secs(1),
+        ))
+        .map(|_| Team {
+            id: Uuid::new_v4(),
+            name: "Team X".to_string(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            owner_id: Uuid::new_v4(),
+            visibility: TeamVisibility::Public,
+            prefix: None,
+        });
+
+        Ok(stream)
     }
 }
@@ -0,0 +1,274 @@
+use chrono::{DateTime, Utc};
+use reqwest::Url;
+use sqlx::{Postgres, QueryBuilder};
+use uuid::Uuid;
+
+use crate::{
+    errors::definitions::{FieldError, PlexoAppError},
+    system::core::Engine,
+};
+
+pub const NAME_MAX_LENGTH: usize = 120;
+pub const TITLE_MAX_LENGTH: usize = 255;
+pub const DESCRIPTION_MAX_LENGTH: usize = 10_000;
+pub const PREFIX_MAX_LENGTH: usize = 10;
+pub const URL_MAX_LENGTH: usize = 2_048;
+
+/// Resources that mutation inputs reference by id.
+#[derive(Clone, Copy, Debug)]
+pub enum Reference {
+    Member,
+    Task,
+    Project,
+    Team,
+    Label,
+    Cycle,
+    Milestone,
+}
+
+impl Reference {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Member => "Member",
+            Self::Task => "Task",
+            Self::Project => "Project",
+            Self::Team => "Team",
+            Self::Label => "Label",
+            Self::Cycle => "Cycle",
+            Self::Milestone => "Milestone",
+        }
+    }
+
+    fn table(&self) -> &'static str {
+        match self {
+            Self::Member => "members",
+            Self::Task => "tasks",
+            Self::Project => "projects",
+            Self::Team => "teams",
+            Self::Label => "labels",
+            Self::Cycle => "cycles",
+            Self::Milestone => "milestones",
+        }
+    }
+
+    /// Whether rows in the trash have to be left out.
+    fn is_soft_deleted(&self) -> bool {
+        matches!(self, Self::Task | Self::Project | Self::Team)
+    }
+}
+
+/// Collects the problems of a mutation input, so they are all reported at once as an
+/// [`PlexoAppError::InvalidInput`]. Fields are named as in the GraphQL schema.
+#[derive(Default)]
+pub struct InputValidator {
+    errors: Vec<FieldError>,
+}
+
+impl InputValidator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn error(&mut self, field: impl Into<String>, reason: impl Into<String>) -> &mut Self {
+        self.errors.push(FieldError {
+            field: field.into(),
+            reason: reason.into(),
+        });
+
+        self
+    }
+
+    /// A text that can't be blank, such as a title or a name.
+    pub fn required_text(
+        &mut self,
+        field: impl Into<String>,
+        value: &str,
+        max: usize,
+    ) -> &mut Self {
+        if value.trim().is_empty() {
+            return self.error(field, "can't be empty");
+        }
+
+        self.text(field, Some(value), max)
+    }
+
+    /// A new value for a text that can't be blank. `None` leaves the text as it is.
+    pub fn optional_required_text(
+        &mut self,
+        field: impl Into<String>,
+        value: Option<&str>,
+        max: usize,
+    ) -> &mut Self {
+        match value {
+            Some(value) => self.required_text(field, value, max),
+            None => self,
+        }
+    }
+
+    pub fn text(&mut self, field: impl Into<String>, value: Option<&str>, max: usize) -> &mut Self {
+        match value {
+            Some(value) if value.chars().count() > max => {
+                self.error(field, format!("must be at most {} characters long", max))
+            }
+            _ => self,
+        }
+    }
+
+    /// A color such as `#1a2b3c` or `#fff`.
+    pub fn hex_color(&mut self, field: impl Into<String>, value: Option<&str>) -> &mut Self {
+        let Some(value) = value else {
+            return self;
+        };
+
+        let is_hex_color = value.strip_prefix('#').is_some_and(|digits| {
+            matches!(digits.len(), 3 | 6) && digits.chars().all(|c| c.is_ascii_hexdigit())
+        });
+
+        if !is_hex_color {
+            return self.error(field, "must be a hex color such as #1a2b3c");
+        }
+
+        self
+    }
+
+    /// A task key prefix: a letter followed by letters or digits, such as `API` or `V2`.
+    pub fn prefix(&mut self, field: impl Into<String>, value: Option<&str>) -> &mut Self {
+        let Some(value) = value else {
+            return self;
+        };
+
+        let is_prefix = value.len() <= PREFIX_MAX_LENGTH
+            && value
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_ascii_alphabetic())
+            && value.chars().all(|c| c.is_ascii_alphanumeric());
+
+        if !is_prefix {
+            return self.error(
+                field,
+                format!(
+                    "must be a letter followed by up to {} letters or digits",
+                    PREFIX_MAX_LENGTH - 1
+                ),
+            );
+        }
+
+        self
+    }
+
+    pub fn email(&mut self, field: impl Into<String>, value: Option<&str>) -> &mut Self {
+        let Some(value) = value else {
+            return self;
+        };
+
+        let is_email = value
+            .split_once('@')
+            .is_some_and(|(user, domain)| !user.is_empty() && domain.contains('.'))
+            && !value.chars().any(char::is_whitespace);
+
+        if !is_email {
+            return self.error(field, "must be an email address");
+        }
+
+        self.text(field, Some(value), NAME_MAX_LENGTH)
+    }
+
+    /// An http or https URL.
+    pub fn url(&mut self, field: impl Into<String>, value: Option<&str>) -> &mut Self {
+        let Some(value) = value else {
+            return self;
+        };
+
+        if !Url::parse(value).is_ok_and(|url| matches!(url.scheme(), "http" | "https")) {
+            return self.error(field, "must be an http or https URL");
+        }
+
+        self.text(field, Some(value), URL_MAX_LENGTH)
+    }
+
+    pub fn non_negative<T>(&mut self, field: impl Into<String>, value: Option<T>) -> &mut Self
+    where
+        T: PartialOrd + Default,
+    {
+        match value {
+            Some(value) if value < T::default() => self.error(field, "can't be negative"),
+            _ => self,
+        }
+    }
+
+    /// Checks that `end` isn't before `start`, reporting the problem on the end field.
+    pub fn date_order(
+        &mut self,
+        start_field: &str,
+        start: Option<DateTime<Utc>>,
+        end_field: impl Into<String>,
+        end: Option<DateTime<Utc>>,
+    ) -> &mut Self {
+        match (start, end) {
+            (Some(start), Some(end)) if end < start => {
+                self.error(end_field, format!("can't be before {}", start_field))
+            }
+            _ => self,
+        }
+    }
+
+    /// Checks that every id points to an existing resource, leaving out the ones in the trash.
+    pub async fn exists(
+        &mut self,
+        plexo_engine: &Engine,
+        field: impl Into<String>,
+        reference: Reference,
+        ids: impl IntoIterator<Item = Uuid>,
+    ) -> Result<&mut Self, sqlx::Error> {
+        let ids: Vec<Uuid> = ids.into_iter().collect();
+
+        if ids.is_empty() {
+            return Ok(self);
+        }
+
+        let mut query: QueryBuilder<'_, Postgres> = QueryBuilder::new(format!(
+            "SELECT id FROM {} WHERE id = ANY(",
+            reference.table()
+        ));
+
+        query.push_bind(ids.clone()).push(")");
+
+        if reference.is_soft_deleted() {
+            query.push(" AND deleted_at IS NULL");
+        }
+
+        let found: Vec<Uuid> = query
+            .build_query_scalar()
+            .fetch_all(&*plexo_engine.pool)
+            .await?;
+
+        let field = field.into();
+
+        for id in ids.into_iter().filter(|id| !found.contains(id)) {
+            self.error(
+                field.clone(),
+                format!(
+                    "references {} {}, which doesn't exist",
+                    reference.name(),
+                    id
+                ),
+            );
+        }
+
+        Ok(self)
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Fails with every problem found so far.
+    pub fn finish(self) -> Result<(), PlexoAppError> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(PlexoAppError::InvalidInput(self.errors))
+        }
+    }
+}
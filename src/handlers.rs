@@ -1,23 +1,33 @@
 use async_graphql::{
-    http::{GraphiQLSource, ALL_WEBSOCKET_PROTOCOLS},
-    Data, Schema,
+    http::{receive_body, GraphiQLSource, MultipartOptions, ALL_WEBSOCKET_PROTOCOLS},
+    Data, ErrorExtensions, Schema,
 };
 
-use async_graphql_poem::{GraphQLProtocol, GraphQLRequest, GraphQLResponse, GraphQLWebSocket};
+use async_graphql_poem::{GraphQLProtocol, GraphQLResponse, GraphQLWebSocket};
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
 use serde_json::Value;
+use tokio_util::{compat::TokioAsyncReadCompatExt, io::ReaderStream};
+use uuid::Uuid;
 
 use crate::{
+    auth::core::PlexoAuthToken,
     commons::authorization::{get_token_from_cookie, get_token_from_headers},
     config::DOMAIN,
+    errors::definitions::PlexoAppError,
     graphql::{mutations::MutationRoot, queries::QueryRoot, subscription::SubscriptionRoot},
+    system::core::Engine,
 };
 
 use poem::{
+    error::ResponseError,
     handler,
-    http::HeaderMap,
+    http::{
+        header::{CACHE_CONTROL, CONTENT_DISPOSITION, CONTENT_TYPE, X_CONTENT_TYPE_OPTIONS},
+        HeaderMap, StatusCode,
+    },
     web::Html,
-    web::{websocket::WebSocket, Data as PoemData},
-    IntoResponse,
+    web::{websocket::WebSocket, Data as PoemData, Path},
+    Body, IntoResponse, Response,
 };
 
 #[handler]
@@ -33,10 +43,33 @@ pub async fn graphiq_handler() -> impl IntoResponse {
 #[handler]
 pub async fn index_handler(
     schema: PoemData<&Schema<QueryRoot, MutationRoot, SubscriptionRoot>>,
+    plexo_engine: PoemData<&Engine>,
     headers: &HeaderMap,
-    req: GraphQLRequest,
-) -> GraphQLResponse {
-    let mut req = req.0;
+    body: Body,
+) -> poem::Result<GraphQLResponse> {
+    let content_type = headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(ToString::to_string);
+
+    let mut options = MultipartOptions::default();
+
+    // Uploads over the attachment size limit are refused while they are received.
+    if content_type
+        .as_deref()
+        .is_some_and(|content_type| content_type.starts_with("multipart/"))
+    {
+        let limits = plexo_engine
+            .get_attachment_limits()
+            .await
+            .map_err(PlexoAppError::from)?;
+
+        options = options.max_file_size(limits.max_attachment_size.max(0) as usize);
+    }
+
+    let mut req = receive_body(content_type, body.into_async_read().compat(), options)
+        .await
+        .map_err(poem::error::BadRequest)?;
     // let mut with_token = false;
 
     if let Some(token) = get_token_from_headers(headers) {
@@ -49,7 +82,7 @@ pub async fn index_handler(
         // with_token = true;
     }
 
-    schema.execute(req).await.into()
+    Ok(schema.execute(req).await.into())
 }
 
 #[handler]
@@ -74,11 +107,93 @@ pub async fn on_connection_init(value: Value) -> async_graphql::Result<Data> {
             if let Some(Value::String(token)) = map.get("Authorization") {
                 let mut data = Data::default();
                 data.insert(token.to_string());
+                // Lets subscriptions know the signed-in member like queries do.
+                data.insert(PlexoAuthToken(token.to_string()));
                 Ok(data)
             } else {
-                Err("Authorization token is required".into())
+                Err(PlexoAppError::MissingAuthorizationToken.extend())
             }
         }
-        _ => Err("Authorization token is required".into()),
+        _ => Err(PlexoAppError::MissingAuthorizationToken.extend()),
     }
 }
+
+/// Serves attachment contents to signed-in members.
+#[handler]
+pub async fn attachment_handler(
+    plexo_engine: PoemData<&Engine>,
+    headers: &HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Response {
+    let Some(token) = get_token_from_headers(headers).or_else(|| get_token_from_cookie(headers))
+    else {
+        return StatusCode::UNAUTHORIZED.into_response();
+    };
+
+    if plexo_engine.auth.extract_claims(&token).is_err() {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+
+    // Attachments of trashed tasks are hidden along with their task.
+    let attachment = sqlx::query!(
+        r#"
+        SELECT attachments.filename, attachments.content_type, attachments.storage_key
+        FROM attachments
+        JOIN tasks ON tasks.id = attachments.task_id
+        WHERE attachments.id = $1 AND tasks.deleted_at IS NULL
+        "#,
+        id,
+    )
+    .fetch_optional(&*plexo_engine.pool)
+    .await;
+
+    let attachment = match attachment {
+        Ok(Some(attachment)) => attachment,
+        Ok(None) => return StatusCode::NOT_FOUND.into_response(),
+        Err(e) => {
+            println!("Failed to serve attachment {}: {:?}", id, e);
+
+            return PlexoAppError::from(e).as_response();
+        }
+    };
+
+    let content = match plexo_engine.storage.get(&attachment.storage_key).await {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return StatusCode::NOT_FOUND.into_response()
+        }
+        Err(e) => {
+            println!("Failed to serve attachment {}: {:?}", id, e);
+
+            return PlexoAppError::from(e).as_response();
+        }
+    };
+
+    // The plain filename is a fallback for clients without RFC 5987 support.
+    let fallback_filename: String = attachment
+        .filename
+        .chars()
+        .map(|c| {
+            if c.is_ascii_graphic() && c != '"' && c != '\\' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(CONTENT_TYPE, attachment.content_type)
+        .header(
+            CONTENT_DISPOSITION,
+            format!(
+                "attachment; filename=\"{}\"; filename*=UTF-8''{}",
+                fallback_filename,
+                utf8_percent_encode(&attachment.filename, NON_ALPHANUMERIC)
+            ),
+        )
+        .header(CACHE_CONTROL, "private, no-store")
+        .header(X_CONTENT_TYPE_OPTIONS, "nosniff")
+        .body(Body::from_bytes_stream(ReaderStream::new(content)))
+}
@@ -5,6 +5,7 @@ pub mod errors;
 pub mod graphql;
 pub mod handlers;
 pub mod llm;
+pub mod mail;
 pub mod openapi;
 pub mod sdk;
 pub mod statics;
@@ -7,7 +7,7 @@ use async_openai::{
     Client,
 };
 
-use crate::config::LLM_MODEL_NAME;
+use crate::{config::LLM_MODEL_NAME, errors::definitions::PlexoAppError};
 
 #[derive(Clone)]
 pub struct LLMEngine {
@@ -20,34 +20,32 @@ impl LLMEngine {
         Self { client }
     }
 
-    pub async fn chat_completion(&self, system_message: String, user_message: String) -> String {
+    pub async fn chat_completion(
+        &self,
+        system_message: String,
+        user_message: String,
+    ) -> Result<String, PlexoAppError> {
         let request = CreateChatCompletionRequestArgs::default()
             .max_tokens(512u16)
             .model(LLM_MODEL_NAME.to_string())
             .messages([
                 ChatCompletionRequestSystemMessageArgs::default()
                     .content(system_message)
-                    .build()
-                    .unwrap()
+                    .build()?
                     .into(),
                 ChatCompletionRequestUserMessageArgs::default()
                     .content(user_message)
-                    .build()
-                    .unwrap()
+                    .build()?
                     .into(),
             ])
-            .build()
-            .unwrap();
+            .build()?;
 
-        let response = self.client.chat().create(request).await.unwrap();
+        let response = self.client.chat().create(request).await?;
 
         response
             .choices
             .first()
-            .unwrap()
-            .message
-            .content
-            .clone()
-            .unwrap()
+            .and_then(|choice| choice.message.content.clone())
+            .ok_or_else(|| PlexoAppError::Upstream("Empty completion".to_string()))
     }
 }
@@ -1,13 +1,15 @@
 use async_graphql::{InputObject, SimpleObject};
 use chrono::{DateTime, Local, Utc};
 use serde::Deserialize;
-use serde_json::Result;
 use sqlx::{query, Pool, Postgres};
 use uuid::Uuid;
 
-use crate::sdk::{
-    task::{Task, TaskPriority, TaskStatus},
-    utilities::DateTimeBridge,
+use crate::{
+    errors::definitions::PlexoAppError,
+    sdk::{
+        task::{Task, TaskPriority, TaskStatus},
+        utilities::DateTimeBridge,
+    },
 };
 
 use super::openai::LLMEngine;
@@ -49,7 +51,7 @@ impl AutoSuggestionsEngine {
     }
 
     fn calculate_task_fingerprint(task: Task) -> String {
-        serde_json::to_string(&task).unwrap()
+        serde_json::to_string(&task).unwrap_or_default()
     }
 
     fn calculate_task_suggestion_fingerprint(task_suggestion: TaskSuggestionInput) -> String {
@@ -78,19 +80,19 @@ impl AutoSuggestionsEngine {
         )
     }
 
-    async fn acquire_tasks_fingerprints(&self) -> Vec<String> {
+    async fn acquire_tasks_fingerprints(&self) -> Result<Vec<String>, sqlx::Error> {
         let tasks = query!(
             r#"
             SELECT *
             FROM tasks
+            WHERE deleted_at IS NULL
             LIMIT 10
             "#,
         )
         .fetch_all(&*self.pool)
-        .await
-        .unwrap();
+        .await?;
 
-        tasks
+        Ok(tasks
             .iter()
             .map(|r| Task {
                 id: r.id,
@@ -106,17 +108,23 @@ impl AutoSuggestionsEngine {
                 owner_id: r.owner_id,
                 count: r.count,
                 parent_id: r.parent_id,
+                rank: r.rank.clone(),
+                milestone_id: r.milestone_id,
+                cycle_id: r.cycle_id,
+                estimate: r.estimate,
+                key: r.key.clone(),
+                state_id: r.state_id,
             })
             .map(Self::calculate_task_fingerprint)
-            .collect::<Vec<String>>()
+            .collect::<Vec<String>>())
     }
 
     pub async fn get_suggestions(
         &self,
         proto_task: TaskSuggestionInput,
         _context: Option<SuggestionContext>,
-    ) -> Result<TaskSuggestionResult> {
-        let tasks_fingerprints = self.acquire_tasks_fingerprints().await;
+    ) -> Result<TaskSuggestionResult, PlexoAppError> {
+        let tasks_fingerprints = self.acquire_tasks_fingerprints().await?;
 
         let system_message = "The user pass to you a list of tasks and you should predict the following based on the input of the user.
         Please return only a valid json with the following struct {
@@ -145,28 +153,27 @@ impl AutoSuggestionsEngine {
         let result = self
             .llm_engine
             .chat_completion(system_message, user_message)
-            .await;
+            .await?;
 
-        let suggestion_result: TaskSuggestionResult = serde_json::from_str(&result)?;
-
-        Ok(suggestion_result)
+        serde_json::from_str(&result)
+            .map_err(|e| PlexoAppError::Upstream(format!("Malformed suggestion: {}", e)))
     }
 
     pub async fn subdivide_task(
         &self,
         task_id: Uuid,
         subtasks: u32,
-    ) -> Result<Vec<TaskSuggestionResult>> {
+    ) -> Result<Vec<TaskSuggestionResult>, PlexoAppError> {
         let task = sqlx::query!(
             r#"
             SELECT * FROM tasks
-            WHERE id = $1
+            WHERE id = $1 AND deleted_at IS NULL
             "#,
             task_id
         )
-        .fetch_one(&*self.pool)
-        .await
-        .unwrap();
+        .fetch_optional(&*self.pool)
+        .await?
+        .ok_or_else(|| PlexoAppError::not_found("Task", task_id))?;
 
         let task = Task {
             id: task.id,
@@ -182,6 +189,12 @@ impl AutoSuggestionsEngine {
             owner_id: task.owner_id,
             count: task.count,
             parent_id: task.parent_id,
+            rank: task.rank,
+            milestone_id: task.milestone_id,
+            cycle_id: task.cycle_id,
+            estimate: task.estimate,
+            key: task.key.clone(),
+            state_id: task.state_id,
         };
 
         let system_message =
@@ -216,11 +229,10 @@ impl AutoSuggestionsEngine {
         let result = self
             .llm_engine
             .chat_completion(system_message, user_message)
-            .await;
-
-        let subtasks: Vec<TaskSuggestionResult> = serde_json::from_str(&result)?;
+            .await?;
 
-        Ok(subtasks)
+        serde_json::from_str(&result)
+            .map_err(|e| PlexoAppError::Upstream(format!("Malformed subtasks: {}", e)))
     }
 
     // pub async fn get_
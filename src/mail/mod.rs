@@ -0,0 +1,2 @@
+pub mod templates;
+pub mod transport;
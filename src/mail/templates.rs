@@ -0,0 +1,241 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::config::{DOMAIN, ORGANIZATION_NAME};
+
+use super::transport::MailMessage;
+
+const LAYOUT_HTML: &str = include_str!("templates/layout.html");
+const LAYOUT_TEXT: &str = include_str!("templates/layout.txt");
+const ASSIGNED_HTML: &str = include_str!("templates/assigned.html");
+const ASSIGNED_TEXT: &str = include_str!("templates/assigned.txt");
+const MENTIONED_HTML: &str = include_str!("templates/mentioned.html");
+const MENTIONED_TEXT: &str = include_str!("templates/mentioned.txt");
+const DUE_SOON_HTML: &str = include_str!("templates/due_soon.html");
+const DUE_SOON_TEXT: &str = include_str!("templates/due_soon.txt");
+
+pub struct TaskSummary {
+    pub id: Uuid,
+    pub key: String,
+    pub title: String,
+}
+
+/// Something an email tells a member about.
+pub enum EmailItem {
+    Assigned {
+        task: TaskSummary,
+    },
+    Mentioned {
+        task: TaskSummary,
+        author: String,
+        body: String,
+    },
+    DueSoon {
+        task: TaskSummary,
+        due_date: DateTime<Utc>,
+    },
+}
+
+/// Fills the `{{name}}` placeholders of a template in a single pass, so values are never read as
+/// placeholders themselves. Unknown placeholders are left as they are.
+fn render(template: &str, vars: &[(&str, &str)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}").map(|end| start + end + 2) else {
+            break;
+        };
+
+        out.push_str(&rest[..start]);
+
+        let name = rest[start + 2..end - 2].trim();
+
+        match vars.iter().find(|(key, _)| *key == name) {
+            Some((_, value)) => out.push_str(value),
+            None => out.push_str(&rest[start..end]),
+        }
+
+        rest = &rest[end..];
+    }
+
+    out.push_str(rest);
+    out
+}
+
+fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+impl EmailItem {
+    fn task(&self) -> &TaskSummary {
+        match self {
+            Self::Assigned { task } | Self::Mentioned { task, .. } | Self::DueSoon { task, .. } => {
+                task
+            }
+        }
+    }
+
+    fn subject(&self) -> String {
+        match self {
+            Self::Assigned { task } => format!("You were assigned to {} {}", task.key, task.title),
+            Self::Mentioned { task, author, .. } => {
+                format!("{} mentioned you on {}", author, task.key)
+            }
+            Self::DueSoon { task, .. } => format!("{} {} is due soon", task.key, task.title),
+        }
+    }
+
+    fn render(&self, html: bool) -> String {
+        let task = self.task();
+        let url = format!("{}/tasks/{}", *DOMAIN, task.id);
+
+        let (template, mut vars) = match self {
+            Self::Assigned { .. } => (if html { ASSIGNED_HTML } else { ASSIGNED_TEXT }, vec![]),
+            Self::Mentioned { author, body, .. } => (
+                if html { MENTIONED_HTML } else { MENTIONED_TEXT },
+                vec![("author", author.clone()), ("body", body.clone())],
+            ),
+            Self::DueSoon { due_date, .. } => (
+                if html { DUE_SOON_HTML } else { DUE_SOON_TEXT },
+                vec![(
+                    "due_date",
+                    due_date.format("on %b %-d at %H:%M UTC").to_string(),
+                )],
+            ),
+        };
+
+        vars.extend([
+            ("key", task.key.clone()),
+            ("title", task.title.clone()),
+            ("url", url),
+        ]);
+
+        let vars: Vec<(&str, String)> = vars
+            .into_iter()
+            .map(|(name, value)| (name, if html { escape_html(&value) } else { value }))
+            .collect();
+
+        render(
+            template,
+            &vars
+                .iter()
+                .map(|(name, value)| (*name, value.as_str()))
+                .collect::<Vec<_>>(),
+        )
+    }
+}
+
+fn layout(to: &str, subject: String, heading: &str, items: &[EmailItem]) -> MailMessage {
+    let html_items: Vec<String> = items.iter().map(|item| item.render(true)).collect();
+    let text_items: Vec<String> = items.iter().map(|item| item.render(false)).collect();
+
+    let html = render(
+        LAYOUT_HTML,
+        &[
+            ("subject", &escape_html(&subject)),
+            ("heading", &escape_html(heading)),
+            ("content", &html_items.join("\n")),
+            ("organization", &escape_html(&ORGANIZATION_NAME)),
+        ],
+    );
+
+    let text = render(
+        LAYOUT_TEXT,
+        &[
+            ("heading", heading),
+            ("content", text_items.join("\n").trim_end()),
+            ("organization", ORGANIZATION_NAME.as_str()),
+        ],
+    );
+
+    MailMessage {
+        to: to.to_string(),
+        subject,
+        html,
+        text,
+    }
+}
+
+/// An email about a single item, sent as soon as it happens.
+pub fn immediate_email(to: &str, item: EmailItem) -> MailMessage {
+    let subject = item.subject();
+
+    layout(to, subject.clone(), &subject, &[item])
+}
+
+/// The daily email gathering every item since the previous one.
+pub fn digest_email(to: &str, items: &[EmailItem]) -> MailMessage {
+    let subject = match items.len() {
+        1 => format!("Your {} digest: 1 update", *ORGANIZATION_NAME),
+        count => format!("Your {} digest: {} updates", *ORGANIZATION_NAME, count),
+    };
+
+    layout(
+        to,
+        subject,
+        "Here is what happened since your last digest",
+        items,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_placeholders_in_a_single_pass() {
+        let rendered = render(
+            "{{ title }} by {{author}}: {{unknown}} {{",
+            &[("title", "{{author}}"), ("author", "Ada")],
+        );
+
+        assert_eq!(rendered, "{{author}} by Ada: {{unknown}} {{");
+    }
+
+    #[test]
+    fn escapes_html() {
+        assert_eq!(
+            escape_html(r#"<a href="x">Tom & Jerry's</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&#39;s&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn escapes_values_in_html_bodies_only() {
+        let message = immediate_email(
+            "ada@plexo.app",
+            EmailItem::Mentioned {
+                task: TaskSummary {
+                    id: Uuid::new_v4(),
+                    key: "PLX-1".into(),
+                    title: "<script>alert(1)</script>".into(),
+                },
+                author: "Ada".into(),
+                body: "See {{url}} & reply".into(),
+            },
+        );
+
+        assert!(message
+            .html
+            .contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+        assert!(!message.html.contains("<script>"));
+        assert!(message.html.contains("See {{url}} &amp; reply"));
+
+        assert!(message.text.contains("<script>alert(1)</script>"));
+        assert!(message.text.contains("See {{url}} & reply"));
+    }
+}
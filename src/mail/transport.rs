@@ -0,0 +1,158 @@
+use std::{io, path::PathBuf};
+
+use async_trait::async_trait;
+use lettre::{
+    message::MultiPart, transport::smtp::authentication::Credentials, AsyncSmtpTransport,
+    AsyncTransport, Message, Tokio1Executor,
+};
+use uuid::Uuid;
+
+use crate::{config::MAIL_FROM, errors::definitions::PlexoAppError};
+
+/// An email with both an HTML and a plain text body.
+#[derive(Clone, Debug)]
+pub struct MailMessage {
+    pub to: String,
+    pub subject: String,
+    pub html: String,
+    pub text: String,
+}
+
+impl MailMessage {
+    fn build(self) -> Result<Message, PlexoAppError> {
+        let from = MAIL_FROM
+            .parse()
+            .map_err(|e| PlexoAppError::Internal(format!("Invalid MAIL_FROM: {}", e)))?;
+
+        let to = self
+            .to
+            .parse()
+            .map_err(|e| PlexoAppError::validation("to", format!("Invalid address: {}", e)))?;
+
+        Message::builder()
+            .from(from)
+            .to(to)
+            .subject(self.subject)
+            .multipart(MultiPart::alternative_plain_html(self.text, self.html))
+            .map_err(|e| PlexoAppError::Internal(e.to_string()))
+    }
+}
+
+/// Delivers emails. Sending is slow, so the engine only calls it from the mail scheduler.
+#[async_trait]
+pub trait MailTransport: Send + Sync {
+    async fn send(&self, message: MailMessage) -> Result<(), PlexoAppError>;
+}
+
+/// Relays emails to an SMTP server over STARTTLS.
+pub struct SmtpTransport {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+impl SmtpTransport {
+    pub fn new(
+        host: &str,
+        port: u16,
+        credentials: Option<(String, String)>,
+    ) -> Result<Self, PlexoAppError> {
+        let mut builder = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)
+            .map_err(|e| PlexoAppError::Internal(format!("Invalid SMTP relay: {}", e)))?
+            .port(port);
+
+        if let Some((username, password)) = credentials {
+            builder = builder.credentials(Credentials::new(username, password));
+        }
+
+        Ok(Self {
+            transport: builder.build(),
+        })
+    }
+}
+
+#[async_trait]
+impl MailTransport for SmtpTransport {
+    async fn send(&self, message: MailMessage) -> Result<(), PlexoAppError> {
+        self.transport
+            .send(message.build()?)
+            .await
+            .map_err(|e| PlexoAppError::Upstream(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+/// Writes emails to a maildir instead of sending them, which is handy without an SMTP server.
+/// Any mail client can open the directory.
+pub struct MaildirTransport {
+    root: PathBuf,
+}
+
+impl MaildirTransport {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    async fn deliver(&self, content: Vec<u8>) -> io::Result<()> {
+        for dir in ["tmp", "new", "cur"] {
+            tokio::fs::create_dir_all(self.root.join(dir)).await?;
+        }
+
+        // Messages are written to tmp first, so readers of new never see a partial one.
+        let name = format!(
+            "{}.{}.plexo",
+            chrono::Utc::now().timestamp(),
+            Uuid::new_v4()
+        );
+        let tmp = self.root.join("tmp").join(&name);
+
+        tokio::fs::write(&tmp, content).await?;
+        tokio::fs::rename(tmp, self.root.join("new").join(name)).await
+    }
+}
+
+#[async_trait]
+impl MailTransport for MaildirTransport {
+    async fn send(&self, message: MailMessage) -> Result<(), PlexoAppError> {
+        let content = message.build()?.formatted();
+
+        self.deliver(content)
+            .await
+            .map_err(|e| PlexoAppError::Internal(format!("Failed to write email: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn maildir_delivers_into_new() {
+        let root = std::env::temp_dir().join(format!("plexo-maildir-{}", Uuid::new_v4()));
+        let transport = MaildirTransport::new(&root);
+
+        transport
+            .send(MailMessage {
+                to: "ada@plexo.app".into(),
+                subject: "Hello".into(),
+                html: "<p>Hello</p>".into(),
+                text: "Hello".into(),
+            })
+            .await
+            .unwrap();
+
+        let delivered: Vec<_> = std::fs::read_dir(root.join("new"))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+
+        assert_eq!(delivered.len(), 1);
+        assert_eq!(std::fs::read_dir(root.join("tmp")).unwrap().count(), 0);
+
+        let content = std::fs::read_to_string(&delivered[0]).unwrap();
+
+        assert!(content.contains("To: ada@plexo.app"));
+        assert!(content.contains("Subject: Hello"));
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
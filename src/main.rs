@@ -11,7 +11,7 @@ use plexo::{
         DATABASE_URL, DOMAIN, GITHUB_CLIENT_ID, GITHUB_CLIENT_SECRET, GITHUB_REDIRECT_URL,
         JWT_ACCESS_TOKEN_SECRET, STATIC_PAGE_ENABLED, URL,
     },
-    handlers::{graphiq_handler, index_handler, ws_switch_handler},
+    handlers::{attachment_handler, graphiq_handler, index_handler, ws_switch_handler},
     openapi::api::Api,
     statics::StaticServer,
     system::{core::Engine, prelude::Prelude, schema::GraphQLSchema},
@@ -42,6 +42,13 @@ async fn main() {
 
     plexo_engine.prelude().await;
 
+    plexo_engine.spawn_recurrence_scheduler();
+    plexo_engine.spawn_cycle_scheduler();
+    plexo_engine.spawn_trash_purge_scheduler();
+    plexo_engine.spawn_rank_rebalance_scheduler();
+    plexo_engine.spawn_mail_scheduler();
+    plexo_engine.spawn_webhook_scheduler();
+
     let schema = plexo_engine.graphql_api_schema();
 
     let api_service = OpenApiService::new(Api::default(), "Hello World", "1.0")
@@ -67,7 +74,9 @@ async fn main() {
         //
         .at("/playground", get(graphiq_handler))
         .at("/graphql", post(index_handler))
-        .at("/graphql/ws", get(ws_switch_handler));
+        .at("/graphql/ws", get(ws_switch_handler))
+        //
+        .at("/attachments/:id", get(attachment_handler));
 
     if *STATIC_PAGE_ENABLED {
         let static_page_root_path = "plexo-platform/out".to_string();
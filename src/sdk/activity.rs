@@ -1,4 +1,4 @@
-use async_graphql::{dataloader::DataLoader, ComplexObject, Context, Enum, Result, SimpleObject};
+use async_graphql::{dataloader::DataLoader, ComplexObject, Context, Enum, SimpleObject};
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
@@ -6,6 +6,8 @@ use std::str::FromStr;
 
 use super::loaders::MemberLoader;
 use super::member::Member;
+use crate::errors::definitions::PlexoAppError;
+use crate::errors::extensions::Result;
 use crate::graphql::auth::extract_context;
 
 #[derive(SimpleObject, Clone, Debug)]
@@ -29,7 +31,10 @@ impl Activity {
 
         let loader = ctx.data::<DataLoader<MemberLoader>>()?;
 
-        Ok(loader.load_one(self.member_id).await?.unwrap())
+        Ok(loader
+            .load_one(self.member_id)
+            .await?
+            .ok_or_else(|| PlexoAppError::not_found("Member", self.member_id))?)
     }
 }
 
@@ -38,6 +43,7 @@ pub enum ActivityOperationType {
     Create,
     Update,
     Delete,
+    Restore,
 }
 
 impl ToString for ActivityOperationType {
@@ -46,6 +52,7 @@ impl ToString for ActivityOperationType {
             ActivityOperationType::Create => "Create".to_string(),
             ActivityOperationType::Update => "Update".to_string(),
             ActivityOperationType::Delete => "Delete".to_string(),
+            ActivityOperationType::Restore => "Restore".to_string(),
         }
     }
 }
@@ -58,11 +65,24 @@ impl FromStr for ActivityOperationType {
             "Create" => Ok(ActivityOperationType::Create),
             "Update" => Ok(ActivityOperationType::Update),
             "Delete" => Ok(ActivityOperationType::Delete),
+            "Restore" => Ok(ActivityOperationType::Restore),
             _ => Err(()),
         }
     }
 }
 
+impl ActivityOperationType {
+    /// Past tense used in webhook event names, such as `created` in `task.created`.
+    pub fn event_name(&self) -> &'static str {
+        match self {
+            ActivityOperationType::Create => "created",
+            ActivityOperationType::Update => "updated",
+            ActivityOperationType::Delete => "deleted",
+            ActivityOperationType::Restore => "restored",
+        }
+    }
+}
+
 #[derive(Enum, Copy, Clone, Eq, PartialEq, Debug)]
 pub enum ActivityResourceType {
     Task,
@@ -71,6 +91,12 @@ pub enum ActivityResourceType {
     Member,
     Label,
     Organization,
+    Comment,
+    TimeEntry,
+    Cycle,
+    Milestone,
+    Attachment,
+    TaskTemplate,
 }
 
 impl ToString for ActivityResourceType {
@@ -82,6 +108,32 @@ impl ToString for ActivityResourceType {
             ActivityResourceType::Member => "Member".to_string(),
             ActivityResourceType::Label => "Label".to_string(),
             ActivityResourceType::Organization => "Organization".to_string(),
+            ActivityResourceType::Comment => "Comment".to_string(),
+            ActivityResourceType::TimeEntry => "TimeEntry".to_string(),
+            ActivityResourceType::Cycle => "Cycle".to_string(),
+            ActivityResourceType::Milestone => "Milestone".to_string(),
+            ActivityResourceType::Attachment => "Attachment".to_string(),
+            ActivityResourceType::TaskTemplate => "TaskTemplate".to_string(),
+        }
+    }
+}
+
+impl ActivityResourceType {
+    /// Resource part of webhook event names, such as `task` in `task.created`.
+    pub fn event_name(&self) -> &'static str {
+        match self {
+            ActivityResourceType::Task => "task",
+            ActivityResourceType::Project => "project",
+            ActivityResourceType::Team => "team",
+            ActivityResourceType::Member => "member",
+            ActivityResourceType::Label => "label",
+            ActivityResourceType::Organization => "organization",
+            ActivityResourceType::Comment => "comment",
+            ActivityResourceType::TimeEntry => "time_entry",
+            ActivityResourceType::Cycle => "cycle",
+            ActivityResourceType::Milestone => "milestone",
+            ActivityResourceType::Attachment => "attachment",
+            ActivityResourceType::TaskTemplate => "task_template",
         }
     }
 }
@@ -97,6 +149,12 @@ impl FromStr for ActivityResourceType {
             "Member" => Ok(ActivityResourceType::Member),
             "Label" => Ok(ActivityResourceType::Label),
             "Organization" => Ok(ActivityResourceType::Organization),
+            "Comment" => Ok(ActivityResourceType::Comment),
+            "TimeEntry" => Ok(ActivityResourceType::TimeEntry),
+            "Cycle" => Ok(ActivityResourceType::Cycle),
+            "Milestone" => Ok(ActivityResourceType::Milestone),
+            "Attachment" => Ok(ActivityResourceType::Attachment),
+            "TaskTemplate" => Ok(ActivityResourceType::TaskTemplate),
             _ => Err(()),
         }
     }
@@ -0,0 +1,61 @@
+use async_graphql::{dataloader::DataLoader, ComplexObject, Context, SimpleObject};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use super::loaders::{MemberLoader, TaskLoader};
+use super::{member::Member, task::Task};
+use crate::config::DOMAIN;
+use crate::errors::extensions::Result;
+use crate::graphql::auth::extract_context;
+
+#[derive(SimpleObject, Clone, Debug)]
+#[graphql(complex)]
+pub struct Attachment {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+
+    pub task_id: Uuid,
+    pub uploader_id: Uuid,
+
+    pub filename: String,
+    pub content_type: String,
+    /// Size in bytes.
+    pub size: i64,
+
+    #[graphql(skip)]
+    pub storage_key: String,
+}
+
+#[derive(SimpleObject, Clone, Debug)]
+pub struct AttachmentLimits {
+    /// Largest accepted file, in bytes.
+    pub max_attachment_size: i64,
+    /// Largest size of all attachments together, in bytes.
+    pub max_attachments_total_size: i64,
+    pub used_size: i64,
+}
+
+#[ComplexObject]
+impl Attachment {
+    /// Download URL. It requires the same session as the API.
+    pub async fn url(&self) -> String {
+        format!("{}/attachments/{}", *DOMAIN, self.id)
+    }
+
+    pub async fn uploader(&self, ctx: &Context<'_>) -> Result<Option<Member>> {
+        let (_plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<MemberLoader>>()?;
+
+        Ok(loader.load_one(self.uploader_id).await?)
+    }
+
+    pub async fn task(&self, ctx: &Context<'_>) -> Result<Option<Task>> {
+        let (_plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<TaskLoader>>()?;
+
+        Ok(loader.load_one(self.task_id).await?)
+    }
+}
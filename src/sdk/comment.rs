@@ -0,0 +1,81 @@
+use async_graphql::{dataloader::DataLoader, ComplexObject, Context, SimpleObject};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use super::connections::{paginate, PlexoConnection};
+use super::loaders::{CommentLoader, MemberLoader, TaskLoader};
+use super::{member::Member, task::Task};
+use crate::errors::extensions::Result;
+use crate::graphql::auth::extract_context;
+
+#[derive(SimpleObject, Clone, Debug)]
+#[graphql(complex)]
+pub struct Comment {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+
+    pub task_id: Uuid,
+    pub author_id: Uuid,
+    pub parent_id: Option<Uuid>,
+
+    pub body: String,
+    pub edited_at: Option<DateTime<Utc>>,
+}
+
+#[ComplexObject]
+impl Comment {
+    pub async fn author(&self, ctx: &Context<'_>) -> Result<Option<Member>> {
+        let (_plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<MemberLoader>>()?;
+
+        Ok(loader.load_one(self.author_id).await?)
+    }
+
+    pub async fn task(&self, ctx: &Context<'_>) -> Result<Option<Task>> {
+        let (_plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<TaskLoader>>()?;
+
+        Ok(loader.load_one(self.task_id).await?)
+    }
+
+    pub async fn parent(&self, ctx: &Context<'_>) -> Result<Option<Comment>> {
+        let (_plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<CommentLoader>>()?;
+
+        Ok(match self.parent_id {
+            Some(parent_id) => loader.load_one(parent_id).await?,
+            None => None,
+        })
+    }
+
+    pub async fn replies(
+        &self,
+        ctx: &Context<'_>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> Result<PlexoConnection<Comment>> {
+        let (plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<CommentLoader>>()?;
+
+        paginate(
+            &plexo_engine.pool,
+            loader,
+            "comments",
+            |query| {
+                query.push("parent_id = ").push_bind(self.id);
+            },
+            after,
+            before,
+            first,
+            last,
+        )
+        .await
+    }
+}
@@ -0,0 +1,287 @@
+use std::sync::Arc;
+
+use async_graphql::{
+    connection::{query, Connection, Edge, OpaqueCursor},
+    dataloader::{DataLoader, Loader},
+    OutputType, SimpleObject,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{types::time::OffsetDateTime, Pool, Postgres, QueryBuilder};
+use uuid::Uuid;
+
+use crate::errors::extensions::{PlexoGraphQLError, Result};
+
+pub const DEFAULT_PAGE_SIZE: usize = 50;
+pub const MAX_PAGE_SIZE: usize = 500;
+
+#[derive(SimpleObject)]
+pub struct ConnectionFields {
+    pub total_count: i64,
+}
+
+/// Position of a row inside a list ordered by `(created_at, id)`. Lists with a custom order
+/// also carry the row offset, which is what they paginate on.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct Cursor {
+    pub created_at: i64,
+    pub id: Uuid,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset: Option<i64>,
+}
+
+impl Cursor {
+    pub fn new(id: Uuid, created_at: OffsetDateTime) -> Self {
+        Self {
+            // Microseconds keep the full precision of Postgres timestamps.
+            created_at: (created_at.unix_timestamp_nanos() / 1_000) as i64,
+            id,
+            offset: None,
+        }
+    }
+
+    pub fn with_offset(id: Uuid, created_at: OffsetDateTime, offset: i64) -> Self {
+        Self {
+            offset: Some(offset),
+            ..Self::new(id, created_at)
+        }
+    }
+
+    pub fn created_at(&self) -> OffsetDateTime {
+        OffsetDateTime::from_unix_timestamp_nanos(self.created_at as i128 * 1_000)
+            .unwrap_or(OffsetDateTime::UNIX_EPOCH)
+    }
+}
+
+pub type PlexoConnection<T> = Connection<OpaqueCursor<Cursor>, T, ConnectionFields>;
+
+/// Tables with soft deleted rows, which connections leave out.
+const SOFT_DELETE_TABLES: [&str; 3] = ["tasks", "projects", "teams"];
+
+fn push_where<F>(query: &mut QueryBuilder<'_, Postgres>, table: &str, push_filter: &F)
+where
+    F: Fn(&mut QueryBuilder<'_, Postgres>),
+{
+    query.push("(");
+    push_filter(query);
+    query.push(")");
+
+    if SOFT_DELETE_TABLES.contains(&table) {
+        query.push(" AND deleted_at IS NULL");
+    }
+}
+
+/// Builds a Relay connection over `table`, ordered by `(created_at, id)`.
+///
+/// `push_filter` must push a boolean SQL predicate over `table`; it is used for both the
+/// page and the total count. Nodes are hydrated through the given data loader.
+pub async fn paginate<L, F>(
+    pool: &Pool<Postgres>,
+    loader: &DataLoader<L>,
+    table: &'static str,
+    push_filter: F,
+    after: Option<String>,
+    before: Option<String>,
+    first: Option<i32>,
+    last: Option<i32>,
+) -> Result<PlexoConnection<L::Value>>
+where
+    L: Loader<Uuid, Error = Arc<sqlx::Error>>,
+    L::Value: OutputType,
+    F: Fn(&mut QueryBuilder<'_, Postgres>),
+{
+    query(
+        after,
+        before,
+        first,
+        last,
+        |after: Option<OpaqueCursor<Cursor>>,
+         before: Option<OpaqueCursor<Cursor>>,
+         first: Option<usize>,
+         last: Option<usize>| async move {
+            let mut count_query = QueryBuilder::new(format!("SELECT COUNT(*) FROM {table} WHERE "));
+            push_where(&mut count_query, table, &push_filter);
+
+            let total_count: i64 = count_query.build_query_scalar().fetch_one(pool).await?;
+
+            // Paginating backwards reads the list in reverse and flips the page afterwards.
+            let backwards = first.is_none() && last.is_some();
+            let limit = first
+                .or(last)
+                .unwrap_or(DEFAULT_PAGE_SIZE)
+                .min(MAX_PAGE_SIZE);
+
+            let mut page_query =
+                QueryBuilder::new(format!("SELECT id, created_at FROM {table} WHERE "));
+            push_where(&mut page_query, table, &push_filter);
+
+            if let Some(after) = &after {
+                page_query
+                    .push(" AND (created_at, id) > (")
+                    .push_bind(after.0.created_at())
+                    .push(", ")
+                    .push_bind(after.0.id)
+                    .push(")");
+            }
+
+            if let Some(before) = &before {
+                page_query
+                    .push(" AND (created_at, id) < (")
+                    .push_bind(before.0.created_at())
+                    .push(", ")
+                    .push_bind(before.0.id)
+                    .push(")");
+            }
+
+            if backwards {
+                page_query.push(" ORDER BY created_at DESC, id DESC LIMIT ");
+            } else {
+                page_query.push(" ORDER BY created_at, id LIMIT ");
+            }
+
+            page_query.push_bind((limit + 1) as i64);
+
+            let mut rows: Vec<(Uuid, OffsetDateTime)> =
+                page_query.build_query_as().fetch_all(pool).await?;
+
+            let has_more = rows.len() > limit;
+            rows.truncate(limit);
+
+            if backwards {
+                rows.reverse();
+            }
+
+            let (has_previous_page, has_next_page) = if backwards {
+                (has_more, before.is_some())
+            } else {
+                (after.is_some(), has_more)
+            };
+
+            let nodes = loader
+                .load_many(rows.iter().map(|(id, _)| *id).collect::<Vec<Uuid>>())
+                .await?;
+
+            let mut connection = Connection::with_additional_fields(
+                has_previous_page,
+                has_next_page,
+                ConnectionFields { total_count },
+            );
+
+            connection
+                .edges
+                .extend(rows.into_iter().filter_map(|(id, created_at)| {
+                    nodes.get(&id).map(|node| {
+                        Edge::new(OpaqueCursor(Cursor::new(id, created_at)), node.clone())
+                    })
+                }));
+
+            Ok::<_, PlexoGraphQLError>(connection)
+        },
+    )
+    .await
+    .map_err(Into::into)
+}
+
+/// Like [`paginate`], but ordered by `order_by` (a trusted SQL `ORDER BY` list over `table`)
+/// before `(created_at, id)`. Arbitrary orders can't be used as keysets, so pages are read by
+/// offset. Without `order_by` this is the same as [`paginate`].
+#[allow(clippy::too_many_arguments)]
+pub async fn paginate_ordered<L, F>(
+    pool: &Pool<Postgres>,
+    loader: &DataLoader<L>,
+    table: &'static str,
+    push_filter: F,
+    order_by: Option<String>,
+    after: Option<String>,
+    before: Option<String>,
+    first: Option<i32>,
+    last: Option<i32>,
+) -> Result<PlexoConnection<L::Value>>
+where
+    L: Loader<Uuid, Error = Arc<sqlx::Error>>,
+    L::Value: OutputType,
+    F: Fn(&mut QueryBuilder<'_, Postgres>),
+{
+    let Some(order_by) = order_by else {
+        return paginate(pool, loader, table, push_filter, after, before, first, last).await;
+    };
+
+    query(
+        after,
+        before,
+        first,
+        last,
+        |after: Option<OpaqueCursor<Cursor>>,
+         before: Option<OpaqueCursor<Cursor>>,
+         first: Option<usize>,
+         last: Option<usize>| async move {
+            let mut count_query = QueryBuilder::new(format!("SELECT COUNT(*) FROM {table} WHERE "));
+            push_where(&mut count_query, table, &push_filter);
+
+            let total_count: i64 = count_query.build_query_scalar().fetch_one(pool).await?;
+
+            let limit = first
+                .or(last)
+                .unwrap_or(DEFAULT_PAGE_SIZE)
+                .min(MAX_PAGE_SIZE) as i64;
+
+            let after_offset = after.as_ref().and_then(|c| c.0.offset);
+            let before_offset = before.as_ref().and_then(|c| c.0.offset);
+
+            let (start, end) = if first.is_none() && last.is_some() {
+                let end = before_offset.unwrap_or(total_count);
+                let start = (end - limit).max(after_offset.map_or(0, |o| o + 1));
+                (start, end)
+            } else {
+                let start = after_offset.map_or(0, |o| o + 1);
+                let end = (start + limit).min(before_offset.unwrap_or(i64::MAX));
+                (start, end)
+            };
+
+            let mut page_query =
+                QueryBuilder::new(format!("SELECT id, created_at FROM {table} WHERE "));
+            push_where(&mut page_query, table, &push_filter);
+
+            page_query
+                .push(format!(" ORDER BY {order_by}, created_at, id OFFSET "))
+                .push_bind(start.max(0))
+                .push(" LIMIT ")
+                .push_bind((end - start).max(0));
+
+            let rows: Vec<(Uuid, OffsetDateTime)> =
+                page_query.build_query_as().fetch_all(pool).await?;
+
+            let nodes = loader
+                .load_many(rows.iter().map(|(id, _)| *id).collect::<Vec<Uuid>>())
+                .await?;
+
+            let mut connection = Connection::with_additional_fields(
+                start > 0,
+                start + (rows.len() as i64) < total_count,
+                ConnectionFields { total_count },
+            );
+
+            connection
+                .edges
+                .extend(
+                    rows.into_iter()
+                        .enumerate()
+                        .filter_map(|(i, (id, created_at))| {
+                            nodes.get(&id).map(|node| {
+                                Edge::new(
+                                    OpaqueCursor(Cursor::with_offset(
+                                        id,
+                                        created_at,
+                                        start + i as i64,
+                                    )),
+                                    node.clone(),
+                                )
+                            })
+                        }),
+                );
+
+            Ok::<_, PlexoGraphQLError>(connection)
+        },
+    )
+    .await
+    .map_err(Into::into)
+}
@@ -0,0 +1,202 @@
+use std::str::FromStr;
+
+use async_graphql::{
+    dataloader::DataLoader, ComplexObject, Context, Enum, InputObject, Json, SimpleObject,
+};
+use chrono::{DateTime, NaiveDate, SecondsFormat, TimeZone, Utc};
+use reqwest::Url;
+use serde_json::Value;
+use uuid::Uuid;
+
+use super::loaders::{CustomFieldLoader, ProjectLoader};
+use super::project::Project;
+use crate::errors::{definitions::PlexoAppError, extensions::Result};
+use crate::graphql::auth::extract_context;
+
+#[derive(SimpleObject, Clone, Debug)]
+#[graphql(complex)]
+pub struct CustomField {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+
+    /// Empty for fields shared by every project of the organization.
+    pub project_id: Option<Uuid>,
+
+    pub name: String,
+    pub description: Option<String>,
+    pub field_type: CustomFieldType,
+    /// Allowed values of select fields.
+    pub options: Vec<String>,
+    pub position: i32,
+}
+
+#[ComplexObject]
+impl CustomField {
+    pub async fn project(&self, ctx: &Context<'_>) -> Result<Option<Project>> {
+        let (_plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<ProjectLoader>>()?;
+
+        Ok(match self.project_id {
+            Some(project_id) => loader.load_one(project_id).await?,
+            None => None,
+        })
+    }
+}
+
+/// Value of a custom field on a task. Text and URL values are strings, numbers are numbers,
+/// dates are RFC 3339 strings in UTC, members are member ids, single selects are one of the
+/// options and multi selects are a list of them.
+#[derive(SimpleObject, Clone, Debug)]
+#[graphql(complex)]
+pub struct CustomFieldValue {
+    pub field_id: Uuid,
+    pub value: Json<Value>,
+}
+
+#[ComplexObject]
+impl CustomFieldValue {
+    pub async fn field(&self, ctx: &Context<'_>) -> Result<Option<CustomField>> {
+        let (_plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<CustomFieldLoader>>()?;
+
+        Ok(loader.load_one(self.field_id).await?)
+    }
+}
+
+#[derive(InputObject, Clone, Debug)]
+pub struct CustomFieldValueInput {
+    pub field_id: Uuid,
+    /// `null` clears the value.
+    pub value: Option<Json<Value>>,
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq, Debug)]
+pub enum CustomFieldType {
+    Text,
+    Number,
+    SingleSelect,
+    MultiSelect,
+    Date,
+    Member,
+    Url,
+}
+
+impl CustomFieldType {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Self::Text => "Text",
+            Self::Number => "Number",
+            Self::SingleSelect => "SingleSelect",
+            Self::MultiSelect => "MultiSelect",
+            Self::Date => "Date",
+            Self::Member => "Member",
+            Self::Url => "Url",
+        }
+    }
+
+    pub fn has_options(&self) -> bool {
+        matches!(self, Self::SingleSelect | Self::MultiSelect)
+    }
+}
+
+impl FromStr for CustomFieldType {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Text" => Ok(Self::Text),
+            "Number" => Ok(Self::Number),
+            "SingleSelect" => Ok(Self::SingleSelect),
+            "MultiSelect" => Ok(Self::MultiSelect),
+            "Date" => Ok(Self::Date),
+            "Member" => Ok(Self::Member),
+            "Url" => Ok(Self::Url),
+            _ => Err(()),
+        }
+    }
+}
+
+impl CustomField {
+    /// Checks `value` against the field type and returns it in the stored form. Member ids are
+    /// only checked for shape here, the caller checks they exist.
+    pub fn normalize_value(&self, value: Value) -> Result<Value, PlexoAppError> {
+        let invalid = |reason: &str| {
+            PlexoAppError::InvalidCustomFieldValue(format!("{}: {reason}", self.name))
+        };
+
+        match self.field_type {
+            CustomFieldType::Text => match value {
+                Value::String(s) => Ok(Value::String(s)),
+                _ => Err(invalid("expected a string")),
+            },
+            CustomFieldType::Number => match value {
+                Value::Number(n) if n.as_f64().is_some_and(f64::is_finite) => Ok(Value::Number(n)),
+                _ => Err(invalid("expected a number")),
+            },
+            CustomFieldType::SingleSelect => match value {
+                Value::String(s) if self.options.contains(&s) => Ok(Value::String(s)),
+                _ => Err(invalid("expected one of the field options")),
+            },
+            CustomFieldType::MultiSelect => {
+                let Value::Array(values) = value else {
+                    return Err(invalid("expected a list of field options"));
+                };
+
+                let mut selected = vec![];
+
+                for value in values {
+                    match value {
+                        Value::String(s) if self.options.contains(&s) => {
+                            if !selected.contains(&s) {
+                                selected.push(s);
+                            }
+                        }
+                        _ => return Err(invalid("expected a list of field options")),
+                    }
+                }
+
+                Ok(Value::Array(
+                    selected.into_iter().map(Value::String).collect(),
+                ))
+            }
+            CustomFieldType::Date => {
+                let Value::String(s) = value else {
+                    return Err(invalid("expected a date"));
+                };
+
+                let date = parse_date(&s).ok_or_else(|| invalid("expected a RFC 3339 date"))?;
+
+                // A single format keeps dates comparable as text when filtering and sorting.
+                Ok(Value::String(
+                    date.to_rfc3339_opts(SecondsFormat::Secs, true),
+                ))
+            }
+            CustomFieldType::Member => match value {
+                Value::String(s) if Uuid::from_str(&s).is_ok() => Ok(Value::String(s)),
+                _ => Err(invalid("expected a member id")),
+            },
+            CustomFieldType::Url => match value {
+                Value::String(s)
+                    if Url::parse(&s).is_ok_and(|url| matches!(url.scheme(), "http" | "https")) =>
+                {
+                    Ok(Value::String(s))
+                }
+                _ => Err(invalid("expected an http or https URL")),
+            },
+        }
+    }
+}
+
+fn parse_date(s: &str) -> Option<DateTime<Utc>> {
+    if let Ok(date_time) = DateTime::parse_from_rfc3339(s) {
+        return Some(date_time.with_timezone(&Utc));
+    }
+
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .ok()?
+        .and_hms_opt(0, 0, 0)
+        .map(|date_time| Utc.from_utc_datetime(&date_time))
+}
@@ -0,0 +1,95 @@
+use async_graphql::{dataloader::DataLoader, ComplexObject, Context, SimpleObject};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use super::connections::{paginate, PlexoConnection};
+use super::loaders::{TaskLoader, TeamLoader};
+use super::{task::Task, team::Team};
+use crate::errors::extensions::Result;
+use crate::graphql::auth::extract_context;
+
+#[derive(SimpleObject, Clone, Debug)]
+#[graphql(complex)]
+pub struct Cycle {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+
+    pub team_id: Uuid,
+
+    pub name: String,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+
+    /// Whether unfinished tasks move to the team's next cycle when this one is closed.
+    pub carry_over: bool,
+    pub closed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(SimpleObject, Clone, Debug, Default)]
+pub struct CycleProgress {
+    pub completed_points: i64,
+    pub total_points: i64,
+
+    pub completed_tasks: i64,
+    pub total_tasks: i64,
+}
+
+#[derive(SimpleObject, Clone, Debug)]
+pub struct TeamVelocity {
+    /// Average completed points over `cycles`.
+    pub average_points: f64,
+    /// Closed cycles the velocity was computed from, most recent first.
+    pub cycles: Vec<CycleVelocity>,
+}
+
+#[derive(SimpleObject, Clone, Debug)]
+pub struct CycleVelocity {
+    pub cycle: Cycle,
+    pub completed_points: i64,
+}
+
+#[ComplexObject]
+impl Cycle {
+    pub async fn team(&self, ctx: &Context<'_>) -> Result<Option<Team>> {
+        let (_plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<TeamLoader>>()?;
+
+        Ok(loader.load_one(self.team_id).await?)
+    }
+
+    pub async fn tasks(
+        &self,
+        ctx: &Context<'_>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> Result<PlexoConnection<Task>> {
+        let (plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<TaskLoader>>()?;
+
+        paginate(
+            &plexo_engine.pool,
+            loader,
+            "tasks",
+            |query| {
+                query.push("cycle_id = ").push_bind(self.id);
+            },
+            after,
+            before,
+            first,
+            last,
+        )
+        .await
+    }
+
+    /// Completed versus total points of the cycle. Tasks without an estimate count as zero points.
+    pub async fn progress(&self, ctx: &Context<'_>) -> Result<CycleProgress> {
+        let (plexo_engine, _member_id) = extract_context(ctx)?;
+
+        Ok(plexo_engine.get_cycle_progress(self.id).await?)
+    }
+}
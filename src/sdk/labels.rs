@@ -1,8 +1,10 @@
+use crate::errors::extensions::Result;
 use crate::graphql::auth::extract_context;
-use async_graphql::{dataloader::DataLoader, ComplexObject, Context, Result, SimpleObject};
+use async_graphql::{dataloader::DataLoader, ComplexObject, Context, SimpleObject};
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
+use super::connections::{paginate, PlexoConnection};
 use super::loaders::TaskLoader;
 use super::task::Task;
 
@@ -20,32 +22,33 @@ pub struct Label {
 
 #[ComplexObject]
 impl Label {
-    pub async fn tasks(&self, ctx: &Context<'_>) -> Result<Vec<Task>> {
+    pub async fn tasks(
+        &self,
+        ctx: &Context<'_>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> Result<PlexoConnection<Task>> {
         let (plexo_engine, _member_id) = extract_context(ctx)?;
 
-        let loader = ctx.data::<DataLoader<TaskLoader>>().unwrap();
-
-        let ids: Vec<Uuid> = sqlx::query!(
-            r#"
-            SELECT task_id FROM labels_by_tasks
-            WHERE label_id = $1
-            "#,
-            &self.id
+        let loader = ctx.data::<DataLoader<TaskLoader>>()?;
+
+        paginate(
+            &plexo_engine.pool,
+            loader,
+            "tasks",
+            |query| {
+                query
+                    .push("id IN (SELECT task_id FROM labels_by_tasks WHERE label_id = ")
+                    .push_bind(self.id)
+                    .push(")");
+            },
+            after,
+            before,
+            first,
+            last,
         )
-        .fetch_all(&*plexo_engine.pool)
         .await
-        .unwrap()
-        .into_iter()
-        .map(|id| id.task_id)
-        .collect();
-
-        let tasks_map = loader.load_many(ids.clone()).await.unwrap();
-
-        let tasks: &Vec<Task> = &ids
-            .into_iter()
-            .map(|id| tasks_map.get(&id).unwrap().clone())
-            .collect();
-
-        Ok(tasks.clone())
     }
 }
@@ -1,17 +1,28 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, str::FromStr, sync::Arc};
 
 use crate::system::core::Engine;
-use async_graphql::dataloader::Loader;
+use async_graphql::{dataloader::Loader, Json};
 
 use uuid::Uuid;
 
 use super::{
+    activity::{Activity, ActivityOperationType, ActivityResourceType},
+    attachment::Attachment,
+    comment::Comment,
+    custom_field::{CustomField, CustomFieldType},
+    cycle::Cycle,
     labels::Label,
     member::{Member, MemberRole},
+    milestone::{Milestone, MilestoneStatus},
+    notification::Notification,
     project::Project,
     task::{Task, TaskPriority, TaskStatus},
+    task_template::TaskTemplate,
     team::{Team, TeamVisibility},
+    time_entry::TimeEntry,
     utilities::DateTimeBridge,
+    webhook::{Webhook, WebhookDelivery, WebhookDeliveryStatus},
+    workflow::{WorkflowState, WorkflowStateCategory},
 };
 
 pub struct TaskLoader(Engine);
@@ -19,6 +30,18 @@ pub struct ProjectLoader(Engine);
 pub struct MemberLoader(Engine);
 pub struct LabelLoader(Engine);
 pub struct TeamLoader(Engine);
+pub struct ActivityLoader(Engine);
+pub struct CommentLoader(Engine);
+pub struct WorkflowStateLoader(Engine);
+pub struct TimeEntryLoader(Engine);
+pub struct CycleLoader(Engine);
+pub struct MilestoneLoader(Engine);
+pub struct CustomFieldLoader(Engine);
+pub struct AttachmentLoader(Engine);
+pub struct TaskTemplateLoader(Engine);
+pub struct NotificationLoader(Engine);
+pub struct WebhookLoader(Engine);
+pub struct WebhookDeliveryLoader(Engine);
 
 impl TaskLoader {
     pub fn new(e: Engine) -> Self {
@@ -50,6 +73,78 @@ impl TeamLoader {
     }
 }
 
+impl ActivityLoader {
+    pub fn new(e: Engine) -> Self {
+        Self(e)
+    }
+}
+
+impl CommentLoader {
+    pub fn new(e: Engine) -> Self {
+        Self(e)
+    }
+}
+
+impl WorkflowStateLoader {
+    pub fn new(e: Engine) -> Self {
+        Self(e)
+    }
+}
+
+impl TimeEntryLoader {
+    pub fn new(e: Engine) -> Self {
+        Self(e)
+    }
+}
+
+impl CycleLoader {
+    pub fn new(e: Engine) -> Self {
+        Self(e)
+    }
+}
+
+impl MilestoneLoader {
+    pub fn new(e: Engine) -> Self {
+        Self(e)
+    }
+}
+
+impl CustomFieldLoader {
+    pub fn new(e: Engine) -> Self {
+        Self(e)
+    }
+}
+
+impl AttachmentLoader {
+    pub fn new(e: Engine) -> Self {
+        Self(e)
+    }
+}
+
+impl TaskTemplateLoader {
+    pub fn new(e: Engine) -> Self {
+        Self(e)
+    }
+}
+
+impl NotificationLoader {
+    pub fn new(e: Engine) -> Self {
+        Self(e)
+    }
+}
+
+impl WebhookLoader {
+    pub fn new(e: Engine) -> Self {
+        Self(e)
+    }
+}
+
+impl WebhookDeliveryLoader {
+    pub fn new(e: Engine) -> Self {
+        Self(e)
+    }
+}
+
 #[async_trait::async_trait]
 impl Loader<Uuid> for TaskLoader {
     type Value = Task;
@@ -58,13 +153,13 @@ impl Loader<Uuid> for TaskLoader {
     async fn load(&self, keys: &'_ [Uuid]) -> Result<HashMap<Uuid, Self::Value>, Self::Error> {
         let tasks = sqlx::query!(
             r#"
-            SELECT * FROM tasks WHERE id  = ANY($1)
+            SELECT * FROM tasks WHERE id  = ANY($1) AND deleted_at IS NULL
             "#,
             &keys
         )
         .fetch_all(&*self.0.pool)
         .await
-        .unwrap();
+        .map_err(Arc::new)?;
 
         //iterate to get the hashmap
         let tasks_map: HashMap<Uuid, Task> = tasks
@@ -86,6 +181,12 @@ impl Loader<Uuid> for TaskLoader {
                         lead_id: task.lead_id,
                         count: task.count,
                         parent_id: task.parent_id,
+                        rank: task.rank.clone(),
+                        milestone_id: task.milestone_id,
+                        cycle_id: task.cycle_id,
+                        estimate: task.estimate,
+                        key: task.key.clone(),
+                        state_id: task.state_id,
                     },
                 )
             })
@@ -104,13 +205,13 @@ impl Loader<Uuid> for ProjectLoader {
     async fn load(&self, keys: &'_ [Uuid]) -> Result<HashMap<Uuid, Self::Value>, Self::Error> {
         let projects = sqlx::query!(
             r#"
-            SELECT * FROM projects WHERE id  = ANY($1)
+            SELECT * FROM projects WHERE id  = ANY($1) AND deleted_at IS NULL
             "#,
             &keys
         )
         .fetch_all(&*self.0.pool)
         .await
-        .unwrap();
+        .map_err(Arc::new)?;
 
         //iterate to get the hashmap
         let projects_map: HashMap<Uuid, Project> = projects
@@ -155,7 +256,7 @@ impl Loader<Uuid> for MemberLoader {
         )
         .fetch_all(&*self.0.pool)
         .await
-        .unwrap();
+        .map_err(Arc::new)?;
 
         //iterate to get the hashmap
         let members_map: HashMap<Uuid, Member> = members
@@ -198,7 +299,7 @@ impl Loader<Uuid> for LabelLoader {
         )
         .fetch_all(&*self.0.pool)
         .await
-        .unwrap();
+        .map_err(Arc::new)?;
 
         //iterate to get the hashmap
         let labels_map: HashMap<Uuid, Label> = labels
@@ -231,13 +332,13 @@ impl Loader<Uuid> for TeamLoader {
     async fn load(&self, keys: &'_ [Uuid]) -> Result<HashMap<Uuid, Self::Value>, Self::Error> {
         let teams = sqlx::query!(
             r#"
-            SELECT * FROM teams WHERE id  = ANY($1)
+            SELECT * FROM teams WHERE id  = ANY($1) AND deleted_at IS NULL
             "#,
             &keys
         )
         .fetch_all(&*self.0.pool)
         .await
-        .unwrap();
+        .map_err(Arc::new)?;
 
         //iterate to get the hashmap
         let teams_map: HashMap<Uuid, Team> = teams
@@ -263,3 +364,488 @@ impl Loader<Uuid> for TeamLoader {
         Ok(teams_map)
     }
 }
+
+#[async_trait::async_trait]
+impl Loader<Uuid> for ActivityLoader {
+    type Value = Activity;
+    type Error = Arc<sqlx::Error>;
+
+    async fn load(&self, keys: &'_ [Uuid]) -> Result<HashMap<Uuid, Self::Value>, Self::Error> {
+        let activities = sqlx::query!(
+            r#"
+            SELECT * FROM activity WHERE id  = ANY($1)
+            "#,
+            &keys
+        )
+        .fetch_all(&*self.0.pool)
+        .await
+        .map_err(Arc::new)?;
+
+        //iterate to get the hashmap
+        // Rows with an unknown operation or resource type are skipped.
+        let activities_map: HashMap<Uuid, Activity> = activities
+            .iter()
+            .filter_map(|r| {
+                Some((
+                    r.id,
+                    Activity {
+                        id: r.id,
+                        created_at: DateTimeBridge::from_offset_date_time(r.created_at),
+                        updated_at: DateTimeBridge::from_offset_date_time(r.updated_at),
+                        resource_type: ActivityResourceType::from_str(&r.resource_type).ok()?,
+                        operation: ActivityOperationType::from_str(&r.operation).ok()?,
+                        resource_id: r.resource_id,
+                        member_id: r.member_id,
+                    },
+                ))
+            })
+            .collect();
+
+        Ok(activities_map)
+    }
+}
+
+#[async_trait::async_trait]
+impl Loader<Uuid> for CommentLoader {
+    type Value = Comment;
+    type Error = Arc<sqlx::Error>;
+
+    async fn load(&self, keys: &'_ [Uuid]) -> Result<HashMap<Uuid, Self::Value>, Self::Error> {
+        let comments = sqlx::query!(
+            r#"
+            SELECT * FROM comments WHERE id  = ANY($1)
+            "#,
+            &keys
+        )
+        .fetch_all(&*self.0.pool)
+        .await
+        .map_err(Arc::new)?;
+
+        //iterate to get the hashmap
+        let comments_map: HashMap<Uuid, Comment> = comments
+            .iter()
+            .map(|r| {
+                (
+                    r.id,
+                    Comment {
+                        id: r.id,
+                        created_at: DateTimeBridge::from_offset_date_time(r.created_at),
+                        updated_at: DateTimeBridge::from_offset_date_time(r.updated_at),
+                        task_id: r.task_id,
+                        author_id: r.author_id,
+                        parent_id: r.parent_id,
+                        body: r.body.clone(),
+                        edited_at: r.edited_at.map(DateTimeBridge::from_offset_date_time),
+                    },
+                )
+            })
+            .collect();
+
+        Ok(comments_map)
+    }
+}
+
+#[async_trait::async_trait]
+impl Loader<Uuid> for WorkflowStateLoader {
+    type Value = WorkflowState;
+    type Error = Arc<sqlx::Error>;
+
+    async fn load(&self, keys: &'_ [Uuid]) -> Result<HashMap<Uuid, Self::Value>, Self::Error> {
+        let states = sqlx::query!(
+            r#"
+            SELECT * FROM workflow_states WHERE id  = ANY($1)
+            "#,
+            &keys
+        )
+        .fetch_all(&*self.0.pool)
+        .await
+        .map_err(Arc::new)?;
+
+        //iterate to get the hashmap
+        let states_map: HashMap<Uuid, WorkflowState> = states
+            .iter()
+            .map(|r| {
+                (
+                    r.id,
+                    WorkflowState {
+                        id: r.id,
+                        created_at: DateTimeBridge::from_offset_date_time(r.created_at),
+                        updated_at: DateTimeBridge::from_offset_date_time(r.updated_at),
+                        project_id: r.project_id,
+                        name: r.name.clone(),
+                        category: WorkflowStateCategory::from_str(&r.category).unwrap_or_default(),
+                        position: r.position,
+                    },
+                )
+            })
+            .collect();
+
+        Ok(states_map)
+    }
+}
+
+#[async_trait::async_trait]
+impl Loader<Uuid> for TimeEntryLoader {
+    type Value = TimeEntry;
+    type Error = Arc<sqlx::Error>;
+
+    async fn load(&self, keys: &'_ [Uuid]) -> Result<HashMap<Uuid, Self::Value>, Self::Error> {
+        let entries = sqlx::query!(
+            r#"
+            SELECT * FROM time_entries WHERE id  = ANY($1)
+            "#,
+            &keys
+        )
+        .fetch_all(&*self.0.pool)
+        .await
+        .map_err(Arc::new)?;
+
+        //iterate to get the hashmap
+        let entries_map: HashMap<Uuid, TimeEntry> = entries
+            .iter()
+            .map(|r| {
+                (
+                    r.id,
+                    TimeEntry {
+                        id: r.id,
+                        created_at: DateTimeBridge::from_offset_date_time(r.created_at),
+                        updated_at: DateTimeBridge::from_offset_date_time(r.updated_at),
+                        task_id: r.task_id,
+                        member_id: r.member_id,
+                        started_at: DateTimeBridge::from_offset_date_time(r.started_at),
+                        ended_at: r.ended_at.map(DateTimeBridge::from_offset_date_time),
+                        duration: r.duration,
+                        note: r.note.clone(),
+                    },
+                )
+            })
+            .collect();
+
+        Ok(entries_map)
+    }
+}
+
+#[async_trait::async_trait]
+impl Loader<Uuid> for CycleLoader {
+    type Value = Cycle;
+    type Error = Arc<sqlx::Error>;
+
+    async fn load(&self, keys: &'_ [Uuid]) -> Result<HashMap<Uuid, Self::Value>, Self::Error> {
+        let cycles = sqlx::query!(
+            r#"
+            SELECT * FROM cycles WHERE id  = ANY($1)
+            "#,
+            &keys
+        )
+        .fetch_all(&*self.0.pool)
+        .await
+        .map_err(Arc::new)?;
+
+        //iterate to get the hashmap
+        let cycles_map: HashMap<Uuid, Cycle> = cycles
+            .iter()
+            .map(|r| {
+                (
+                    r.id,
+                    Cycle {
+                        id: r.id,
+                        created_at: DateTimeBridge::from_offset_date_time(r.created_at),
+                        updated_at: DateTimeBridge::from_offset_date_time(r.updated_at),
+                        team_id: r.team_id,
+                        name: r.name.clone(),
+                        starts_at: DateTimeBridge::from_offset_date_time(r.starts_at),
+                        ends_at: DateTimeBridge::from_offset_date_time(r.ends_at),
+                        carry_over: r.carry_over,
+                        closed_at: r.closed_at.map(DateTimeBridge::from_offset_date_time),
+                    },
+                )
+            })
+            .collect();
+
+        Ok(cycles_map)
+    }
+}
+
+#[async_trait::async_trait]
+impl Loader<Uuid> for MilestoneLoader {
+    type Value = Milestone;
+    type Error = Arc<sqlx::Error>;
+
+    async fn load(&self, keys: &'_ [Uuid]) -> Result<HashMap<Uuid, Self::Value>, Self::Error> {
+        let milestones = sqlx::query!(
+            r#"
+            SELECT * FROM milestones WHERE id  = ANY($1)
+            "#,
+            &keys
+        )
+        .fetch_all(&*self.0.pool)
+        .await
+        .map_err(Arc::new)?;
+
+        //iterate to get the hashmap
+        let milestones_map: HashMap<Uuid, Milestone> = milestones
+            .iter()
+            .map(|r| {
+                (
+                    r.id,
+                    Milestone {
+                        id: r.id,
+                        created_at: DateTimeBridge::from_offset_date_time(r.created_at),
+                        updated_at: DateTimeBridge::from_offset_date_time(r.updated_at),
+                        project_id: r.project_id,
+                        name: r.name.clone(),
+                        description: r.description.clone(),
+                        target_date: r.target_date.map(DateTimeBridge::from_offset_date_time),
+                        status: MilestoneStatus::from_str(&r.status).unwrap_or_default(),
+                    },
+                )
+            })
+            .collect();
+
+        Ok(milestones_map)
+    }
+}
+
+#[async_trait::async_trait]
+impl Loader<Uuid> for CustomFieldLoader {
+    type Value = CustomField;
+    type Error = Arc<sqlx::Error>;
+
+    async fn load(&self, keys: &'_ [Uuid]) -> Result<HashMap<Uuid, Self::Value>, Self::Error> {
+        let custom_fields = sqlx::query!(
+            r#"
+            SELECT * FROM custom_fields WHERE id  = ANY($1)
+            "#,
+            &keys
+        )
+        .fetch_all(&*self.0.pool)
+        .await
+        .map_err(Arc::new)?;
+
+        //iterate to get the hashmap
+        let custom_fields_map: HashMap<Uuid, CustomField> = custom_fields
+            .iter()
+            .filter_map(|r| {
+                Some((
+                    r.id,
+                    CustomField {
+                        id: r.id,
+                        created_at: DateTimeBridge::from_offset_date_time(r.created_at),
+                        updated_at: DateTimeBridge::from_offset_date_time(r.updated_at),
+                        project_id: r.project_id,
+                        name: r.name.clone(),
+                        description: r.description.clone(),
+                        field_type: CustomFieldType::from_str(&r.field_type).ok()?,
+                        options: r.options.clone(),
+                        position: r.position,
+                    },
+                ))
+            })
+            .collect();
+
+        Ok(custom_fields_map)
+    }
+}
+
+#[async_trait::async_trait]
+impl Loader<Uuid> for AttachmentLoader {
+    type Value = Attachment;
+    type Error = Arc<sqlx::Error>;
+
+    async fn load(&self, keys: &'_ [Uuid]) -> Result<HashMap<Uuid, Self::Value>, Self::Error> {
+        let attachments = sqlx::query!(
+            r#"
+            SELECT * FROM attachments WHERE id  = ANY($1)
+            "#,
+            &keys
+        )
+        .fetch_all(&*self.0.pool)
+        .await
+        .map_err(Arc::new)?;
+
+        //iterate to get the hashmap
+        let attachments_map: HashMap<Uuid, Attachment> = attachments
+            .iter()
+            .map(|r| {
+                (
+                    r.id,
+                    Attachment {
+                        id: r.id,
+                        created_at: DateTimeBridge::from_offset_date_time(r.created_at),
+                        updated_at: DateTimeBridge::from_offset_date_time(r.updated_at),
+                        task_id: r.task_id,
+                        uploader_id: r.uploader_id,
+                        filename: r.filename.clone(),
+                        content_type: r.content_type.clone(),
+                        size: r.size,
+                        storage_key: r.storage_key.clone(),
+                    },
+                )
+            })
+            .collect();
+
+        Ok(attachments_map)
+    }
+}
+
+#[async_trait::async_trait]
+impl Loader<Uuid> for TaskTemplateLoader {
+    type Value = TaskTemplate;
+    type Error = Arc<sqlx::Error>;
+
+    async fn load(&self, keys: &'_ [Uuid]) -> Result<HashMap<Uuid, Self::Value>, Self::Error> {
+        let templates = sqlx::query!(
+            r#"
+            SELECT * FROM task_templates WHERE id  = ANY($1)
+            "#,
+            &keys
+        )
+        .fetch_all(&*self.0.pool)
+        .await
+        .map_err(Arc::new)?;
+
+        //iterate to get the hashmap
+        let templates_map: HashMap<Uuid, TaskTemplate> = templates
+            .iter()
+            .filter_map(|r| {
+                Some((
+                    r.id,
+                    TaskTemplate {
+                        id: r.id,
+                        created_at: DateTimeBridge::from_offset_date_time(r.created_at),
+                        updated_at: DateTimeBridge::from_offset_date_time(r.updated_at),
+                        owner_id: r.owner_id,
+                        project_id: r.project_id,
+                        name: r.name.clone(),
+                        description: r.description.clone(),
+                        root: serde_json::from_value(r.tree.clone()).ok()?,
+                    },
+                ))
+            })
+            .collect();
+
+        Ok(templates_map)
+    }
+}
+
+#[async_trait::async_trait]
+impl Loader<Uuid> for NotificationLoader {
+    type Value = Notification;
+    type Error = Arc<sqlx::Error>;
+
+    async fn load(&self, keys: &'_ [Uuid]) -> Result<HashMap<Uuid, Self::Value>, Self::Error> {
+        let notifications = sqlx::query!(
+            r#"
+            SELECT * FROM notifications WHERE id = ANY($1)
+            "#,
+            &keys
+        )
+        .fetch_all(&*self.0.pool)
+        .await
+        .map_err(Arc::new)?;
+
+        let notifications_map: HashMap<Uuid, Notification> = notifications
+            .iter()
+            .map(|r| {
+                (
+                    r.id,
+                    Notification {
+                        id: r.id,
+                        created_at: DateTimeBridge::from_offset_date_time(r.created_at),
+                        updated_at: DateTimeBridge::from_offset_date_time(r.updated_at),
+                        member_id: r.member_id,
+                        activity_id: r.activity_id,
+                        read_at: r.read_at.map(DateTimeBridge::from_offset_date_time),
+                    },
+                )
+            })
+            .collect();
+
+        Ok(notifications_map)
+    }
+}
+
+#[async_trait::async_trait]
+impl Loader<Uuid> for WebhookLoader {
+    type Value = Webhook;
+    type Error = Arc<sqlx::Error>;
+
+    async fn load(&self, keys: &'_ [Uuid]) -> Result<HashMap<Uuid, Self::Value>, Self::Error> {
+        let webhooks = sqlx::query!(
+            r#"
+            SELECT * FROM webhooks WHERE id = ANY($1)
+            "#,
+            &keys
+        )
+        .fetch_all(&*self.0.pool)
+        .await
+        .map_err(Arc::new)?;
+
+        let webhooks_map: HashMap<Uuid, Webhook> = webhooks
+            .iter()
+            .map(|r| {
+                (
+                    r.id,
+                    Webhook {
+                        id: r.id,
+                        created_at: DateTimeBridge::from_offset_date_time(r.created_at),
+                        updated_at: DateTimeBridge::from_offset_date_time(r.updated_at),
+                        owner_id: r.owner_id,
+                        url: r.url.clone(),
+                        events: r.events.clone(),
+                        active: r.active,
+                    },
+                )
+            })
+            .collect();
+
+        Ok(webhooks_map)
+    }
+}
+
+#[async_trait::async_trait]
+impl Loader<Uuid> for WebhookDeliveryLoader {
+    type Value = WebhookDelivery;
+    type Error = Arc<sqlx::Error>;
+
+    async fn load(&self, keys: &'_ [Uuid]) -> Result<HashMap<Uuid, Self::Value>, Self::Error> {
+        let deliveries = sqlx::query!(
+            r#"
+            SELECT * FROM webhook_deliveries WHERE id = ANY($1)
+            "#,
+            &keys
+        )
+        .fetch_all(&*self.0.pool)
+        .await
+        .map_err(Arc::new)?;
+
+        // Rows with an unknown status are skipped.
+        let deliveries_map: HashMap<Uuid, WebhookDelivery> = deliveries
+            .iter()
+            .filter_map(|r| {
+                Some((
+                    r.id,
+                    WebhookDelivery {
+                        id: r.id,
+                        created_at: DateTimeBridge::from_offset_date_time(r.created_at),
+                        updated_at: DateTimeBridge::from_offset_date_time(r.updated_at),
+                        webhook_id: r.webhook_id,
+                        event: r.event.clone(),
+                        payload: Json(r.payload.clone()),
+                        status: WebhookDeliveryStatus::from_str(&r.status).ok()?,
+                        attempts: r.attempts,
+                        next_attempt_at: r
+                            .next_attempt_at
+                            .map(DateTimeBridge::from_offset_date_time),
+                        delivered_at: r.delivered_at.map(DateTimeBridge::from_offset_date_time),
+                        response_status: r.response_status,
+                        response_body: r.response_body.clone(),
+                        error: r.error.clone(),
+                    },
+                ))
+            })
+            .collect();
+
+        Ok(deliveries_map)
+    }
+}
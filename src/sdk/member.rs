@@ -1,12 +1,14 @@
 use std::str::FromStr;
 
-use async_graphql::{dataloader::DataLoader, ComplexObject, Context, Enum, Result, SimpleObject};
+use async_graphql::{dataloader::DataLoader, ComplexObject, Context, Enum, SimpleObject};
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
 use crate::{
+    errors::extensions::Result,
     graphql::auth::extract_context,
     sdk::{
+        connections::{paginate, PlexoConnection},
         project::Project,
         task::{Task, TaskPriority, TaskStatus},
         team::Team,
@@ -42,10 +44,12 @@ impl Member {
     pub async fn owned_tasks(&self, ctx: &Context<'_>) -> Result<Vec<Task>> {
         let (plexo_engine, _member_id) = extract_context(ctx)?;
 
-        let tasks = sqlx::query!(r#"SELECT * FROM tasks WHERE owner_id = $1"#, &self.id)
-            .fetch_all(&*plexo_engine.pool)
-            .await
-            .unwrap();
+        let tasks = sqlx::query!(
+            r#"SELECT * FROM tasks WHERE owner_id = $1 AND deleted_at IS NULL"#,
+            &self.id
+        )
+        .fetch_all(&*plexo_engine.pool)
+        .await?;
 
         Ok(tasks
             .iter()
@@ -63,6 +67,12 @@ impl Member {
                 owner_id: r.owner_id,
                 count: r.count,
                 parent_id: r.parent_id,
+                rank: r.rank.clone(),
+                milestone_id: r.milestone_id,
+                cycle_id: r.cycle_id,
+                estimate: r.estimate,
+                key: r.key.clone(),
+                state_id: r.state_id,
             })
             .collect())
     }
@@ -70,10 +80,12 @@ impl Member {
     pub async fn leading_tasks(&self, ctx: &Context<'_>) -> Result<Vec<Task>> {
         let (plexo_engine, _member_id) = extract_context(ctx)?;
 
-        let tasks = sqlx::query!(r#"SELECT * FROM tasks WHERE lead_id = $1"#, &self.id)
-            .fetch_all(&*plexo_engine.pool)
-            .await
-            .unwrap();
+        let tasks = sqlx::query!(
+            r#"SELECT * FROM tasks WHERE lead_id = $1 AND deleted_at IS NULL"#,
+            &self.id
+        )
+        .fetch_all(&*plexo_engine.pool)
+        .await?;
 
         Ok(tasks
             .iter()
@@ -91,46 +103,55 @@ impl Member {
                 owner_id: r.owner_id,
                 count: r.count,
                 parent_id: r.parent_id,
+                rank: r.rank.clone(),
+                milestone_id: r.milestone_id,
+                cycle_id: r.cycle_id,
+                estimate: r.estimate,
+                key: r.key.clone(),
+                state_id: r.state_id,
             })
             .collect())
     }
 
-    pub async fn tasks(&self, ctx: &Context<'_>) -> Result<Vec<Task>> {
+    pub async fn tasks(
+        &self,
+        ctx: &Context<'_>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> Result<PlexoConnection<Task>> {
         let (plexo_engine, _member_id) = extract_context(ctx)?;
 
-        let loader = ctx.data::<DataLoader<TaskLoader>>().unwrap();
-
-        let ids: Vec<Uuid> = sqlx::query!(
-            r#"
-            SELECT task_id FROM tasks_by_assignees
-            WHERE assignee_id = $1
-            "#,
-            &self.id
+        let loader = ctx.data::<DataLoader<TaskLoader>>()?;
+
+        paginate(
+            &plexo_engine.pool,
+            loader,
+            "tasks",
+            |query| {
+                query
+                    .push("id IN (SELECT task_id FROM tasks_by_assignees WHERE assignee_id = ")
+                    .push_bind(self.id)
+                    .push(")");
+            },
+            after,
+            before,
+            first,
+            last,
         )
-        .fetch_all(&*plexo_engine.pool)
         .await
-        .unwrap()
-        .into_iter()
-        .map(|id| id.task_id)
-        .collect();
-
-        let tasks_map = loader.load_many(ids.clone()).await.unwrap();
-
-        let tasks: &Vec<Task> = &ids
-            .into_iter()
-            .map(|id| tasks_map.get(&id).unwrap().clone())
-            .collect();
-
-        Ok(tasks.clone())
     }
 
     pub async fn owned_projects(&self, ctx: &Context<'_>) -> Result<Vec<Project>> {
         let (plexo_engine, _member_id) = extract_context(ctx)?;
 
-        let projects = sqlx::query!(r#"SELECT * FROM projects WHERE owner_id = $1"#, &self.id)
-            .fetch_all(&*plexo_engine.pool)
-            .await
-            .unwrap();
+        let projects = sqlx::query!(
+            r#"SELECT * FROM projects WHERE owner_id = $1 AND deleted_at IS NULL"#,
+            &self.id
+        )
+        .fetch_all(&*plexo_engine.pool)
+        .await?;
 
         Ok(projects
             .iter()
@@ -152,7 +173,7 @@ impl Member {
     pub async fn projects(&self, ctx: &Context<'_>) -> Result<Vec<Project>> {
         let (plexo_engine, _member_id) = extract_context(ctx)?;
 
-        let loader = ctx.data::<DataLoader<ProjectLoader>>().unwrap();
+        let loader = ctx.data::<DataLoader<ProjectLoader>>()?;
 
         let ids: Vec<Uuid> = sqlx::query!(
             r#"
@@ -162,8 +183,7 @@ impl Member {
             &self.id
         )
         .fetch_all(&*plexo_engine.pool)
-        .await
-        .unwrap()
+        .await?
         .into_iter()
         .map(|id| id.project_id)
         .collect();
@@ -172,7 +192,7 @@ impl Member {
 
         let projects: &Vec<Project> = &ids
             .into_iter()
-            .map(|id| projects_map.get(&id).unwrap().clone())
+            .filter_map(|id| projects_map.get(&id).cloned())
             .collect();
 
         Ok(projects.clone())
@@ -200,7 +220,7 @@ impl Member {
 
         let teams: &Vec<Team> = &ids
             .into_iter()
-            .map(|id| teams_map.get(&id).unwrap().clone())
+            .filter_map(|id| teams_map.get(&id).cloned())
             .collect();
 
         Ok(teams.clone())
@@ -242,3 +262,37 @@ impl FromStr for MemberRole {
         }
     }
 }
+
+/// How a member gets emails about assignments, mentions and due dates.
+#[derive(Enum, Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub enum EmailNotificationMode {
+    /// An email as soon as something happens.
+    #[default]
+    Immediate,
+    /// A single email a day.
+    Digest,
+    Off,
+}
+
+impl EmailNotificationMode {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Self::Immediate => "Immediate",
+            Self::Digest => "Digest",
+            Self::Off => "Off",
+        }
+    }
+}
+
+impl FromStr for EmailNotificationMode {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Immediate" => Ok(Self::Immediate),
+            "Digest" => Ok(Self::Digest),
+            "Off" => Ok(Self::Off),
+            _ => Err(()),
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use std::str::FromStr;
+
+use async_graphql::{dataloader::DataLoader, ComplexObject, Context, Enum, SimpleObject};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use super::connections::{paginate, PlexoConnection};
+use super::loaders::{ProjectLoader, TaskLoader};
+use super::{project::Project, task::Task};
+use crate::errors::extensions::Result;
+use crate::graphql::auth::extract_context;
+
+#[derive(SimpleObject, Clone, Debug)]
+#[graphql(complex)]
+pub struct Milestone {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+
+    pub project_id: Uuid,
+
+    pub name: String,
+    pub description: Option<String>,
+    pub target_date: Option<DateTime<Utc>>,
+    pub status: MilestoneStatus,
+}
+
+#[derive(SimpleObject, Clone, Debug, Default)]
+pub struct MilestoneProgress {
+    pub completed_tasks: i64,
+    pub total_tasks: i64,
+    /// Share of completed tasks, from 0 to 1. Canceled tasks don't count.
+    pub ratio: f64,
+}
+
+#[ComplexObject]
+impl Milestone {
+    pub async fn project(&self, ctx: &Context<'_>) -> Result<Option<Project>> {
+        let (_plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<ProjectLoader>>()?;
+
+        Ok(loader.load_one(self.project_id).await?)
+    }
+
+    pub async fn tasks(
+        &self,
+        ctx: &Context<'_>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> Result<PlexoConnection<Task>> {
+        let (plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<TaskLoader>>()?;
+
+        paginate(
+            &plexo_engine.pool,
+            loader,
+            "tasks",
+            |query| {
+                query.push("milestone_id = ").push_bind(self.id);
+            },
+            after,
+            before,
+            first,
+            last,
+        )
+        .await
+    }
+
+    pub async fn progress(&self, ctx: &Context<'_>) -> Result<MilestoneProgress> {
+        let (plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let counts = sqlx::query!(
+            r#"
+            SELECT
+                COUNT(*) FILTER (WHERE workflow_states.category = 'Completed') AS "completed_tasks!",
+                COUNT(*) FILTER (WHERE workflow_states.category IS DISTINCT FROM 'Canceled') AS "total_tasks!"
+            FROM tasks
+            LEFT JOIN workflow_states ON workflow_states.id = tasks.state_id
+            WHERE tasks.milestone_id = $1 AND tasks.deleted_at IS NULL
+            "#,
+            &self.id
+        )
+        .fetch_one(&*plexo_engine.pool)
+        .await?;
+
+        Ok(MilestoneProgress {
+            completed_tasks: counts.completed_tasks,
+            total_tasks: counts.total_tasks,
+            ratio: if counts.total_tasks > 0 {
+                counts.completed_tasks as f64 / counts.total_tasks as f64
+            } else {
+                0.0
+            },
+        })
+    }
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub enum MilestoneStatus {
+    #[default]
+    Planned,
+    InProgress,
+    Completed,
+    Canceled,
+}
+
+impl MilestoneStatus {
+    pub fn from_optional_str(s: &Option<String>) -> Self {
+        match s {
+            Some(s) => Self::from_str(s.as_str()).unwrap_or(Self::Planned),
+            None => Self::Planned,
+        }
+    }
+
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Self::Planned => "Planned",
+            Self::InProgress => "InProgress",
+            Self::Completed => "Completed",
+            Self::Canceled => "Canceled",
+        }
+    }
+}
+
+impl FromStr for MilestoneStatus {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Planned" => Ok(Self::Planned),
+            "InProgress" => Ok(Self::InProgress),
+            "Completed" => Ok(Self::Completed),
+            "Canceled" => Ok(Self::Canceled),
+            _ => Err(()),
+        }
+    }
+}
@@ -1,8 +1,22 @@
 pub mod activity;
+pub mod attachment;
+pub mod comment;
+pub mod connections;
+pub mod custom_field;
+pub mod cycle;
 pub mod labels;
 pub mod loaders;
 pub mod member;
+pub mod milestone;
+pub mod notification;
 pub mod project;
+pub mod recurrence;
+pub mod search;
 pub mod task;
+pub mod task_template;
 pub mod team;
+pub mod time_entry;
+pub mod trash;
 pub mod utilities;
+pub mod webhook;
+pub mod workflow;
@@ -0,0 +1,34 @@
+use async_graphql::{dataloader::DataLoader, ComplexObject, Context, SimpleObject};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use super::activity::Activity;
+use super::loaders::ActivityLoader;
+use crate::errors::extensions::Result;
+use crate::graphql::auth::extract_context;
+
+/// Activity on a task or a project that a member watches.
+#[derive(SimpleObject, Clone, Debug)]
+#[graphql(complex)]
+pub struct Notification {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+
+    pub member_id: Uuid,
+    pub activity_id: Uuid,
+
+    /// Empty while the notification is unread.
+    pub read_at: Option<DateTime<Utc>>,
+}
+
+#[ComplexObject]
+impl Notification {
+    pub async fn activity(&self, ctx: &Context<'_>) -> Result<Option<Activity>> {
+        let (_plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<ActivityLoader>>()?;
+
+        Ok(loader.load_one(self.activity_id).await?)
+    }
+}
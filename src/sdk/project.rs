@@ -1,18 +1,22 @@
-use async_graphql::{ComplexObject, Context, Result, SimpleObject};
+use async_graphql::{ComplexObject, Context, InputObject, SimpleObject};
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
 use async_graphql::dataloader::DataLoader;
 use poem_openapi::Object;
 
-use super::loaders::{MemberLoader, TeamLoader};
+use super::loaders::{MemberLoader, MilestoneLoader, TaskLoader, TeamLoader, WorkflowStateLoader};
 use crate::{
+    errors::extensions::Result,
     graphql::auth::extract_context,
     sdk::{
+        activity::ActivityResourceType,
+        connections::{paginate, PlexoConnection},
         member::Member,
-        task::{Task, TaskPriority, TaskStatus},
+        milestone::Milestone,
+        task::Task,
         team::Team,
-        utilities::DateTimeBridge,
+        workflow::WorkflowState,
     },
 };
 
@@ -34,20 +38,33 @@ pub struct Project {
     pub due_date: Option<DateTime<Utc>>,
 }
 
+/// What `duplicateProject` copies besides the project, its workflow and its tasks with their
+/// labels and dependencies.
+#[derive(InputObject, Clone, Debug, Default)]
+pub struct DuplicateProjectOptions {
+    /// Prefix of the copy's task keys, the original prefix by default.
+    pub prefix: Option<String>,
+    /// Copies the project members and the task assignees.
+    #[graphql(default)]
+    pub include_members: bool,
+    #[graphql(default)]
+    pub include_teams: bool,
+}
+
 #[ComplexObject]
 impl Project {
     pub async fn owner(&self, ctx: &Context<'_>) -> Result<Option<Member>> {
         let (_plexo_engine, _member_id) = extract_context(ctx)?;
 
-        let loader = ctx.data::<DataLoader<MemberLoader>>().unwrap();
+        let loader = ctx.data::<DataLoader<MemberLoader>>()?;
 
-        Ok(loader.load_one(self.owner_id).await.unwrap())
+        Ok(loader.load_one(self.owner_id).await?)
     }
 
     pub async fn members(&self, ctx: &Context<'_>) -> Result<Vec<Member>> {
         let (plexo_engine, _member_id) = extract_context(ctx)?;
 
-        let loader = ctx.data::<DataLoader<MemberLoader>>().unwrap();
+        let loader = ctx.data::<DataLoader<MemberLoader>>()?;
 
         let ids: Vec<Uuid> = sqlx::query!(
             r#"
@@ -57,60 +74,70 @@ impl Project {
             &self.id
         )
         .fetch_all(&*plexo_engine.pool)
-        .await
-        .unwrap()
+        .await?
         .into_iter()
         .map(|id| id.member_id)
         .collect();
 
-        let members_map = loader.load_many(ids.clone()).await.unwrap();
+        let members_map = loader.load_many(ids.clone()).await?;
 
         let members: &Vec<Member> = &ids
             .into_iter()
-            .map(|id| members_map.get(&id).unwrap().clone())
+            .filter_map(|id| members_map.get(&id).cloned())
             .collect();
 
         Ok(members.clone())
     }
 
-    pub async fn tasks(&self, ctx: &Context<'_>) -> Result<Vec<Task>> {
-        //este caso específico necesita revisión
+    /// Members notified of the activity of the project.
+    pub async fn watchers(&self, ctx: &Context<'_>) -> Result<Vec<Member>> {
         let (plexo_engine, _member_id) = extract_context(ctx)?;
 
-        let tasks = sqlx::query!(
-            r#"
-        SELECT * FROM tasks
-        WHERE project_id = $1"#,
-            &self.id
+        let loader = ctx.data::<DataLoader<MemberLoader>>()?;
+
+        let ids = plexo_engine
+            .get_watcher_ids(ActivityResourceType::Project, self.id)
+            .await?;
+
+        let members_map = loader.load_many(ids.clone()).await?;
+
+        Ok(ids
+            .into_iter()
+            .filter_map(|id| members_map.get(&id).cloned())
+            .collect())
+    }
+
+    pub async fn tasks(
+        &self,
+        ctx: &Context<'_>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> Result<PlexoConnection<Task>> {
+        let (plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<TaskLoader>>()?;
+
+        paginate(
+            &plexo_engine.pool,
+            loader,
+            "tasks",
+            |query| {
+                query.push("project_id = ").push_bind(self.id);
+            },
+            after,
+            before,
+            first,
+            last,
         )
-        .fetch_all(&*plexo_engine.pool)
         .await
-        .unwrap();
-
-        Ok(tasks
-            .iter()
-            .map(|r| Task {
-                id: r.id,
-                created_at: DateTimeBridge::from_offset_date_time(r.created_at),
-                updated_at: DateTimeBridge::from_offset_date_time(r.updated_at),
-                title: r.title.clone(),
-                description: r.description.clone(),
-                status: TaskStatus::from_optional_str(&r.status),
-                priority: TaskPriority::from_optional_str(&r.priority),
-                due_date: r.due_date.map(DateTimeBridge::from_offset_date_time),
-                project_id: r.project_id,
-                lead_id: r.lead_id,
-                owner_id: r.owner_id,
-                count: r.count,
-                parent_id: r.parent_id,
-            })
-            .collect())
     }
 
     pub async fn teams(&self, ctx: &Context<'_>) -> Result<Vec<Team>> {
         let (plexo_engine, _member_id) = extract_context(ctx)?;
 
-        let loader = ctx.data::<DataLoader<TeamLoader>>().unwrap();
+        let loader = ctx.data::<DataLoader<TeamLoader>>()?;
 
         let ids: Vec<Uuid> = sqlx::query!(
             r#"
@@ -120,29 +147,72 @@ impl Project {
             &self.id
         )
         .fetch_all(&*plexo_engine.pool)
-        .await
-        .unwrap()
+        .await?
         .into_iter()
         .map(|id| id.team_id)
         .collect();
 
-        let teams_map = loader.load_many(ids.clone()).await.unwrap();
+        let teams_map = loader.load_many(ids.clone()).await?;
 
         let teams: &Vec<Team> = &ids
             .into_iter()
-            .map(|id| teams_map.get(&id).unwrap().clone())
+            .filter_map(|id| teams_map.get(&id).cloned())
             .collect();
 
         Ok(teams.clone())
     }
 
-    pub async fn leader(&self, ctx: &Context<'_>) -> Option<Member> {
-        let loader = ctx.data::<DataLoader<MemberLoader>>().unwrap();
+    /// Milestones of the project, by target date. Milestones without a date go last.
+    pub async fn milestones(&self, ctx: &Context<'_>) -> Result<Vec<Milestone>> {
+        let (plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<MilestoneLoader>>()?;
+
+        let ids: Vec<Uuid> = sqlx::query!(
+            r#"
+            SELECT id FROM milestones
+            WHERE project_id = $1
+            ORDER BY target_date NULLS LAST, created_at
+            "#,
+            &self.id
+        )
+        .fetch_all(&*plexo_engine.pool)
+        .await?
+        .into_iter()
+        .map(|r| r.id)
+        .collect();
+
+        let milestones_map = loader.load_many(ids.clone()).await?;
+
+        Ok(ids
+            .into_iter()
+            .filter_map(|id| milestones_map.get(&id).cloned())
+            .collect())
+    }
+
+    /// States available to the tasks of this project.
+    pub async fn workflow(&self, ctx: &Context<'_>) -> Result<Vec<WorkflowState>> {
+        let (plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<WorkflowStateLoader>>()?;
+
+        let ids = plexo_engine.get_workflow_state_ids(Some(self.id)).await?;
+
+        let states_map = loader.load_many(ids.clone()).await?;
+
+        Ok(ids
+            .into_iter()
+            .filter_map(|id| states_map.get(&id).cloned())
+            .collect())
+    }
+
+    pub async fn leader(&self, ctx: &Context<'_>) -> Result<Option<Member>> {
+        let loader = ctx.data::<DataLoader<MemberLoader>>()?;
 
         //match to see is project_id is none
-        match self.lead_id {
-            Some(lead_id) => loader.load_one(lead_id).await.unwrap(),
+        Ok(match self.lead_id {
+            Some(lead_id) => loader.load_one(lead_id).await?,
             None => None,
-        }
+        })
     }
 }
@@ -0,0 +1,542 @@
+use std::{fmt, str::FromStr};
+
+use async_graphql::SimpleObject;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, TimeZone, Utc, Weekday};
+
+use crate::errors::definitions::PlexoAppError;
+
+/// Upper bound of periods walked while looking for the next occurrence, so rules that can never
+/// match (e.g. `BYMONTHDAY=31` on `FREQ=YEARLY` started in February) don't loop forever.
+const MAX_PERIODS: i64 = 10_000;
+
+#[derive(SimpleObject, Clone, Debug)]
+pub struct TaskRecurrence {
+    /// Rule in RRULE syntax, e.g. `FREQ=WEEKLY;BYDAY=MO,WE`.
+    pub rule: String,
+    pub starts_at: DateTime<Utc>,
+
+    pub current_occurrence_at: DateTime<Utc>,
+    /// When the next occurrence is created, unless the current one is completed earlier.
+    /// Empty once the rule has no more occurrences.
+    pub next_occurrence_at: Option<DateTime<Utc>>,
+
+    pub occurrences: i32,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum RecurrenceFrequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// The supported subset of RFC 5545 recurrence rules: `FREQ`, `INTERVAL`, `BYDAY` (without
+/// ordinals), `BYMONTHDAY`, `COUNT` and `UNTIL`. Yearly rules repeat in the month of the start
+/// date.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecurrenceRule {
+    pub frequency: RecurrenceFrequency,
+    pub interval: u32,
+    pub by_day: Vec<Weekday>,
+    pub by_month_day: Vec<i32>,
+    pub count: Option<u32>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl RecurrenceRule {
+    /// Returns the first occurrence strictly after `after` of the series starting at `starts_at`.
+    /// The start itself is always the first occurrence.
+    pub fn next_after(
+        &self,
+        starts_at: DateTime<Utc>,
+        after: DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        if starts_at > after {
+            return Some(starts_at);
+        }
+
+        let mut seen = 1;
+
+        for period in 0..MAX_PERIODS {
+            let mut dates = self.period_dates(starts_at.date_naive(), period)?;
+            dates.sort();
+            dates.dedup();
+
+            for date in dates {
+                let occurrence = Utc.from_utc_datetime(&date.and_time(starts_at.time()));
+
+                if occurrence <= starts_at {
+                    continue;
+                }
+
+                if matches!(self.until, Some(until) if occurrence > until) {
+                    return None;
+                }
+
+                seen += 1;
+
+                if matches!(self.count, Some(count) if seen > count) {
+                    return None;
+                }
+
+                if occurrence > after {
+                    return Some(occurrence);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Candidate dates of the `period`-th period after the one containing `start`.
+    fn period_dates(&self, start: NaiveDate, period: i64) -> Option<Vec<NaiveDate>> {
+        let step = period * self.interval as i64;
+
+        Some(match self.frequency {
+            RecurrenceFrequency::Daily => {
+                let date = start.checked_add_signed(Duration::days(step))?;
+
+                if self.matches_by_day(date) && self.matches_by_month_day(date) {
+                    vec![date]
+                } else {
+                    vec![]
+                }
+            }
+            RecurrenceFrequency::Weekly => {
+                let week_start = start
+                    .checked_sub_signed(Duration::days(
+                        start.weekday().num_days_from_monday() as i64
+                    ))?
+                    .checked_add_signed(Duration::weeks(step))?;
+
+                let weekdays = if self.by_day.is_empty() {
+                    vec![start.weekday()]
+                } else {
+                    self.by_day.clone()
+                };
+
+                weekdays
+                    .into_iter()
+                    .filter_map(|weekday| {
+                        week_start.checked_add_signed(Duration::days(
+                            weekday.num_days_from_monday() as i64,
+                        ))
+                    })
+                    .filter(|date| self.matches_by_month_day(*date))
+                    .collect()
+            }
+            RecurrenceFrequency::Monthly => {
+                let months = start.year() as i64 * 12 + start.month0() as i64 + step;
+                let year = i32::try_from(months.div_euclid(12)).ok()?;
+                let month = months.rem_euclid(12) as u32 + 1;
+
+                self.month_dates(start, year, month)
+            }
+            RecurrenceFrequency::Yearly => {
+                let year = i32::try_from(start.year() as i64 + step).ok()?;
+
+                self.month_dates(start, year, start.month())
+            }
+        })
+    }
+
+    fn month_dates(&self, start: NaiveDate, year: i32, month: u32) -> Vec<NaiveDate> {
+        let Some(days_in_month) = days_in_month(year, month) else {
+            return vec![];
+        };
+
+        let days: Vec<u32> = if !self.by_month_day.is_empty() {
+            self.by_month_day
+                .iter()
+                .filter_map(|day| resolve_month_day(*day, days_in_month))
+                .collect()
+        } else if !self.by_day.is_empty() {
+            (1..=days_in_month).collect()
+        } else {
+            vec![start.day()]
+        };
+
+        days.into_iter()
+            .filter_map(|day| NaiveDate::from_ymd_opt(year, month, day))
+            .filter(|date| self.matches_by_day(*date))
+            .collect()
+    }
+
+    fn matches_by_day(&self, date: NaiveDate) -> bool {
+        self.by_day.is_empty() || self.by_day.contains(&date.weekday())
+    }
+
+    fn matches_by_month_day(&self, date: NaiveDate) -> bool {
+        if self.by_month_day.is_empty() {
+            return true;
+        }
+
+        let Some(days_in_month) = days_in_month(date.year(), date.month()) else {
+            return false;
+        };
+
+        self.by_month_day
+            .iter()
+            .any(|day| resolve_month_day(*day, days_in_month) == Some(date.day()))
+    }
+}
+
+fn days_in_month(year: i32, month: u32) -> Option<u32> {
+    let (next_year, next_month) = if month == 12 {
+        (year.checked_add(1)?, 1)
+    } else {
+        (year, month + 1)
+    };
+
+    NaiveDate::from_ymd_opt(next_year, next_month, 1)?
+        .pred_opt()
+        .map(|date| date.day())
+}
+
+/// Negative month days count from the end of the month, `-1` being the last day.
+fn resolve_month_day(day: i32, days_in_month: u32) -> Option<u32> {
+    let day = if day < 0 {
+        days_in_month as i32 + day + 1
+    } else {
+        day
+    };
+
+    (1..=days_in_month as i32)
+        .contains(&day)
+        .then_some(day as u32)
+}
+
+fn parse_weekday(s: &str) -> Option<Weekday> {
+    match s {
+        "MO" => Some(Weekday::Mon),
+        "TU" => Some(Weekday::Tue),
+        "WE" => Some(Weekday::Wed),
+        "TH" => Some(Weekday::Thu),
+        "FR" => Some(Weekday::Fri),
+        "SA" => Some(Weekday::Sat),
+        "SU" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+fn weekday_to_str(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+fn parse_until(s: &str) -> Option<DateTime<Utc>> {
+    if let Ok(date_time) = NaiveDateTime::parse_from_str(s, "%Y%m%dT%H%M%SZ") {
+        return Some(Utc.from_utc_datetime(&date_time));
+    }
+
+    // A plain date includes the whole day.
+    NaiveDate::parse_from_str(s, "%Y%m%d")
+        .ok()?
+        .and_hms_opt(23, 59, 59)
+        .map(|date_time| Utc.from_utc_datetime(&date_time))
+}
+
+impl FromStr for RecurrenceRule {
+    type Err = PlexoAppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| PlexoAppError::InvalidRecurrenceRule(reason.to_string());
+
+        let s = s.trim();
+        let s = s.strip_prefix("RRULE:").unwrap_or(s);
+
+        let mut frequency = None;
+        let mut interval = 1;
+        let mut by_day = vec![];
+        let mut by_month_day = vec![];
+        let mut count = None;
+        let mut until = None;
+
+        for part in s.split(';').filter(|part| !part.is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| invalid("expected KEY=VALUE pairs"))?;
+
+            match key.to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    frequency = Some(match value.to_ascii_uppercase().as_str() {
+                        "DAILY" => RecurrenceFrequency::Daily,
+                        "WEEKLY" => RecurrenceFrequency::Weekly,
+                        "MONTHLY" => RecurrenceFrequency::Monthly,
+                        "YEARLY" => RecurrenceFrequency::Yearly,
+                        _ => return Err(invalid("FREQ must be DAILY, WEEKLY, MONTHLY or YEARLY")),
+                    })
+                }
+                "INTERVAL" => {
+                    interval = value
+                        .parse::<u32>()
+                        .ok()
+                        .filter(|interval| *interval > 0)
+                        .ok_or_else(|| invalid("INTERVAL must be a positive integer"))?
+                }
+                "BYDAY" => {
+                    by_day = value
+                        .split(',')
+                        .map(|day| parse_weekday(&day.to_ascii_uppercase()))
+                        .collect::<Option<Vec<_>>>()
+                        .ok_or_else(|| {
+                            invalid("BYDAY must be a list of MO, TU, WE, TH, FR, SA, SU")
+                        })?
+                }
+                "BYMONTHDAY" => {
+                    by_month_day = value
+                        .split(',')
+                        .map(|day| {
+                            day.parse::<i32>()
+                                .ok()
+                                .filter(|day| *day != 0 && (-31..=31).contains(day))
+                        })
+                        .collect::<Option<Vec<_>>>()
+                        .ok_or_else(|| {
+                            invalid("BYMONTHDAY must be a list of days between -31 and 31")
+                        })?
+                }
+                "COUNT" => {
+                    count = Some(
+                        value
+                            .parse::<u32>()
+                            .ok()
+                            .filter(|count| *count > 0)
+                            .ok_or_else(|| invalid("COUNT must be a positive integer"))?,
+                    )
+                }
+                "UNTIL" => {
+                    until = Some(parse_until(value).ok_or_else(|| {
+                        invalid("UNTIL must look like 20240131 or 20240131T120000Z")
+                    })?)
+                }
+                _ => return Err(invalid(&format!("{key} is not supported"))),
+            }
+        }
+
+        if count.is_some() && until.is_some() {
+            return Err(invalid("COUNT and UNTIL can't be used together"));
+        }
+
+        Ok(Self {
+            frequency: frequency.ok_or_else(|| invalid("FREQ is required"))?,
+            interval,
+            by_day,
+            by_month_day,
+            count,
+            until,
+        })
+    }
+}
+
+impl fmt::Display for RecurrenceRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let frequency = match self.frequency {
+            RecurrenceFrequency::Daily => "DAILY",
+            RecurrenceFrequency::Weekly => "WEEKLY",
+            RecurrenceFrequency::Monthly => "MONTHLY",
+            RecurrenceFrequency::Yearly => "YEARLY",
+        };
+
+        write!(f, "FREQ={frequency}")?;
+
+        if self.interval != 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+
+        if !self.by_day.is_empty() {
+            let days: Vec<&str> = self.by_day.iter().map(|d| weekday_to_str(*d)).collect();
+            write!(f, ";BYDAY={}", days.join(","))?;
+        }
+
+        if !self.by_month_day.is_empty() {
+            let days: Vec<String> = self.by_month_day.iter().map(|d| d.to_string()).collect();
+            write!(f, ";BYMONTHDAY={}", days.join(","))?;
+        }
+
+        if let Some(count) = self.count {
+            write!(f, ";COUNT={count}")?;
+        }
+
+        if let Some(until) = self.until {
+            write!(f, ";UNTIL={}", until.format("%Y%m%dT%H%M%SZ"))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(year: i32, month: u32, day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, 9, 0, 0).unwrap()
+    }
+
+    fn rule(s: &str) -> RecurrenceRule {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_and_prints_rules() {
+        let parsed = rule("RRULE:FREQ=weekly;INTERVAL=2;BYDAY=mo,we;COUNT=4");
+
+        assert_eq!(parsed.frequency, RecurrenceFrequency::Weekly);
+        assert_eq!(parsed.interval, 2);
+        assert_eq!(parsed.by_day, vec![Weekday::Mon, Weekday::Wed]);
+        assert_eq!(parsed.count, Some(4));
+        assert_eq!(
+            parsed.to_string(),
+            "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE;COUNT=4"
+        );
+        assert_eq!(rule(&parsed.to_string()), parsed);
+    }
+
+    #[test]
+    fn plain_until_date_includes_the_whole_day() {
+        let parsed = rule("FREQ=DAILY;UNTIL=20240131");
+
+        assert_eq!(
+            parsed.until,
+            Some(Utc.with_ymd_and_hms(2024, 1, 31, 23, 59, 59).unwrap())
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_rules() {
+        for s in [
+            "",
+            "INTERVAL=2",
+            "FREQ=HOURLY",
+            "FREQ=DAILY;INTERVAL=0",
+            "FREQ=WEEKLY;BYDAY=XX",
+            "FREQ=MONTHLY;BYMONTHDAY=0",
+            "FREQ=MONTHLY;BYMONTHDAY=32",
+            "FREQ=DAILY;COUNT=2;UNTIL=20240131",
+            "FREQ=DAILY;BYSETPOS=1",
+            "FREQ",
+        ] {
+            assert!(
+                s.parse::<RecurrenceRule>().is_err(),
+                "{s} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn start_is_the_first_occurrence() {
+        let start = at(2024, 1, 1);
+
+        assert_eq!(
+            rule("FREQ=DAILY").next_after(start, at(2023, 12, 1)),
+            Some(start)
+        );
+    }
+
+    #[test]
+    fn daily_with_interval() {
+        let start = at(2024, 1, 1);
+
+        assert_eq!(
+            rule("FREQ=DAILY;INTERVAL=2").next_after(start, start),
+            Some(at(2024, 1, 3))
+        );
+    }
+
+    #[test]
+    fn daily_by_day_skips_the_weekend() {
+        let friday = at(2024, 1, 5);
+
+        assert_eq!(
+            rule("FREQ=DAILY;BYDAY=MO,TU,WE,TH,FR").next_after(friday, friday),
+            Some(at(2024, 1, 8))
+        );
+    }
+
+    #[test]
+    fn weekly_by_day() {
+        let monday = at(2024, 1, 1);
+        let weekly = rule("FREQ=WEEKLY;BYDAY=WE,MO");
+
+        assert_eq!(weekly.next_after(monday, monday), Some(at(2024, 1, 3)));
+        assert_eq!(
+            weekly.next_after(monday, at(2024, 1, 3)),
+            Some(at(2024, 1, 8))
+        );
+    }
+
+    #[test]
+    fn monthly_skips_months_without_the_start_day() {
+        let start = at(2024, 1, 31);
+
+        assert_eq!(
+            rule("FREQ=MONTHLY").next_after(start, start),
+            Some(at(2024, 3, 31))
+        );
+    }
+
+    #[test]
+    fn monthly_negative_month_day_is_the_last_day() {
+        let start = at(2024, 1, 31);
+        let last_day = rule("FREQ=MONTHLY;BYMONTHDAY=-1");
+
+        assert_eq!(last_day.next_after(start, start), Some(at(2024, 2, 29)));
+        assert_eq!(
+            last_day.next_after(start, at(2024, 2, 29)),
+            Some(at(2024, 3, 31))
+        );
+    }
+
+    #[test]
+    fn monthly_by_day_picks_every_matching_weekday() {
+        let start = at(2024, 1, 29);
+
+        assert_eq!(
+            rule("FREQ=MONTHLY;BYDAY=FR").next_after(start, start),
+            Some(at(2024, 2, 2))
+        );
+    }
+
+    #[test]
+    fn count_includes_the_start() {
+        let start = at(2024, 1, 1);
+        let three_times = rule("FREQ=DAILY;COUNT=3");
+
+        assert_eq!(
+            three_times.next_after(start, at(2024, 1, 2)),
+            Some(at(2024, 1, 3))
+        );
+        assert_eq!(three_times.next_after(start, at(2024, 1, 3)), None);
+    }
+
+    #[test]
+    fn until_is_inclusive() {
+        let start = at(2024, 1, 1);
+        let until = rule("FREQ=DAILY;UNTIL=20240105");
+
+        assert_eq!(
+            until.next_after(start, at(2024, 1, 4)),
+            Some(at(2024, 1, 5))
+        );
+        assert_eq!(until.next_after(start, at(2024, 1, 5)), None);
+    }
+
+    #[test]
+    fn rules_that_never_match_stop_after_max_periods() {
+        let start = at(2024, 2, 1);
+
+        assert_eq!(
+            rule("FREQ=YEARLY;BYMONTHDAY=31").next_after(start, start),
+            None
+        );
+    }
+}
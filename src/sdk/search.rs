@@ -0,0 +1,94 @@
+use std::str::FromStr;
+
+use async_graphql::{dataloader::DataLoader, ComplexObject, Context, Enum, SimpleObject, Union};
+use uuid::Uuid;
+
+use super::loaders::{MemberLoader, ProjectLoader, TaskLoader, TeamLoader};
+use super::{member::Member, project::Project, task::Task, team::Team};
+use crate::errors::extensions::Result;
+
+#[derive(SimpleObject, Clone, Debug)]
+#[graphql(complex)]
+pub struct SearchResult {
+    pub resource_type: SearchResourceType,
+    pub resource_id: Uuid,
+
+    pub title: String,
+    /// Matching fragments as escaped HTML, with hits wrapped in `<mark>` tags.
+    pub snippet: String,
+
+    pub rank: f32,
+}
+
+#[ComplexObject]
+impl SearchResult {
+    pub async fn resource(&self, ctx: &Context<'_>) -> Result<Option<SearchResource>> {
+        Ok(match self.resource_type {
+            SearchResourceType::Task => ctx
+                .data::<DataLoader<TaskLoader>>()?
+                .load_one(self.resource_id)
+                .await?
+                .map(SearchResource::Task),
+            SearchResourceType::Project => ctx
+                .data::<DataLoader<ProjectLoader>>()?
+                .load_one(self.resource_id)
+                .await?
+                .map(SearchResource::Project),
+            SearchResourceType::Team => ctx
+                .data::<DataLoader<TeamLoader>>()?
+                .load_one(self.resource_id)
+                .await?
+                .map(SearchResource::Team),
+            SearchResourceType::Member => ctx
+                .data::<DataLoader<MemberLoader>>()?
+                .load_one(self.resource_id)
+                .await?
+                .map(SearchResource::Member),
+        })
+    }
+}
+
+#[derive(Union, Clone)]
+pub enum SearchResource {
+    Task(Task),
+    Project(Project),
+    Team(Team),
+    Member(Member),
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq, Debug)]
+pub enum SearchResourceType {
+    Task,
+    Project,
+    Team,
+    Member,
+}
+
+impl SearchResourceType {
+    pub fn all() -> Vec<Self> {
+        vec![Self::Task, Self::Project, Self::Team, Self::Member]
+    }
+
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Self::Task => "Task",
+            Self::Project => "Project",
+            Self::Team => "Team",
+            Self::Member => "Member",
+        }
+    }
+}
+
+impl FromStr for SearchResourceType {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Task" => Ok(Self::Task),
+            "Project" => Ok(Self::Project),
+            "Team" => Ok(Self::Team),
+            "Member" => Ok(Self::Member),
+            _ => Err(()),
+        }
+    }
+}
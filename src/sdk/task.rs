@@ -1,6 +1,6 @@
 use std::str::FromStr;
 
-use async_graphql::{ComplexObject, Context, Enum, Result, SimpleObject};
+use async_graphql::{ComplexObject, Context, Enum, Json, SimpleObject};
 use chrono::{DateTime, Utc};
 
 use async_graphql::dataloader::DataLoader;
@@ -9,10 +9,21 @@ use uuid::Uuid;
 
 use serde::Deserialize;
 
-use super::{labels::Label, member::Member, project::Project};
-
-use super::loaders::{LabelLoader, MemberLoader, ProjectLoader, TaskLoader};
-use crate::graphql::auth::extract_context;
+use super::connections::{paginate, PlexoConnection};
+use super::{
+    activity::ActivityResourceType, attachment::Attachment, comment::Comment,
+    custom_field::CustomFieldValue, cycle::Cycle, labels::Label, member::Member,
+    milestone::Milestone, project::Project, recurrence::TaskRecurrence, time_entry::TimeEntry,
+    utilities::DateTimeBridge, workflow::WorkflowState,
+};
+
+use super::loaders::{
+    AttachmentLoader, CommentLoader, CycleLoader, LabelLoader, MemberLoader, MilestoneLoader,
+    ProjectLoader, TaskLoader, TimeEntryLoader, WorkflowStateLoader,
+};
+use crate::{
+    config::TASK_TREE_MAX_DEPTH, errors::extensions::Result, graphql::auth::extract_context,
+};
 use poem_openapi::Enum as OpenApiEnum;
 use serde::Serialize;
 
@@ -37,29 +48,63 @@ pub struct Task {
     pub lead_id: Option<Uuid>,
 
     pub count: i32,
+    /// Human-readable identifier such as `API-42`.
+    pub key: String,
 
     pub parent_id: Option<Uuid>,
+
+    pub state_id: Option<Uuid>,
+
+    /// Estimate in points.
+    pub estimate: Option<i32>,
+    pub cycle_id: Option<Uuid>,
+
+    pub milestone_id: Option<Uuid>,
+
+    /// Position inside the board column of the task, which is its project and workflow state. Tasks
+    /// without a rank go after the ranked ones.
+    pub rank: Option<String>,
 }
 
 #[ComplexObject]
 impl Task {
+    /// Keys the task had before moving to another project. They still resolve in `taskByKey`.
+    pub async fn key_aliases(&self, ctx: &Context<'_>) -> Result<Vec<String>> {
+        let (plexo_engine, _member_id) = extract_context(ctx)?;
+
+        Ok(sqlx::query!(
+            r#"
+            SELECT key FROM task_keys
+            WHERE task_id = $1 AND key <> $2
+            ORDER BY created_at
+            "#,
+            &self.id,
+            &self.key,
+        )
+        .fetch_all(&*plexo_engine.pool)
+        .await?
+        .into_iter()
+        .map(|r| r.key)
+        .collect())
+    }
+
     pub async fn owner(&self, ctx: &Context<'_>) -> Result<Option<Member>> {
         let (_plexo_engine, _member_id) = extract_context(ctx)?;
 
-        let loader = ctx.data::<DataLoader<MemberLoader>>().unwrap();
+        let loader = ctx.data::<DataLoader<MemberLoader>>()?;
 
         //match to see is project_id is none
-        Ok(loader.load_one(self.owner_id).await.unwrap())
+        Ok(loader.load_one(self.owner_id).await?)
     }
 
     pub async fn leader(&self, ctx: &Context<'_>) -> Result<Option<Member>> {
         let (_plexo_engine, _member_id) = extract_context(ctx)?;
 
-        let loader = ctx.data::<DataLoader<MemberLoader>>().unwrap();
+        let loader = ctx.data::<DataLoader<MemberLoader>>()?;
 
         //match to see is project_id is none
         Ok(match self.lead_id {
-            Some(lead_id) => loader.load_one(lead_id).await.unwrap(),
+            Some(lead_id) => loader.load_one(lead_id).await?,
             None => None,
         })
     }
@@ -67,19 +112,75 @@ impl Task {
     pub async fn project(&self, ctx: &Context<'_>) -> Result<Option<Project>> {
         let (_plexo_engine, _member_id) = extract_context(ctx)?;
 
-        let loader = ctx.data::<DataLoader<ProjectLoader>>().unwrap();
+        let loader = ctx.data::<DataLoader<ProjectLoader>>()?;
 
         //match to see is project_id is none
         Ok(match self.project_id {
-            Some(project_id) => loader.load_one(project_id).await.unwrap(),
+            Some(project_id) => loader.load_one(project_id).await?,
+            None => None,
+        })
+    }
+
+    pub async fn state(&self, ctx: &Context<'_>) -> Result<Option<WorkflowState>> {
+        let (_plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<WorkflowStateLoader>>()?;
+
+        Ok(match self.state_id {
+            Some(state_id) => loader.load_one(state_id).await?,
+            None => None,
+        })
+    }
+
+    pub async fn cycle(&self, ctx: &Context<'_>) -> Result<Option<Cycle>> {
+        let (_plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<CycleLoader>>()?;
+
+        Ok(match self.cycle_id {
+            Some(cycle_id) => loader.load_one(cycle_id).await?,
+            None => None,
+        })
+    }
+
+    pub async fn milestone(&self, ctx: &Context<'_>) -> Result<Option<Milestone>> {
+        let (_plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<MilestoneLoader>>()?;
+
+        Ok(match self.milestone_id {
+            Some(milestone_id) => loader.load_one(milestone_id).await?,
             None => None,
         })
     }
 
+    pub async fn custom_fields(&self, ctx: &Context<'_>) -> Result<Vec<CustomFieldValue>> {
+        let (plexo_engine, _member_id) = extract_context(ctx)?;
+
+        Ok(sqlx::query!(
+            r#"
+            SELECT task_custom_field_values.field_id, task_custom_field_values.value
+            FROM task_custom_field_values
+            JOIN custom_fields ON custom_fields.id = task_custom_field_values.field_id
+            WHERE task_custom_field_values.task_id = $1
+            ORDER BY custom_fields.project_id NULLS FIRST, custom_fields.position, custom_fields.created_at
+            "#,
+            &self.id
+        )
+        .fetch_all(&*plexo_engine.pool)
+        .await?
+        .into_iter()
+        .map(|r| CustomFieldValue {
+            field_id: r.field_id,
+            value: Json(r.value),
+        })
+        .collect())
+    }
+
     pub async fn assignees(&self, ctx: &Context<'_>) -> Result<Vec<Member>> {
         let (plexo_engine, _member_id) = extract_context(ctx)?;
 
-        let loader = ctx.data::<DataLoader<MemberLoader>>().unwrap();
+        let loader = ctx.data::<DataLoader<MemberLoader>>()?;
 
         let ids: Vec<Uuid> = sqlx::query!(
             r#"
@@ -89,26 +190,43 @@ impl Task {
             &self.id
         )
         .fetch_all(&*plexo_engine.pool)
-        .await
-        .unwrap()
+        .await?
         .into_iter()
         .map(|id| id.assignee_id)
         .collect();
 
-        let members_map = loader.load_many(ids.clone()).await.unwrap();
+        let members_map = loader.load_many(ids.clone()).await?;
 
         let members: &Vec<Member> = &ids
             .into_iter()
-            .map(|id| members_map.get(&id).unwrap().clone())
+            .filter_map(|id| members_map.get(&id).cloned())
             .collect();
 
         Ok(members.clone())
     }
 
+    /// Members notified of the activity of the task.
+    pub async fn watchers(&self, ctx: &Context<'_>) -> Result<Vec<Member>> {
+        let (plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<MemberLoader>>()?;
+
+        let ids = plexo_engine
+            .get_watcher_ids(ActivityResourceType::Task, self.id)
+            .await?;
+
+        let members_map = loader.load_many(ids.clone()).await?;
+
+        Ok(ids
+            .into_iter()
+            .filter_map(|id| members_map.get(&id).cloned())
+            .collect())
+    }
+
     pub async fn labels(&self, ctx: &Context<'_>) -> Result<Vec<Label>> {
         let (plexo_engine, _member_id) = extract_context(ctx)?;
 
-        let loader = ctx.data::<DataLoader<LabelLoader>>().unwrap();
+        let loader = ctx.data::<DataLoader<LabelLoader>>()?;
 
         let ids: Vec<Uuid> = sqlx::query!(
             r#"
@@ -118,17 +236,16 @@ impl Task {
             &self.id
         )
         .fetch_all(&*plexo_engine.pool)
-        .await
-        .unwrap()
+        .await?
         .into_iter()
         .map(|id| id.label_id)
         .collect();
 
-        let labels_map = loader.load_many(ids.clone()).await.unwrap();
+        let labels_map = loader.load_many(ids.clone()).await?;
 
         let labels: &Vec<Label> = &ids
             .into_iter()
-            .map(|id| labels_map.get(&id).unwrap().clone())
+            .filter_map(|id| labels_map.get(&id).cloned())
             .collect();
 
         Ok(labels.clone())
@@ -137,41 +254,291 @@ impl Task {
     pub async fn parent(&self, ctx: &Context<'_>) -> Result<Option<Task>> {
         let (_plexo_engine, _member_id) = extract_context(ctx)?;
 
-        let loader = ctx.data::<DataLoader<TaskLoader>>().unwrap();
+        let loader = ctx.data::<DataLoader<TaskLoader>>()?;
 
         Ok(match self.parent_id {
-            Some(parent_id) => loader.load_one(parent_id).await.unwrap(),
+            Some(parent_id) => loader.load_one(parent_id).await?,
             None => None,
         })
     }
 
-    pub async fn subtasks(&self, ctx: &Context<'_>) -> Result<Vec<Task>> {
+    pub async fn subtasks(
+        &self,
+        ctx: &Context<'_>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> Result<PlexoConnection<Task>> {
         let (plexo_engine, _member_id) = extract_context(ctx)?;
 
-        let loader = ctx.data::<DataLoader<TaskLoader>>().unwrap();
+        let loader = ctx.data::<DataLoader<TaskLoader>>()?;
+
+        paginate(
+            &plexo_engine.pool,
+            loader,
+            "tasks",
+            |query| {
+                query.push("parent_id = ").push_bind(self.id);
+            },
+            after,
+            before,
+            first,
+            last,
+        )
+        .await
+    }
+
+    /// Subtasks at every level down to `maxDepth`, direct subtasks being at depth 1. Levels come
+    /// in order, each one by rank.
+    pub async fn descendants(
+        &self,
+        ctx: &Context<'_>,
+        max_depth: Option<i32>,
+    ) -> Result<Vec<Task>> {
+        let (plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<TaskLoader>>()?;
+
+        let max_depth = max_depth
+            .unwrap_or(*TASK_TREE_MAX_DEPTH)
+            .clamp(1, *TASK_TREE_MAX_DEPTH);
 
         let ids: Vec<Uuid> = sqlx::query!(
             r#"
-            SELECT id FROM tasks
-            WHERE parent_id = $1
+            WITH RECURSIVE tree(id, depth) AS (
+                SELECT id, 1 FROM tasks
+                WHERE parent_id = $1 AND deleted_at IS NULL
+                UNION ALL
+                SELECT tasks.id, tree.depth + 1 FROM tasks
+                JOIN tree ON tasks.parent_id = tree.id
+                WHERE tasks.deleted_at IS NULL AND tree.depth < $2
+            )
+            SELECT tasks.id FROM tree
+            JOIN tasks ON tasks.id = tree.id
+            ORDER BY tree.depth, tasks.rank NULLS LAST, tasks.created_at, tasks.id
+            "#,
+            &self.id,
+            max_depth,
+        )
+        .fetch_all(&*plexo_engine.pool)
+        .await?
+        .into_iter()
+        .map(|r| r.id)
+        .collect();
+
+        let tasks_map = loader.load_many(ids.clone()).await?;
+
+        Ok(ids
+            .into_iter()
+            .filter_map(|id| tasks_map.get(&id).cloned())
+            .collect())
+    }
+
+    /// Fraction of the subtasks at every level that are done, from 0 to 1. Canceled subtasks
+    /// don't count. Null for tasks without subtasks or with only canceled ones.
+    pub async fn progress(&self, ctx: &Context<'_>) -> Result<Option<f64>> {
+        let (plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let counts = sqlx::query!(
+            r#"
+            WITH RECURSIVE tree(id, status, depth) AS (
+                SELECT id, status, 1 FROM tasks
+                WHERE parent_id = $1 AND deleted_at IS NULL
+                UNION ALL
+                SELECT tasks.id, tasks.status, tree.depth + 1 FROM tasks
+                JOIN tree ON tasks.parent_id = tree.id
+                WHERE tasks.deleted_at IS NULL AND tree.depth < $2
+            )
+            SELECT
+                COUNT(*) FILTER (WHERE status = $3) AS "done!",
+                COUNT(*) FILTER (WHERE status IS DISTINCT FROM $4) AS "total!"
+            FROM tree
+            "#,
+            &self.id,
+            *TASK_TREE_MAX_DEPTH,
+            TaskStatus::Done.to_str(),
+            TaskStatus::Canceled.to_str(),
+        )
+        .fetch_one(&*plexo_engine.pool)
+        .await?;
+
+        Ok((counts.total > 0).then(|| counts.done as f64 / counts.total as f64))
+    }
+
+    pub async fn blocks(&self, ctx: &Context<'_>) -> Result<Vec<Task>> {
+        let (plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<TaskLoader>>()?;
+
+        let ids: Vec<Uuid> = sqlx::query!(
+            r#"
+            SELECT blocked_id FROM task_dependencies
+            WHERE blocker_id = $1
             "#,
             &self.id
         )
         .fetch_all(&*plexo_engine.pool)
+        .await?
+        .into_iter()
+        .map(|r| r.blocked_id)
+        .collect();
+
+        let tasks_map = loader.load_many(ids.clone()).await?;
+
+        Ok(ids
+            .into_iter()
+            .filter_map(|id| tasks_map.get(&id).cloned())
+            .collect())
+    }
+
+    pub async fn blocked_by(&self, ctx: &Context<'_>) -> Result<Vec<Task>> {
+        let (plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<TaskLoader>>()?;
+
+        let ids: Vec<Uuid> = sqlx::query!(
+            r#"
+            SELECT blocker_id FROM task_dependencies
+            WHERE blocked_id = $1
+            "#,
+            &self.id
+        )
+        .fetch_all(&*plexo_engine.pool)
+        .await?
+        .into_iter()
+        .map(|r| r.blocker_id)
+        .collect();
+
+        let tasks_map = loader.load_many(ids.clone()).await?;
+
+        Ok(ids
+            .into_iter()
+            .filter_map(|id| tasks_map.get(&id).cloned())
+            .collect())
+    }
+
+    pub async fn time_entries(
+        &self,
+        ctx: &Context<'_>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> Result<PlexoConnection<TimeEntry>> {
+        let (plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<TimeEntryLoader>>()?;
+
+        paginate(
+            &plexo_engine.pool,
+            loader,
+            "time_entries",
+            |query| {
+                query.push("task_id = ").push_bind(self.id);
+            },
+            after,
+            before,
+            first,
+            last,
+        )
         .await
-        .unwrap()
+    }
+
+    /// Tracked time in seconds, running timers count up to now.
+    pub async fn total_time_spent(&self, ctx: &Context<'_>) -> Result<i64> {
+        let (plexo_engine, _member_id) = extract_context(ctx)?;
+
+        Ok(sqlx::query!(
+            r#"
+            SELECT COALESCE(SUM(EXTRACT(EPOCH FROM (COALESCE(ended_at, NOW()) - started_at))), 0)::bigint AS "total!"
+            FROM time_entries
+            WHERE task_id = $1
+            "#,
+            &self.id
+        )
+        .fetch_one(&*plexo_engine.pool)
+        .await?
+        .total)
+    }
+
+    pub async fn recurrence(&self, ctx: &Context<'_>) -> Result<Option<TaskRecurrence>> {
+        let (plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let recurrence = sqlx::query!(
+            r#"
+            SELECT * FROM task_recurrences
+            WHERE task_id = $1
+            "#,
+            &self.id
+        )
+        .fetch_optional(&*plexo_engine.pool)
+        .await?;
+
+        Ok(recurrence.map(|r| TaskRecurrence {
+            rule: r.rule,
+            starts_at: DateTimeBridge::from_offset_date_time(r.starts_at),
+            current_occurrence_at: DateTimeBridge::from_offset_date_time(r.current_occurrence_at),
+            next_occurrence_at: r
+                .next_occurrence_at
+                .map(DateTimeBridge::from_offset_date_time),
+            occurrences: r.occurrences,
+        }))
+    }
+
+    pub async fn attachments(&self, ctx: &Context<'_>) -> Result<Vec<Attachment>> {
+        let (plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<AttachmentLoader>>()?;
+
+        let ids: Vec<Uuid> = sqlx::query!(
+            r#"
+            SELECT id FROM attachments
+            WHERE task_id = $1
+            ORDER BY created_at
+            "#,
+            &self.id
+        )
+        .fetch_all(&*plexo_engine.pool)
+        .await?
         .into_iter()
-        .map(|t| t.id)
+        .map(|r| r.id)
         .collect();
 
-        Ok(loader
-            .load_many(ids)
-            .await
-            .unwrap()
-            .values()
-            .map(|x| x.to_owned())
+        let attachments_map = loader.load_many(ids.clone()).await?;
+
+        Ok(ids
+            .into_iter()
+            .filter_map(|id| attachments_map.get(&id).cloned())
             .collect())
     }
+
+    pub async fn comments(
+        &self,
+        ctx: &Context<'_>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> Result<PlexoConnection<Comment>> {
+        let (plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<CommentLoader>>()?;
+
+        paginate(
+            &plexo_engine.pool,
+            loader,
+            "comments",
+            |query| {
+                query.push("task_id = ").push_bind(self.id);
+            },
+            after,
+            before,
+            first,
+            last,
+        )
+        .await
+    }
 }
 
 #[derive(Enum, OpenApiEnum, Copy, Clone, Eq, PartialEq, Debug, Default, Deserialize, Serialize)]
@@ -195,6 +562,18 @@ pub enum TaskPriority {
     Urgent,
 }
 
+/// What happens to the subtasks of a deleted task.
+#[derive(Enum, Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub enum SubtaskDeletion {
+    /// Subtasks at every level go to the trash with the task, and are restored with it.
+    #[default]
+    Delete,
+    /// Direct subtasks move up to the parent of the task.
+    Reparent,
+    /// Direct subtasks become top level tasks.
+    Detach,
+}
+
 impl TaskStatus {
     pub fn from_optional_str(s: &Option<String>) -> Self {
         match s {
@@ -0,0 +1,95 @@
+use async_graphql::{dataloader::DataLoader, ComplexObject, Context, InputObject, SimpleObject};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::loaders::{MemberLoader, ProjectLoader};
+use super::{member::Member, project::Project};
+use crate::{errors::extensions::Result, graphql::auth::extract_context};
+
+#[derive(SimpleObject, Clone, Debug)]
+#[graphql(complex)]
+pub struct TaskTemplate {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+
+    pub owner_id: Uuid,
+    /// Project of the created tasks, unless overridden.
+    pub project_id: Option<Uuid>,
+
+    pub name: String,
+    pub description: Option<String>,
+
+    /// Root task of the template, with its subtasks.
+    pub root: TemplateTask,
+}
+
+/// A task saved in a template.
+#[derive(SimpleObject, Serialize, Deserialize, Clone, Debug)]
+pub struct TemplateTask {
+    pub title: String,
+    pub description: Option<String>,
+    pub priority: Option<String>,
+    /// Due date in seconds after the instantiation date.
+    pub due_offset: Option<i64>,
+    pub estimate: Option<i32>,
+    pub lead_id: Option<Uuid>,
+    #[serde(default)]
+    pub label_ids: Vec<Uuid>,
+    #[serde(default)]
+    pub assignee_ids: Vec<Uuid>,
+    #[serde(default)]
+    pub subtasks: Vec<TemplateTask>,
+}
+
+#[ComplexObject]
+impl TaskTemplate {
+    pub async fn owner(&self, ctx: &Context<'_>) -> Result<Option<Member>> {
+        let (_plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<MemberLoader>>()?;
+
+        Ok(loader.load_one(self.owner_id).await?)
+    }
+
+    pub async fn project(&self, ctx: &Context<'_>) -> Result<Option<Project>> {
+        let (_plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<ProjectLoader>>()?;
+
+        Ok(match self.project_id {
+            Some(project_id) => loader.load_one(project_id).await?,
+            None => None,
+        })
+    }
+
+    /// Number of tasks created by the template.
+    pub async fn task_count(&self) -> i32 {
+        let mut count = 0;
+        let mut pending = vec![&self.root];
+
+        while let Some(task) = pending.pop() {
+            count += 1;
+            pending.extend(task.subtasks.iter());
+        }
+
+        count
+    }
+}
+
+/// Changes applied when instantiating a template. Title, description, lead, labels and
+/// assignees only apply to the root task.
+#[derive(InputObject, Clone, Debug, Default)]
+pub struct TaskTemplateOverrides {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub project_id: Option<Uuid>,
+    pub lead_id: Option<Uuid>,
+    /// Makes the root task a subtask of this task.
+    pub parent_id: Option<Uuid>,
+    pub label_ids: Option<Vec<Uuid>>,
+    pub assignee_ids: Option<Vec<Uuid>>,
+    /// Date due dates are relative to, now by default.
+    pub starts_at: Option<DateTime<Utc>>,
+}
@@ -1,16 +1,17 @@
 use std::str::FromStr;
 
-use async_graphql::{ComplexObject, Context, Enum, Result, SimpleObject};
+use async_graphql::{ComplexObject, Context, Enum, SimpleObject};
 use chrono::{DateTime, Utc};
 
 use crate::{
+    errors::extensions::Result,
     graphql::auth::extract_context,
-    sdk::{member::Member, project::Project},
+    sdk::{cycle::Cycle, member::Member, project::Project},
 };
 use async_graphql::dataloader::DataLoader;
 use uuid::Uuid;
 
-use super::loaders::{MemberLoader, ProjectLoader};
+use super::loaders::{CycleLoader, MemberLoader, ProjectLoader};
 
 #[derive(SimpleObject, Clone, Debug)]
 #[graphql(complex)]
@@ -31,16 +32,16 @@ pub struct Team {
 #[ComplexObject]
 impl Team {
     pub async fn owner(&self, ctx: &Context<'_>) -> Result<Option<Member>> {
-        let loader = ctx.data::<DataLoader<MemberLoader>>().unwrap();
+        let loader = ctx.data::<DataLoader<MemberLoader>>()?;
 
         //match to see is project_id is none
-        Ok(loader.load_one(self.owner_id).await.unwrap())
+        Ok(loader.load_one(self.owner_id).await?)
     }
 
     pub async fn members(&self, ctx: &Context<'_>) -> Result<Vec<Member>> {
         let (plexo_engine, _member_id) = extract_context(ctx)?;
 
-        let loader = ctx.data::<DataLoader<MemberLoader>>().unwrap();
+        let loader = ctx.data::<DataLoader<MemberLoader>>()?;
 
         let ids: Vec<Uuid> = sqlx::query!(
             r#"
@@ -50,17 +51,16 @@ impl Team {
             &self.id
         )
         .fetch_all(&*plexo_engine.pool)
-        .await
-        .unwrap()
+        .await?
         .into_iter()
         .map(|id| id.member_id)
         .collect();
 
-        let members_map = loader.load_many(ids.clone()).await.unwrap();
+        let members_map = loader.load_many(ids.clone()).await?;
 
         let members: &Vec<Member> = &ids
             .into_iter()
-            .map(|id| members_map.get(&id).unwrap().clone())
+            .filter_map(|id| members_map.get(&id).cloned())
             .collect();
 
         Ok(members.clone())
@@ -69,7 +69,7 @@ impl Team {
     pub async fn projects(&self, ctx: &Context<'_>) -> Result<Vec<Project>> {
         let (plexo_engine, _member_id) = extract_context(ctx)?;
 
-        let loader = ctx.data::<DataLoader<ProjectLoader>>().unwrap();
+        let loader = ctx.data::<DataLoader<ProjectLoader>>()?;
 
         let ids: Vec<Uuid> = sqlx::query!(
             r#"
@@ -79,21 +79,48 @@ impl Team {
             &self.id
         )
         .fetch_all(&*plexo_engine.pool)
-        .await
-        .unwrap()
+        .await?
         .into_iter()
         .map(|id| id.project_id)
         .collect();
 
-        let projects_map = loader.load_many(ids.clone()).await.unwrap();
+        let projects_map = loader.load_many(ids.clone()).await?;
 
         let projects: &Vec<Project> = &ids
             .into_iter()
-            .map(|id| projects_map.get(&id).unwrap().clone())
+            .filter_map(|id| projects_map.get(&id).cloned())
             .collect();
 
         Ok(projects.clone())
     }
+
+    /// Cycles of the team, from the oldest to the newest.
+    pub async fn cycles(&self, ctx: &Context<'_>) -> Result<Vec<Cycle>> {
+        let (plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<CycleLoader>>()?;
+
+        let ids: Vec<Uuid> = sqlx::query!(
+            r#"
+            SELECT id FROM cycles
+            WHERE team_id = $1
+            ORDER BY starts_at
+            "#,
+            &self.id
+        )
+        .fetch_all(&*plexo_engine.pool)
+        .await?
+        .into_iter()
+        .map(|r| r.id)
+        .collect();
+
+        let cycles_map = loader.load_many(ids.clone()).await?;
+
+        Ok(ids
+            .into_iter()
+            .filter_map(|id| cycles_map.get(&id).cloned())
+            .collect())
+    }
 }
 
 #[derive(Enum, Copy, Clone, Eq, PartialEq, Debug)]
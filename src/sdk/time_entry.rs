@@ -0,0 +1,91 @@
+use async_graphql::{dataloader::DataLoader, ComplexObject, Context, Enum, SimpleObject};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use super::loaders::{MemberLoader, ProjectLoader, TaskLoader};
+use super::{member::Member, project::Project, task::Task};
+use crate::errors::extensions::Result;
+use crate::graphql::auth::extract_context;
+
+#[derive(SimpleObject, Clone, Debug)]
+#[graphql(complex)]
+pub struct TimeEntry {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+
+    pub task_id: Uuid,
+    pub member_id: Uuid,
+
+    pub started_at: DateTime<Utc>,
+    /// Empty while the timer is running.
+    pub ended_at: Option<DateTime<Utc>>,
+    /// Duration in seconds, empty while the timer is running.
+    pub duration: Option<i32>,
+
+    pub note: Option<String>,
+}
+
+#[ComplexObject]
+impl TimeEntry {
+    pub async fn task(&self, ctx: &Context<'_>) -> Result<Option<Task>> {
+        let (_plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<TaskLoader>>()?;
+
+        Ok(loader.load_one(self.task_id).await?)
+    }
+
+    pub async fn member(&self, ctx: &Context<'_>) -> Result<Option<Member>> {
+        let (_plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<MemberLoader>>()?;
+
+        Ok(loader.load_one(self.member_id).await?)
+    }
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq, Debug)]
+pub enum TimeReportGrouping {
+    Project,
+    Member,
+    Day,
+}
+
+/// Time tracked for one group of a time report. Only the field matching the grouping is set.
+#[derive(SimpleObject, Clone, Debug)]
+#[graphql(complex)]
+pub struct TimeReportRow {
+    pub project_id: Option<Uuid>,
+    pub member_id: Option<Uuid>,
+    pub day: Option<DateTime<Utc>>,
+
+    /// Tracked time in seconds, running timers count up to now.
+    pub total_time_spent: i64,
+    pub entries: i64,
+}
+
+#[ComplexObject]
+impl TimeReportRow {
+    pub async fn project(&self, ctx: &Context<'_>) -> Result<Option<Project>> {
+        let (_plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<ProjectLoader>>()?;
+
+        Ok(match self.project_id {
+            Some(project_id) => loader.load_one(project_id).await?,
+            None => None,
+        })
+    }
+
+    pub async fn member(&self, ctx: &Context<'_>) -> Result<Option<Member>> {
+        let (_plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<MemberLoader>>()?;
+
+        Ok(match self.member_id {
+            Some(member_id) => loader.load_one(member_id).await?,
+            None => None,
+        })
+    }
+}
@@ -0,0 +1,73 @@
+use async_graphql::{dataloader::DataLoader, ComplexObject, Context, Enum, SimpleObject};
+use chrono::{DateTime, Utc};
+use std::str::FromStr;
+use uuid::Uuid;
+
+use super::loaders::MemberLoader;
+use super::member::Member;
+use crate::errors::extensions::Result;
+use crate::graphql::auth::extract_context;
+
+/// A deleted task, project or team that can still be restored.
+#[derive(SimpleObject, Clone, Debug)]
+#[graphql(complex)]
+pub struct TrashItem {
+    pub resource_type: TrashResourceType,
+    pub resource_id: Uuid,
+    /// Title of the task, or name of the project or team.
+    pub title: String,
+
+    pub deleted_at: DateTime<Utc>,
+    pub deleted_by_id: Option<Uuid>,
+    /// When the item will be purged for good.
+    pub purge_at: DateTime<Utc>,
+}
+
+#[ComplexObject]
+impl TrashItem {
+    pub async fn deleted_by(&self, ctx: &Context<'_>) -> Result<Option<Member>> {
+        let (_plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let Some(deleted_by_id) = self.deleted_by_id else {
+            return Ok(None);
+        };
+
+        let loader = ctx.data::<DataLoader<MemberLoader>>()?;
+
+        Ok(loader.load_one(deleted_by_id).await?)
+    }
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq, Debug)]
+pub enum TrashResourceType {
+    Task,
+    Project,
+    Team,
+}
+
+impl TrashResourceType {
+    pub fn all() -> Vec<Self> {
+        vec![Self::Task, Self::Project, Self::Team]
+    }
+
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Self::Task => "Task",
+            Self::Project => "Project",
+            Self::Team => "Team",
+        }
+    }
+}
+
+impl FromStr for TrashResourceType {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Task" => Ok(Self::Task),
+            "Project" => Ok(Self::Project),
+            "Team" => Ok(Self::Team),
+            _ => Err(()),
+        }
+    }
+}
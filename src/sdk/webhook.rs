@@ -0,0 +1,101 @@
+use std::str::FromStr;
+
+use async_graphql::{dataloader::DataLoader, ComplexObject, Context, Enum, Json, SimpleObject};
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use uuid::Uuid;
+
+use super::loaders::{MemberLoader, WebhookLoader};
+use super::member::Member;
+use crate::{errors::extensions::Result, graphql::auth::extract_context};
+
+/// An endpoint that gets the activity matching its event filters, signed with its secret.
+#[derive(SimpleObject, Clone, Debug)]
+#[graphql(complex)]
+pub struct Webhook {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+
+    pub owner_id: Uuid,
+    pub url: String,
+    /// Event filters such as `task.created`, `task.*` for every task event, or `*`.
+    pub events: Vec<String>,
+    pub active: bool,
+}
+
+#[ComplexObject]
+impl Webhook {
+    pub async fn owner(&self, ctx: &Context<'_>) -> Result<Option<Member>> {
+        let (_plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<MemberLoader>>()?;
+
+        Ok(loader.load_one(self.owner_id).await?)
+    }
+}
+
+#[derive(SimpleObject, Clone, Debug)]
+#[graphql(complex)]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+
+    pub webhook_id: Uuid,
+    pub event: String,
+    /// Body posted to the webhook, with the resource and the actor.
+    pub payload: Json<Value>,
+
+    pub status: WebhookDeliveryStatus,
+    pub attempts: i32,
+    /// Empty once the delivery succeeded or ran out of attempts.
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    /// HTTP status of the last response, if the endpoint answered.
+    pub response_status: Option<i32>,
+    pub response_body: Option<String>,
+    pub error: Option<String>,
+}
+
+#[ComplexObject]
+impl WebhookDelivery {
+    pub async fn webhook(&self, ctx: &Context<'_>) -> Result<Option<Webhook>> {
+        let (_plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<WebhookLoader>>()?;
+
+        Ok(loader.load_one(self.webhook_id).await?)
+    }
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq, Debug)]
+pub enum WebhookDeliveryStatus {
+    Pending,
+    Succeeded,
+    /// Every attempt failed.
+    Failed,
+}
+
+impl WebhookDeliveryStatus {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "Pending",
+            Self::Succeeded => "Succeeded",
+            Self::Failed => "Failed",
+        }
+    }
+}
+
+impl FromStr for WebhookDeliveryStatus {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Pending" => Ok(Self::Pending),
+            "Succeeded" => Ok(Self::Succeeded),
+            "Failed" => Ok(Self::Failed),
+            _ => Err(()),
+        }
+    }
+}
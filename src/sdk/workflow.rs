@@ -0,0 +1,110 @@
+use std::str::FromStr;
+
+use async_graphql::{dataloader::DataLoader, ComplexObject, Context, Enum, SimpleObject};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::loaders::{ProjectLoader, WorkflowStateLoader};
+use super::project::Project;
+use crate::errors::extensions::Result;
+use crate::graphql::auth::extract_context;
+
+/// A named step of a project workflow. States with no project make up the default workflow.
+#[derive(SimpleObject, Clone, Debug)]
+#[graphql(complex)]
+pub struct WorkflowState {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+
+    pub project_id: Option<Uuid>,
+
+    pub name: String,
+    pub category: WorkflowStateCategory,
+    pub position: i32,
+}
+
+#[ComplexObject]
+impl WorkflowState {
+    pub async fn project(&self, ctx: &Context<'_>) -> Result<Option<Project>> {
+        let (_plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<ProjectLoader>>()?;
+
+        Ok(match self.project_id {
+            Some(project_id) => loader.load_one(project_id).await?,
+            None => None,
+        })
+    }
+
+    /// States a task in this state may move to.
+    pub async fn transitions(&self, ctx: &Context<'_>) -> Result<Vec<WorkflowState>> {
+        let (plexo_engine, _member_id) = extract_context(ctx)?;
+
+        let loader = ctx.data::<DataLoader<WorkflowStateLoader>>()?;
+
+        let ids: Vec<Uuid> = sqlx::query!(
+            r#"
+            SELECT to_state_id FROM workflow_transitions
+            JOIN workflow_states ON workflow_states.id = workflow_transitions.to_state_id
+            WHERE from_state_id = $1
+            ORDER BY workflow_states.position
+            "#,
+            &self.id
+        )
+        .fetch_all(&*plexo_engine.pool)
+        .await?
+        .into_iter()
+        .map(|r| r.to_state_id)
+        .collect();
+
+        let states_map = loader.load_many(ids.clone()).await?;
+
+        Ok(ids
+            .into_iter()
+            .filter_map(|id| states_map.get(&id).cloned())
+            .collect())
+    }
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq, Debug, Default, Deserialize, Serialize)]
+pub enum WorkflowStateCategory {
+    #[default]
+    Unstarted,
+    Started,
+    Completed,
+    Canceled,
+}
+
+impl WorkflowStateCategory {
+    pub fn from_optional_str(s: &Option<String>) -> Self {
+        match s {
+            Some(s) => Self::from_str(s.as_str()).unwrap_or(Self::Unstarted),
+            None => Self::Unstarted,
+        }
+    }
+
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Self::Unstarted => "Unstarted",
+            Self::Started => "Started",
+            Self::Completed => "Completed",
+            Self::Canceled => "Canceled",
+        }
+    }
+}
+
+impl FromStr for WorkflowStateCategory {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Unstarted" => Ok(Self::Unstarted),
+            "Started" => Ok(Self::Started),
+            "Completed" => Ok(Self::Completed),
+            "Canceled" => Ok(Self::Canceled),
+            _ => Err(()),
+        }
+    }
+}
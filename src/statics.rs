@@ -150,11 +150,12 @@ impl Endpoint for StaticServer {
                 return unauthorized_response;
             };
 
-            let Ok(_member_id) = self
+            let Some(_member_id) = self
                 .plexo_engine
                 .auth
                 .extract_claims(&auth_token)
-                .map(|token_claims| token_claims.member_id())
+                .ok()
+                .and_then(|token_claims| token_claims.member_id())
             else {
                 return unauthorized_response;
             };
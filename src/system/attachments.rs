@@ -0,0 +1,49 @@
+use sqlx::PgConnection;
+
+use crate::{
+    config::{MAX_ATTACHMENTS_TOTAL_SIZE_BYTES, MAX_ATTACHMENT_SIZE_BYTES},
+    sdk::attachment::AttachmentLimits,
+};
+
+use super::core::Engine;
+
+/// Serializes attachment inserts for the rest of the transaction, so concurrent uploads can't
+/// go over the quota together.
+pub async fn lock_attachment_quota(conn: &mut PgConnection) -> Result<(), sqlx::Error> {
+    sqlx::query!("SELECT pg_advisory_xact_lock(hashtext('attachments.size'))")
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(())
+}
+
+/// Organization attachment limits, falling back to the server defaults, and the size used so
+/// far.
+pub async fn attachment_limits(conn: &mut PgConnection) -> Result<AttachmentLimits, sqlx::Error> {
+    let limits = sqlx::query!(
+        r#"
+        SELECT
+            (SELECT max_attachment_size FROM self LIMIT 1) AS max_attachment_size,
+            (SELECT max_attachments_total_size FROM self LIMIT 1) AS max_attachments_total_size,
+            (SELECT COALESCE(SUM(size), 0)::bigint FROM attachments) AS "used_size!"
+        "#
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(AttachmentLimits {
+        max_attachment_size: limits
+            .max_attachment_size
+            .unwrap_or(*MAX_ATTACHMENT_SIZE_BYTES),
+        max_attachments_total_size: limits
+            .max_attachments_total_size
+            .unwrap_or(*MAX_ATTACHMENTS_TOTAL_SIZE_BYTES),
+        used_size: limits.used_size,
+    })
+}
+
+impl Engine {
+    pub async fn get_attachment_limits(&self) -> Result<AttachmentLimits, sqlx::Error> {
+        attachment_limits(&mut *self.pool.acquire().await?).await
+    }
+}
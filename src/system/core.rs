@@ -1,9 +1,12 @@
+use std::sync::Arc;
+
 use sqlx::{Pool, Postgres};
 use uuid::Uuid;
 
 use crate::{
     auth::engine::AuthEngine,
     llm::suggestions::AutoSuggestionsEngine,
+    mail::transport::MailTransport,
     sdk::{
         activity::{Activity, ActivityOperationType, ActivityResourceType},
         member::{Member, MemberRole},
@@ -11,7 +14,11 @@ use crate::{
     },
 };
 
-use super::subscriptions::SubscriptionManager;
+use super::{
+    mail::default_mail_transport,
+    storage::{LocalStorage, StorageBackend},
+    subscriptions::SubscriptionManager,
+};
 
 #[derive(Clone)]
 pub struct Engine {
@@ -19,6 +26,8 @@ pub struct Engine {
     pub auth: AuthEngine,
     pub subscription_manager: SubscriptionManager,
     pub auto_suggestions_engine: AutoSuggestionsEngine,
+    pub storage: Arc<dyn StorageBackend>,
+    pub mail: Arc<dyn MailTransport>,
 }
 
 impl Engine {
@@ -32,6 +41,8 @@ impl Engine {
             auth,
             subscription_manager,
             auto_suggestions_engine,
+            storage: Arc::new(LocalStorage::default()),
+            mail: default_mail_transport(),
         }
     }
 
@@ -71,6 +82,42 @@ impl Engine {
         .ok()
     }
 
+    pub async fn get_member_by_id(&self, id: Uuid) -> Option<Member> {
+        sqlx::query!(
+            "
+            SELECT
+                id,
+                email,
+                name,
+                created_at,
+                updated_at,
+                github_id,
+                google_id,
+                photo_url,
+                role
+            FROM members
+            WHERE
+                id = $1
+            ",
+            id,
+        )
+        .fetch_one(&*self.pool)
+        .await
+        .map(|m| Member {
+            id: m.id,
+            email: m.email.clone(),
+            name: m.name.clone(),
+            created_at: DateTimeBridge::from_offset_date_time(m.created_at),
+            updated_at: DateTimeBridge::from_offset_date_time(m.updated_at),
+            github_id: m.github_id.as_ref().map(|id| id.to_string()),
+            google_id: m.google_id.as_ref().map(|id| id.to_string()),
+            photo_url: m.photo_url.clone(),
+            role: MemberRole::from_optional_str(&m.role),
+            password_hash: None,
+        })
+        .ok()
+    }
+
     pub async fn get_member_by_email(&self, email: String) -> Option<Member> {
         sqlx::query!(
             "
@@ -113,7 +160,7 @@ impl Engine {
         email: String,
         name: String,
         github_id: String,
-    ) -> Member {
+    ) -> Result<Member, sqlx::Error> {
         let m = sqlx::query!(
             "
             INSERT INTO members (email, name, github_id)
@@ -134,10 +181,9 @@ impl Engine {
             github_id,
         )
         .fetch_one(&*self.pool)
-        .await
-        .unwrap();
+        .await?;
 
-        Member {
+        Ok(Member {
             id: m.id,
             email: m.email,
             name: m.name,
@@ -148,7 +194,7 @@ impl Engine {
             photo_url: m.photo_url,
             role: MemberRole::from_optional_str(&m.role),
             password_hash: None,
-        }
+        })
     }
 
     pub async fn create_member_from_email(
@@ -156,7 +202,7 @@ impl Engine {
         email: String,
         name: String,
         password_hash: String,
-    ) -> Option<Member> {
+    ) -> Result<Member, sqlx::Error> {
         let m = sqlx::query!(
             "
             INSERT INTO members (email, name, password_hash)
@@ -191,7 +237,6 @@ impl Engine {
             role: MemberRole::from_optional_str(&m.role),
             password_hash: None,
         })
-        .ok()
     }
 
     pub async fn set_organization_name(&self, name: String) {
@@ -220,7 +265,7 @@ impl Engine {
                 // .unwrap();
             }
             Err(_) => {
-                sqlx::query!(
+                if let Err(e) = sqlx::query!(
                     r#"
                     INSERT INTO self (name)
                     VALUES ($1)
@@ -229,7 +274,9 @@ impl Engine {
                 )
                 .execute(&*self.pool)
                 .await
-                .unwrap();
+                {
+                    println!("Failed to set organization name: {:?}", e);
+                }
             }
         }
     }
@@ -241,7 +288,7 @@ impl Engine {
         resource_id: Uuid,
         member_id: Uuid,
     ) -> Option<Activity> {
-        sqlx::query!(
+        let activity = sqlx::query!(
             r#"
             INSERT INTO activity (operation, resource_type, resource_id, member_id)
             VALUES ($1, $2, $3, $4)
@@ -263,6 +310,56 @@ impl Engine {
             resource_type,
             resource_id,
             member_id,
-        })
+        })?;
+
+        if let Err(e) = self.notify_watchers(&[activity.id]).await {
+            println!("Failed to notify watchers: {:?}", e);
+        }
+
+        if let Err(e) = self.queue_webhook_deliveries(&[activity.id]).await {
+            println!("Failed to queue webhook deliveries: {:?}", e);
+        }
+
+        Some(activity)
+    }
+
+    /// Records the same operation over many resources with a single insert. Like
+    /// [`Engine::record_activity`], failures are logged without failing the change they follow.
+    pub async fn record_activities(
+        &self,
+        operation: ActivityOperationType,
+        resource_type: ActivityResourceType,
+        resource_ids: &[Uuid],
+        member_id: Uuid,
+    ) {
+        let activity_ids: Vec<Uuid> = match sqlx::query!(
+            r#"
+            INSERT INTO activity (operation, resource_type, resource_id, member_id)
+            SELECT $1, $2, resource_id, $4
+            FROM unnest($3::uuid[]) AS resource_id
+            RETURNING id
+            "#,
+            operation.to_string(),
+            resource_type.to_string(),
+            resource_ids,
+            member_id,
+        )
+        .fetch_all(&*self.pool)
+        .await
+        {
+            Ok(rows) => rows.into_iter().map(|r| r.id).collect(),
+            Err(e) => {
+                println!("Failed to record activities: {:?}", e);
+                return;
+            }
+        };
+
+        if let Err(e) = self.notify_watchers(&activity_ids).await {
+            println!("Failed to notify watchers: {:?}", e);
+        }
+
+        if let Err(e) = self.queue_webhook_deliveries(&activity_ids).await {
+            println!("Failed to queue webhook deliveries: {:?}", e);
+        }
     }
 }
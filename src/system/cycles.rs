@@ -0,0 +1,215 @@
+use std::time::Duration;
+
+use async_graphql::dataloader::Loader;
+use uuid::Uuid;
+
+use crate::{
+    config::CYCLE_SCHEDULER_INTERVAL_SECS,
+    sdk::{
+        activity::{ActivityOperationType, ActivityResourceType},
+        cycle::CycleProgress,
+        loaders::TaskLoader,
+    },
+};
+
+use super::core::Engine;
+
+impl Engine {
+    /// Periodically closes the cycles whose end date has passed.
+    pub fn spawn_cycle_scheduler(&self) {
+        let engine = self.clone();
+
+        tokio::spawn(async move {
+            let mut interval =
+                tokio::time::interval(Duration::from_secs(*CYCLE_SCHEDULER_INTERVAL_SECS));
+
+            loop {
+                interval.tick().await;
+
+                if let Err(e) = engine.close_ended_cycles().await {
+                    println!("Failed to close ended cycles: {:?}", e);
+                }
+            }
+        });
+    }
+
+    pub async fn close_ended_cycles(&self) -> Result<(), sqlx::Error> {
+        let ended = sqlx::query!(
+            r#"
+            SELECT id, team_id FROM cycles
+            WHERE closed_at IS NULL AND ends_at <= NOW()
+            ORDER BY ends_at
+            "#,
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+
+        for cycle in ended {
+            let moved = self.close_cycle(cycle.id, None).await?;
+
+            // Closed by the scheduler, so the activity is attributed to the team owner.
+            if let Some(owner_id) = self.get_team_owner_id(cycle.team_id).await? {
+                self.record_activity(
+                    ActivityOperationType::Update,
+                    ActivityResourceType::Cycle,
+                    cycle.id,
+                    owner_id,
+                )
+                .await;
+
+                self.publish_carried_over_tasks(&moved, owner_id).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Closes a cycle. Unfinished tasks move to `carry_over_to`, or to the team's next open cycle
+    /// when the cycle has `carry_over` enabled. Returns the ids of the moved tasks.
+    pub async fn close_cycle(
+        &self,
+        cycle_id: Uuid,
+        carry_over_to: Option<Uuid>,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let cycle = sqlx::query!(
+            r#"
+            UPDATE cycles
+            SET closed_at = COALESCE(closed_at, NOW())
+            WHERE id = $1
+            RETURNING team_id, ends_at, carry_over
+            "#,
+            cycle_id,
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let target = match carry_over_to {
+            Some(target) => Some(target),
+            None if cycle.carry_over => sqlx::query!(
+                r#"
+                SELECT id FROM cycles
+                WHERE
+                    team_id = $1
+                    AND id <> $2
+                    AND closed_at IS NULL
+                    AND starts_at >= $3
+                ORDER BY starts_at
+                LIMIT 1
+                "#,
+                cycle.team_id,
+                cycle_id,
+                cycle.ends_at,
+            )
+            .fetch_optional(&mut *tx)
+            .await?
+            .map(|r| r.id),
+            None => None,
+        };
+
+        let moved: Vec<Uuid> = match target {
+            Some(target) => sqlx::query!(
+                r#"
+                UPDATE tasks
+                SET cycle_id = $2
+                WHERE
+                    cycle_id = $1
+                    AND NOT EXISTS (
+                        SELECT 1 FROM workflow_states
+                        WHERE
+                            workflow_states.id = tasks.state_id
+                            AND workflow_states.category IN ('Completed', 'Canceled')
+                    )
+                RETURNING id
+                "#,
+                cycle_id,
+                target,
+            )
+            .fetch_all(&mut *tx)
+            .await?
+            .into_iter()
+            .map(|r| r.id)
+            .collect(),
+            None => vec![],
+        };
+
+        tx.commit().await?;
+
+        Ok(moved)
+    }
+
+    /// Sends the events and the activity of the tasks that `close_cycle` carried over.
+    pub async fn publish_carried_over_tasks(
+        &self,
+        task_ids: &[Uuid],
+        member_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        if task_ids.is_empty() {
+            return Ok(());
+        }
+
+        let tasks = TaskLoader::new(self.clone())
+            .load(task_ids)
+            .await
+            .map_err(|e| sqlx::Error::Protocol(e.to_string()))?;
+
+        for task_id in task_ids {
+            let Some(task) = tasks.get(task_id) else {
+                continue;
+            };
+
+            self.subscription_manager
+                .send_task_event(task.clone())
+                .await
+                .ok();
+        }
+
+        self.record_activities(
+            ActivityOperationType::Update,
+            ActivityResourceType::Task,
+            task_ids,
+            member_id,
+        )
+        .await;
+
+        Ok(())
+    }
+
+    async fn get_team_owner_id(&self, team_id: Uuid) -> Result<Option<Uuid>, sqlx::Error> {
+        sqlx::query!(
+            r#"
+            SELECT owner_id FROM teams
+            WHERE id = $1
+            "#,
+            team_id,
+        )
+        .fetch_optional(&*self.pool)
+        .await
+        .map(|r| r.map(|r| r.owner_id))
+    }
+
+    pub async fn get_cycle_progress(&self, cycle_id: Uuid) -> Result<CycleProgress, sqlx::Error> {
+        sqlx::query!(
+            r#"
+            SELECT
+                COALESCE(SUM(tasks.estimate) FILTER (WHERE workflow_states.category = 'Completed'), 0)::bigint AS "completed_points!",
+                COALESCE(SUM(tasks.estimate), 0)::bigint AS "total_points!",
+                COUNT(*) FILTER (WHERE workflow_states.category = 'Completed') AS "completed_tasks!",
+                COUNT(*) AS "total_tasks!"
+            FROM tasks
+            LEFT JOIN workflow_states ON workflow_states.id = tasks.state_id
+            WHERE tasks.cycle_id = $1 AND tasks.deleted_at IS NULL
+            "#,
+            cycle_id,
+        )
+        .fetch_one(&*self.pool)
+        .await
+        .map(|r| CycleProgress {
+            completed_points: r.completed_points,
+            total_points: r.total_points,
+            completed_tasks: r.completed_tasks,
+            total_tasks: r.total_tasks,
+        })
+    }
+}
@@ -0,0 +1,296 @@
+use std::{sync::Arc, time::Duration};
+
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::{
+    config::{
+        MAILDIR_PATH, MAIL_DIGEST_HOUR, MAIL_DUE_SOON_HOURS, MAIL_MAX_ATTEMPTS,
+        MAIL_SCHEDULER_INTERVAL_SECS, SMTP_HOST, SMTP_PASSWORD, SMTP_PORT, SMTP_USERNAME,
+    },
+    mail::{
+        templates::{digest_email, immediate_email, EmailItem, TaskSummary},
+        transport::{MailTransport, MaildirTransport, SmtpTransport},
+    },
+    sdk::utilities::DateTimeBridge,
+};
+
+use super::core::Engine;
+
+/// SMTP when `SMTP_HOST` is set, the maildir under `MAILDIR_PATH` otherwise.
+pub fn default_mail_transport() -> Arc<dyn MailTransport> {
+    if let Some(host) = SMTP_HOST.as_deref() {
+        let credentials = SMTP_USERNAME.clone().zip(SMTP_PASSWORD.clone());
+
+        match SmtpTransport::new(host, *SMTP_PORT, credentials) {
+            Ok(transport) => return Arc::new(transport),
+            Err(e) => println!(
+                "Failed to set up SMTP, writing emails to the maildir: {}",
+                e
+            ),
+        }
+    }
+
+    Arc::new(MaildirTransport::new(MAILDIR_PATH.as_str()))
+}
+
+impl Engine {
+    /// Replaces the mail transport, picked from the configuration by default.
+    pub fn with_mail_transport(mut self, mail: impl MailTransport + 'static) -> Self {
+        self.mail = Arc::new(mail);
+        self
+    }
+
+    /// Periodically sends the queued emails, so mutations never wait for them.
+    pub fn spawn_mail_scheduler(&self) {
+        let engine = self.clone();
+
+        tokio::spawn(async move {
+            let mut interval =
+                tokio::time::interval(Duration::from_secs(*MAIL_SCHEDULER_INTERVAL_SECS));
+
+            loop {
+                interval.tick().await;
+
+                if let Err(e) = engine.process_emails().await {
+                    println!("Failed to process emails: {:?}", e);
+                }
+            }
+        });
+    }
+
+    /// Queues due date reminders and sends the pending emails, right away or in the daily
+    /// digest depending on each member's preference. Emails for members who turned them off are
+    /// dropped.
+    pub async fn process_emails(&self) -> Result<(), sqlx::Error> {
+        self.queue_due_soon_emails().await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM email_events
+            USING members
+            WHERE
+                members.id = email_events.member_id
+                AND members.email_notifications = 'Off'
+                AND email_events.sent_at IS NULL
+            "#,
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        self.send_immediate_emails().await?;
+        self.send_digest_emails().await?;
+
+        Ok(())
+    }
+
+    /// Reminds the assignees and the lead of the open tasks due within `MAIL_DUE_SOON_HOURS`.
+    async fn queue_due_soon_emails(&self) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            INSERT INTO email_events (member_id, kind, task_id, due_date)
+            SELECT members.id, 'DueSoon', tasks.id, tasks.due_date
+            FROM tasks
+            CROSS JOIN LATERAL (
+                SELECT assignee_id AS member_id FROM tasks_by_assignees
+                WHERE tasks_by_assignees.task_id = tasks.id
+                UNION
+                SELECT tasks.lead_id
+            ) AS recipients
+            JOIN members ON members.id = recipients.member_id
+            WHERE
+                tasks.deleted_at IS NULL
+                AND tasks.due_date BETWEEN NOW() AND NOW() + make_interval(hours => $1)
+                AND COALESCE(tasks.status, 'None') NOT IN ('Done', 'Canceled')
+            ON CONFLICT DO NOTHING
+            "#,
+            *MAIL_DUE_SOON_HOURS,
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Pending emails of a member, oldest first. Items are `None` when they can't be rendered
+    /// anymore, such as mentions in a deleted comment.
+    async fn pending_emails(
+        &self,
+        member_id: Uuid,
+    ) -> Result<Vec<(Uuid, Option<EmailItem>)>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                email_events.id,
+                email_events.kind,
+                email_events.due_date,
+                tasks.id AS task_id,
+                tasks.key,
+                tasks.title,
+                comments.body AS "comment_body?",
+                authors.name AS "author_name?"
+            FROM email_events
+            JOIN tasks ON tasks.id = email_events.task_id
+            LEFT JOIN comments ON comments.id = email_events.comment_id
+            LEFT JOIN members AS authors ON authors.id = comments.author_id
+            WHERE
+                email_events.member_id = $1
+                AND email_events.sent_at IS NULL
+                AND email_events.attempts < $2
+                AND tasks.deleted_at IS NULL
+            ORDER BY email_events.created_at
+            "#,
+            member_id,
+            *MAIL_MAX_ATTEMPTS,
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| {
+                let task = TaskSummary {
+                    id: r.task_id,
+                    key: r.key,
+                    title: r.title,
+                };
+
+                let item = match r.kind.as_str() {
+                    "Assigned" => Some(EmailItem::Assigned { task }),
+                    "Mentioned" => r.comment_body.map(|body| EmailItem::Mentioned {
+                        task,
+                        author: r.author_name.unwrap_or_else(|| "Someone".to_string()),
+                        body,
+                    }),
+                    "DueSoon" => r.due_date.map(|due_date| EmailItem::DueSoon {
+                        task,
+                        due_date: DateTimeBridge::from_offset_date_time(due_date),
+                    }),
+                    _ => None,
+                };
+
+                (r.id, item)
+            })
+            .collect())
+    }
+
+    async fn mark_emails_sent(&self, ids: &[Uuid]) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE email_events SET sent_at = NOW()
+            WHERE id = ANY($1)
+            "#,
+            ids,
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Failed emails are retried on the next runs, up to `MAIL_MAX_ATTEMPTS` times.
+    async fn mark_emails_failed(&self, ids: &[Uuid], error: String) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE email_events SET attempts = attempts + 1, last_error = $2
+            WHERE id = ANY($1)
+            "#,
+            ids,
+            error,
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn send_immediate_emails(&self) -> Result<(), sqlx::Error> {
+        let members = sqlx::query!(
+            r#"
+            SELECT DISTINCT members.id, members.email FROM members
+            JOIN email_events ON email_events.member_id = members.id
+            WHERE
+                members.email_notifications = 'Immediate'
+                AND email_events.sent_at IS NULL
+                AND email_events.attempts < $1
+            "#,
+            *MAIL_MAX_ATTEMPTS,
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+
+        for member in members {
+            for (id, item) in self.pending_emails(member.id).await? {
+                if let Some(item) = item {
+                    if let Err(e) = self.mail.send(immediate_email(&member.email, item)).await {
+                        self.mark_emails_failed(&[id], format!("{:?}", e)).await?;
+                        continue;
+                    }
+                }
+
+                self.mark_emails_sent(&[id]).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Sends a digest to the members who haven't got one since today's `MAIL_DIGEST_HOUR`.
+    async fn send_digest_emails(&self) -> Result<(), sqlx::Error> {
+        let now = Utc::now();
+
+        let Some(digest_time) = now.date_naive().and_hms_opt(*MAIL_DIGEST_HOUR, 0, 0) else {
+            return Ok(());
+        };
+
+        let digest_time = digest_time.and_utc();
+
+        if now < digest_time {
+            return Ok(());
+        }
+
+        let members = sqlx::query!(
+            r#"
+            SELECT DISTINCT members.id, members.email FROM members
+            JOIN email_events ON email_events.member_id = members.id
+            WHERE
+                members.email_notifications = 'Digest'
+                AND (members.email_digest_sent_at IS NULL OR members.email_digest_sent_at < $1)
+                AND email_events.sent_at IS NULL
+                AND email_events.attempts < $2
+            "#,
+            DateTimeBridge::from_date_time(digest_time),
+            *MAIL_MAX_ATTEMPTS,
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+
+        for member in members {
+            let (ids, items): (Vec<Uuid>, Vec<Option<EmailItem>>) =
+                self.pending_emails(member.id).await?.into_iter().unzip();
+
+            let items: Vec<EmailItem> = items.into_iter().flatten().collect();
+
+            if !items.is_empty() {
+                if let Err(e) = self.mail.send(digest_email(&member.email, &items)).await {
+                    self.mark_emails_failed(&ids, format!("{:?}", e)).await?;
+                    continue;
+                }
+            }
+
+            self.mark_emails_sent(&ids).await?;
+
+            sqlx::query!(
+                r#"
+                UPDATE members SET email_digest_sent_at = NOW()
+                WHERE id = $1
+                "#,
+                member.id,
+            )
+            .execute(&*self.pool)
+            .await?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,5 +1,18 @@
+pub mod attachments;
 pub mod core;
+pub mod cycles;
+pub mod mail;
 pub mod members;
+pub mod notifications;
 pub mod prelude;
+pub mod projects;
+pub mod ranks;
+pub mod recurrences;
 pub mod schema;
+pub mod storage;
 pub mod subscriptions;
+pub mod subtasks;
+pub mod templates;
+pub mod trash;
+pub mod webhooks;
+pub mod workflows;
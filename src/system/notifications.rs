@@ -0,0 +1,121 @@
+use uuid::Uuid;
+
+use crate::sdk::{
+    activity::ActivityResourceType, notification::Notification, utilities::DateTimeBridge,
+};
+
+use super::core::Engine;
+
+impl Engine {
+    /// Notifies the watchers of the resources of the given activities, other than the members
+    /// who did them, and pushes the notifications to their subscribers. Watchers of a project
+    /// are also notified of the activity of its tasks.
+    pub async fn notify_watchers(
+        &self,
+        activity_ids: &[Uuid],
+    ) -> Result<Vec<Notification>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"
+            INSERT INTO notifications (member_id, activity_id)
+            SELECT DISTINCT watchers.member_id, activity.id
+            FROM activity
+            LEFT JOIN tasks ON activity.resource_type = 'Task' AND tasks.id = activity.resource_id
+            JOIN watchers ON
+                (watchers.resource_type = activity.resource_type
+                    AND watchers.resource_id = activity.resource_id)
+                OR (watchers.resource_type = 'Project' AND watchers.resource_id = tasks.project_id)
+            WHERE activity.id = ANY($1) AND watchers.member_id <> activity.member_id
+            RETURNING *
+            "#,
+            activity_ids,
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+
+        let notifications: Vec<Notification> = rows
+            .into_iter()
+            .map(|r| Notification {
+                id: r.id,
+                created_at: DateTimeBridge::from_offset_date_time(r.created_at),
+                updated_at: DateTimeBridge::from_offset_date_time(r.updated_at),
+                member_id: r.member_id,
+                activity_id: r.activity_id,
+                read_at: r.read_at.map(DateTimeBridge::from_offset_date_time),
+            })
+            .collect();
+
+        for notification in &notifications {
+            self.subscription_manager
+                .send_notification_event(notification.clone())
+                .await
+                .ok();
+        }
+
+        Ok(notifications)
+    }
+
+    /// Makes a member watch a task or a project.
+    pub async fn add_watcher(
+        &self,
+        member_id: Uuid,
+        resource_type: ActivityResourceType,
+        resource_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            INSERT INTO watchers (member_id, resource_type, resource_id)
+            VALUES ($1, $2, $3)
+            ON CONFLICT DO NOTHING
+            "#,
+            member_id,
+            resource_type.to_string(),
+            resource_id,
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn remove_watcher(
+        &self,
+        member_id: Uuid,
+        resource_type: ActivityResourceType,
+        resource_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            DELETE FROM watchers
+            WHERE member_id = $1 AND resource_type = $2 AND resource_id = $3
+            "#,
+            member_id,
+            resource_type.to_string(),
+            resource_id,
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Members watching a task or a project, in the order they started.
+    pub async fn get_watcher_ids(
+        &self,
+        resource_type: ActivityResourceType,
+        resource_id: Uuid,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT member_id FROM watchers
+            WHERE resource_type = $1 AND resource_id = $2
+            ORDER BY created_at, member_id
+            "#,
+            resource_type.to_string(),
+            resource_id,
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(|r| r.member_id).collect())
+    }
+}
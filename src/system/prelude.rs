@@ -30,14 +30,20 @@ impl Prelude for Engine {
             let admin_password = ADMIN_PASSWORD.to_owned();
             let admin_name = ADMIN_NAME.to_owned();
 
-            let admin_password_hash = self.auth.hash_password(admin_password.as_str());
+            let admin_password_hash = match self.auth.hash_password(admin_password.as_str()) {
+                Ok(admin_password_hash) => admin_password_hash,
+                Err(e) => {
+                    println!("Failed to hash admin password: {:?}", e);
+                    return;
+                }
+            };
 
             let admin_member = self
                 .create_member_from_email(admin_email.clone(), admin_name, admin_password_hash)
                 .await;
 
-            if admin_member.is_none() {
-                println!("Failed to create admin member");
+            if let Err(e) = admin_member {
+                println!("Failed to create admin member: {:?}", e);
             } else {
                 println!(
                     "Admin created with email: '{}' and password: '{}'",
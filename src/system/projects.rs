@@ -0,0 +1,274 @@
+use async_graphql::dataloader::Loader;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::sdk::{
+    activity::{ActivityOperationType, ActivityResourceType},
+    loaders::{ProjectLoader, TaskLoader},
+    project::DuplicateProjectOptions,
+    utilities::DateTimeBridge,
+};
+
+use super::core::Engine;
+
+impl Engine {
+    /// Copies a project with its workflow, its tasks (keeping parent links, labels and
+    /// dependencies) and optionally its members and teams, in a single transaction. Dates are
+    /// shifted by the distance between the original start (or creation) and `start_date`.
+    /// Returns the id of the copy.
+    pub async fn duplicate_project(
+        &self,
+        project_id: Uuid,
+        name: String,
+        start_date: Option<DateTime<Utc>>,
+        options: DuplicateProjectOptions,
+        owner_id: Uuid,
+    ) -> Result<Uuid, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let source = sqlx::query!(
+            r#"
+            SELECT COALESCE(start_date, created_at) AS "starts_at!"
+            FROM projects
+            WHERE id = $1 AND deleted_at IS NULL
+            FOR SHARE
+            "#,
+            project_id,
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let shift_seconds = start_date
+            .map(|start_date| {
+                (start_date - DateTimeBridge::from_offset_date_time(source.starts_at)).num_seconds()
+                    as f64
+            })
+            .unwrap_or_default();
+
+        let project = sqlx::query!(
+            r#"
+            INSERT INTO projects (name, prefix, owner_id, description, lead_id, start_date, due_date)
+            SELECT
+                $2,
+                COALESCE($3, prefix),
+                $4,
+                description,
+                lead_id,
+                start_date + make_interval(secs => $5),
+                due_date + make_interval(secs => $5)
+            FROM projects
+            WHERE id = $1 AND deleted_at IS NULL
+            RETURNING id
+            "#,
+            project_id,
+            name,
+            options.prefix,
+            owner_id,
+            shift_seconds,
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        // States are unique by name inside a workflow, which maps the copies to the originals.
+        sqlx::query!(
+            r#"
+            INSERT INTO workflow_states (project_id, name, category, position)
+            SELECT $2, name, category, position
+            FROM workflow_states
+            WHERE project_id = $1
+            "#,
+            project_id,
+            project.id,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO workflow_transitions (from_state_id, to_state_id)
+            SELECT new_from.id, new_to.id
+            FROM workflow_transitions
+            JOIN workflow_states old_from ON old_from.id = workflow_transitions.from_state_id
+            JOIN workflow_states old_to ON old_to.id = workflow_transitions.to_state_id
+            JOIN workflow_states new_from ON new_from.project_id = $2 AND new_from.name = old_from.name
+            JOIN workflow_states new_to ON new_to.project_id = $2 AND new_to.name = old_to.name
+            WHERE old_from.project_id = $1
+            "#,
+            project_id,
+            project.id,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let source_task_ids: Vec<Uuid> = sqlx::query!(
+            r#"
+            SELECT id FROM tasks
+            WHERE project_id = $1 AND deleted_at IS NULL
+            ORDER BY created_at, id
+            "#,
+            project_id,
+        )
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .map(|r| r.id)
+        .collect();
+
+        let copy_ids: Vec<Uuid> = source_task_ids.iter().map(|_| Uuid::new_v4()).collect();
+
+        // Parents outside the project aren't copied, those tasks become top level.
+        sqlx::query!(
+            r#"
+            INSERT INTO tasks (id, title, description, owner_id, status, priority, due_date, project_id, lead_id, parent_id, state_id, estimate)
+            SELECT
+                copies.new_id,
+                tasks.title,
+                tasks.description,
+                tasks.owner_id,
+                tasks.status,
+                tasks.priority,
+                tasks.due_date + make_interval(secs => $4),
+                $3,
+                tasks.lead_id,
+                parents.new_id,
+                CASE
+                    WHEN workflow_states.project_id = $5 THEN (
+                        SELECT id FROM workflow_states new_states
+                        WHERE new_states.project_id = $3 AND new_states.name = workflow_states.name
+                    )
+                    ELSE tasks.state_id
+                END,
+                tasks.estimate
+            FROM unnest($1::uuid[], $2::uuid[]) WITH ORDINALITY AS copies(old_id, new_id, position)
+            JOIN tasks ON tasks.id = copies.old_id
+            LEFT JOIN unnest($1::uuid[], $2::uuid[]) AS parents(old_id, new_id) ON parents.old_id = tasks.parent_id
+            LEFT JOIN workflow_states ON workflow_states.id = tasks.state_id
+            WHERE tasks.deleted_at IS NULL
+            ORDER BY copies.position
+            "#,
+            &source_task_ids,
+            &copy_ids,
+            project.id,
+            shift_seconds,
+            project_id,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO labels_by_tasks (task_id, label_id)
+            SELECT copies.new_id, labels_by_tasks.label_id
+            FROM unnest($1::uuid[], $2::uuid[]) AS copies(old_id, new_id)
+            JOIN labels_by_tasks ON labels_by_tasks.task_id = copies.old_id
+            "#,
+            &source_task_ids,
+            &copy_ids,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO task_dependencies (blocker_id, blocked_id)
+            SELECT blockers.new_id, blocked.new_id
+            FROM task_dependencies
+            JOIN unnest($1::uuid[], $2::uuid[]) AS blockers(old_id, new_id) ON blockers.old_id = task_dependencies.blocker_id
+            JOIN unnest($1::uuid[], $2::uuid[]) AS blocked(old_id, new_id) ON blocked.old_id = task_dependencies.blocked_id
+            "#,
+            &source_task_ids,
+            &copy_ids,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if options.include_members {
+            sqlx::query!(
+                r#"
+                INSERT INTO members_by_projects (member_id, project_id)
+                SELECT member_id, $2 FROM members_by_projects
+                WHERE project_id = $1
+                "#,
+                project_id,
+                project.id,
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query!(
+                r#"
+                INSERT INTO tasks_by_assignees (task_id, assignee_id)
+                SELECT copies.new_id, tasks_by_assignees.assignee_id
+                FROM unnest($1::uuid[], $2::uuid[]) AS copies(old_id, new_id)
+                JOIN tasks_by_assignees ON tasks_by_assignees.task_id = copies.old_id
+                "#,
+                &source_task_ids,
+                &copy_ids,
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        if options.include_teams {
+            sqlx::query!(
+                r#"
+                INSERT INTO teams_by_projects (team_id, project_id)
+                SELECT team_id, $2 FROM teams_by_projects
+                WHERE project_id = $1
+                "#,
+                project_id,
+                project.id,
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        if let Some(copy) = ProjectLoader::new(self.clone())
+            .load(&[project.id])
+            .await
+            .map_err(|e| sqlx::Error::Protocol(e.to_string()))?
+            .remove(&project.id)
+        {
+            self.subscription_manager
+                .send_project_event(copy)
+                .await
+                .ok();
+        }
+
+        self.record_activity(
+            ActivityOperationType::Create,
+            ActivityResourceType::Project,
+            project.id,
+            owner_id,
+        )
+        .await;
+
+        let tasks = TaskLoader::new(self.clone())
+            .load(&copy_ids)
+            .await
+            .map_err(|e| sqlx::Error::Protocol(e.to_string()))?;
+
+        for task_id in &copy_ids {
+            let Some(task) = tasks.get(task_id) else {
+                continue;
+            };
+
+            self.subscription_manager
+                .send_task_event(task.clone())
+                .await
+                .ok();
+
+            self.record_activity(
+                ActivityOperationType::Create,
+                ActivityResourceType::Task,
+                task.id,
+                owner_id,
+            )
+            .await;
+        }
+
+        Ok(project.id)
+    }
+}
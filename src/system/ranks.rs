@@ -0,0 +1,352 @@
+use std::time::Duration;
+
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use crate::config::{RANK_REBALANCE_INTERVAL_SECS, RANK_REBALANCE_LENGTH};
+
+use super::core::Engine;
+
+/// Digits of a rank, in the order Postgres compares them under the "C" collation.
+const RANK_DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+const RANK_BASE: usize = RANK_DIGITS.len();
+
+fn rank_digit(c: u8) -> usize {
+    RANK_DIGITS.iter().position(|&d| d == c).unwrap_or(0)
+}
+
+/// Returns a rank sorting strictly between `lower` and `upper`, where `None` stands for the start
+/// or the end of the column. `lower` must sort before `upper`.
+pub fn rank_between(lower: Option<&str>, upper: Option<&str>) -> String {
+    let mut rank = Vec::new();
+
+    push_midpoint(
+        lower.unwrap_or_default().as_bytes(),
+        upper.map(str::as_bytes),
+        &mut rank,
+    );
+
+    String::from_utf8_lossy(&rank).into_owned()
+}
+
+fn push_midpoint(a: &[u8], b: Option<&[u8]>, out: &mut Vec<u8>) {
+    // A missing digit of `a` is a 0, which is why ranks never end with one.
+    if let Some(b) = b {
+        let common = b
+            .iter()
+            .enumerate()
+            .take_while(|&(i, &c)| a.get(i).copied().unwrap_or(b'0') == c)
+            .count();
+
+        if common > 0 {
+            out.extend_from_slice(&b[..common]);
+            return push_midpoint(a.get(common..).unwrap_or_default(), Some(&b[common..]), out);
+        }
+    }
+
+    let digit_a = a.first().map_or(0, |&c| rank_digit(c));
+    let digit_b = b
+        .and_then(|b| b.first())
+        .map_or(RANK_BASE, |&c| rank_digit(c));
+
+    if digit_b > digit_a + 1 {
+        out.push(RANK_DIGITS[(digit_a + digit_b) / 2]);
+    } else if let Some(b) = b.filter(|b| b.len() > 1) {
+        // The first digit of a longer `b` already sorts between both.
+        out.push(b[0]);
+    } else {
+        out.push(RANK_DIGITS[digit_a]);
+        push_midpoint(a.get(1..).unwrap_or_default(), None, out);
+    }
+}
+
+/// Returns `count` short ranks spread evenly, leaving room for a digit of moves between them.
+pub fn spread_ranks(count: usize) -> Vec<String> {
+    let slots = (count as u128 + 1) * RANK_BASE as u128;
+
+    let mut width = 1;
+    let mut space = RANK_BASE as u128;
+
+    while space <= slots {
+        width += 1;
+        space *= RANK_BASE as u128;
+    }
+
+    (1..=count as u128)
+        .map(|i| {
+            let mut value = i * space / (count as u128 + 1);
+            let mut digits = vec![b'0'; width];
+
+            for digit in digits.iter_mut().rev() {
+                *digit = RANK_DIGITS[(value % RANK_BASE as u128) as usize];
+                value /= RANK_BASE as u128;
+            }
+
+            // Trailing zeros can go, ranks of the same width keep their order without them.
+            while digits.last() == Some(&b'0') {
+                digits.pop();
+            }
+
+            String::from_utf8_lossy(&digits).into_owned()
+        })
+        .collect()
+}
+
+/// Rewrites the ranks of a column with [`spread_ranks`], keeping the current order. Unranked
+/// tasks are ranked after the others in creation order.
+async fn rebalance_column(
+    conn: &mut PgConnection,
+    project_id: Option<Uuid>,
+    state_id: Option<Uuid>,
+) -> Result<(), sqlx::Error> {
+    let ids: Vec<Uuid> = sqlx::query!(
+        r#"
+        SELECT id FROM tasks
+        WHERE
+            project_id IS NOT DISTINCT FROM $1
+            AND state_id IS NOT DISTINCT FROM $2
+            AND deleted_at IS NULL
+        ORDER BY rank NULLS LAST, created_at, id
+        FOR UPDATE
+        "#,
+        project_id,
+        state_id,
+    )
+    .fetch_all(&mut *conn)
+    .await?
+    .into_iter()
+    .map(|r| r.id)
+    .collect();
+
+    let ranks = spread_ranks(ids.len());
+
+    sqlx::query!(
+        r#"
+        UPDATE tasks
+        SET rank = data.rank
+        FROM unnest($1::uuid[], $2::text[]) AS data(id, rank)
+        WHERE tasks.id = data.id
+        "#,
+        &ids,
+        &ranks,
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Picks the rank that places a task right after `after_id` and right before `before_id` in a
+/// column. Without neighbors the task goes last. The column is rebalanced first when the
+/// neighbors have no room between them. Returns `None` if the neighbors aren't adjacent tasks
+/// of the column.
+pub async fn rank_in_column(
+    conn: &mut PgConnection,
+    task_id: Uuid,
+    project_id: Option<Uuid>,
+    state_id: Option<Uuid>,
+    before_id: Option<Uuid>,
+    after_id: Option<Uuid>,
+) -> Result<Option<String>, sqlx::Error> {
+    let mut rebalanced = false;
+
+    loop {
+        let column = sqlx::query!(
+            r#"
+            SELECT id, rank FROM tasks
+            WHERE
+                project_id IS NOT DISTINCT FROM $1
+                AND state_id IS NOT DISTINCT FROM $2
+                AND deleted_at IS NULL
+                AND id <> $3
+            ORDER BY rank NULLS LAST, created_at, id
+            FOR UPDATE
+            "#,
+            project_id,
+            state_id,
+            task_id,
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        let position_of = |id: Uuid| column.iter().position(|t| t.id == id);
+
+        let index = match (after_id, before_id) {
+            (Some(after_id), Some(before_id)) => {
+                match (position_of(after_id), position_of(before_id)) {
+                    (Some(after), Some(before)) if after + 1 == before => before,
+                    _ => return Ok(None),
+                }
+            }
+            (Some(after_id), None) => match position_of(after_id) {
+                Some(after) => after + 1,
+                None => return Ok(None),
+            },
+            (None, Some(before_id)) => match position_of(before_id) {
+                Some(before) => before,
+                None => return Ok(None),
+            },
+            (None, None) => column.len(),
+        };
+
+        let lower = index.checked_sub(1).map(|i| column[i].rank.as_deref());
+        let upper = column.get(index).map(|t| t.rank.as_deref());
+
+        let has_room = match (lower, upper) {
+            (Some(None), _) | (_, Some(None)) => false,
+            (Some(Some(lower)), Some(Some(upper))) => lower < upper,
+            _ => true,
+        };
+
+        if has_room || rebalanced {
+            return Ok(Some(rank_between(lower.flatten(), upper.flatten())));
+        }
+
+        rebalance_column(conn, project_id, state_id).await?;
+        rebalanced = true;
+    }
+}
+
+impl Engine {
+    /// Periodically rebalances the board columns whose ranks got too long.
+    pub fn spawn_rank_rebalance_scheduler(&self) {
+        let engine = self.clone();
+
+        tokio::spawn(async move {
+            let mut interval =
+                tokio::time::interval(Duration::from_secs(*RANK_REBALANCE_INTERVAL_SECS));
+
+            loop {
+                interval.tick().await;
+
+                if let Err(e) = engine.rebalance_long_ranks().await {
+                    println!("Failed to rebalance task ranks: {:?}", e);
+                }
+            }
+        });
+    }
+
+    /// Rebalances every column with a rank longer than `RANK_REBALANCE_LENGTH`.
+    pub async fn rebalance_long_ranks(&self) -> Result<(), sqlx::Error> {
+        let columns = sqlx::query!(
+            r#"
+            SELECT DISTINCT project_id, state_id FROM tasks
+            WHERE length(rank) > $1 AND deleted_at IS NULL
+            "#,
+            *RANK_REBALANCE_LENGTH,
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+
+        for column in columns {
+            let mut tx = self.pool.begin().await?;
+
+            rebalance_column(&mut tx, column.project_id, column.state_id).await?;
+
+            tx.commit().await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_between(lower: Option<&str>, upper: Option<&str>) -> String {
+        let rank = rank_between(lower, upper);
+
+        assert!(!rank.is_empty() && !rank.ends_with('0'), "{rank:?}");
+        assert!(
+            lower.map_or(true, |lower| lower < rank.as_str()),
+            "{lower:?} < {rank:?}"
+        );
+        assert!(
+            upper.map_or(true, |upper| rank.as_str() < upper),
+            "{rank:?} < {upper:?}"
+        );
+
+        rank
+    }
+
+    #[test]
+    fn ranks_an_empty_column() {
+        assert_eq!(rank_between(None, None), "i");
+    }
+
+    #[test]
+    fn ranks_at_the_ends() {
+        assert_between(Some("i"), None);
+        assert_between(None, Some("i"));
+        assert_between(Some("zz"), None);
+        assert_between(None, Some("01"));
+        assert_between(None, Some("1"));
+    }
+
+    #[test]
+    fn ranks_between_neighbours() {
+        assert_eq!(assert_between(Some("a"), Some("c")), "b");
+        assert_between(Some("a"), Some("b"));
+        assert_between(Some("a"), Some("a1"));
+        assert_between(Some("a"), Some("b1"));
+        assert_between(Some("az"), Some("b"));
+        assert_between(Some("a0z"), Some("a1"));
+        assert_between(Some("hzzz"), Some("i"));
+    }
+
+    #[test]
+    fn keeps_ranking_the_same_gap() {
+        let mut lower = "a".to_string();
+        let mut upper = "b".to_string();
+
+        for i in 0..200 {
+            let rank = assert_between(Some(&lower), Some(&upper));
+
+            if i % 2 == 0 {
+                lower = rank;
+            } else {
+                upper = rank;
+            }
+        }
+    }
+
+    #[test]
+    fn keeps_ranking_at_the_top() {
+        let mut first = rank_between(None, None);
+
+        for _ in 0..200 {
+            first = assert_between(None, Some(&first));
+        }
+    }
+
+    #[test]
+    fn spreads_ranks_in_order() {
+        assert!(spread_ranks(0).is_empty());
+
+        for count in [1, 2, 35, 36, 1000] {
+            let ranks = spread_ranks(count);
+
+            assert_eq!(ranks.len(), count);
+            assert!(ranks
+                .iter()
+                .all(|rank| !rank.is_empty() && !rank.ends_with('0')));
+            assert!(ranks.windows(2).all(|pair| pair[0] < pair[1]), "{ranks:?}");
+        }
+    }
+
+    #[test]
+    fn spreads_ranks_with_room_between() {
+        let ranks = spread_ranks(1000);
+        let width = ranks.iter().map(String::len).max().unwrap();
+
+        assert!(width <= 3);
+
+        for pair in ranks.windows(2) {
+            assert!(assert_between(Some(&pair[0]), Some(&pair[1])).len() <= width);
+        }
+
+        assert!(assert_between(None, Some(&ranks[0])).len() <= width);
+        assert!(assert_between(Some(&ranks[999]), None).len() <= width);
+    }
+}
@@ -0,0 +1,217 @@
+use std::{str::FromStr, time::Duration};
+
+use async_graphql::dataloader::Loader;
+use sqlx::{Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{
+    config::RECURRENCE_SCHEDULER_INTERVAL_SECS,
+    sdk::{
+        activity::{ActivityOperationType, ActivityResourceType},
+        loaders::TaskLoader,
+        recurrence::RecurrenceRule,
+        utilities::DateTimeBridge,
+    },
+};
+
+use super::core::Engine;
+
+impl Engine {
+    /// Periodically creates the next occurrence of recurring tasks whose current occurrence was
+    /// completed or whose next occurrence is due.
+    pub fn spawn_recurrence_scheduler(&self) {
+        let engine = self.clone();
+
+        tokio::spawn(async move {
+            let mut interval =
+                tokio::time::interval(Duration::from_secs(*RECURRENCE_SCHEDULER_INTERVAL_SECS));
+
+            loop {
+                interval.tick().await;
+
+                if let Err(e) = engine.create_due_occurrences().await {
+                    println!("Failed to create recurring task occurrences: {:?}", e);
+                }
+            }
+        });
+    }
+
+    /// Returns the ids of the tasks created as new occurrences.
+    pub async fn create_due_occurrences(&self) -> Result<Vec<Uuid>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        // SKIP LOCKED lets several instances run the scheduler without creating duplicates.
+        let due = sqlx::query!(
+            r#"
+            SELECT
+                task_recurrences.task_id,
+                task_recurrences.rule,
+                task_recurrences.starts_at,
+                task_recurrences.current_occurrence_at,
+                task_recurrences.next_occurrence_at AS "next_occurrence_at!"
+            FROM task_recurrences
+            JOIN tasks ON tasks.id = task_recurrences.task_id
+            LEFT JOIN workflow_states ON workflow_states.id = tasks.state_id
+            WHERE
+                task_recurrences.next_occurrence_at IS NOT NULL
+                AND tasks.deleted_at IS NULL
+                AND (
+                    task_recurrences.next_occurrence_at <= NOW()
+                    OR workflow_states.category = 'Completed'
+                )
+            ORDER BY task_recurrences.next_occurrence_at
+            LIMIT 100
+            FOR UPDATE OF task_recurrences SKIP LOCKED
+            "#,
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let mut created = Vec::with_capacity(due.len());
+
+        for recurrence in due {
+            let shift = recurrence.next_occurrence_at - recurrence.current_occurrence_at;
+
+            let task_id =
+                copy_task_tree(&mut tx, recurrence.task_id, shift.whole_seconds() as f64).await?;
+
+            let following = RecurrenceRule::from_str(&recurrence.rule)
+                .ok()
+                .and_then(|rule| {
+                    rule.next_after(
+                        DateTimeBridge::from_offset_date_time(recurrence.starts_at),
+                        DateTimeBridge::from_offset_date_time(recurrence.next_occurrence_at),
+                    )
+                })
+                .map(DateTimeBridge::from_date_time);
+
+            sqlx::query!(
+                r#"
+                UPDATE task_recurrences
+                SET
+                    task_id = $2,
+                    current_occurrence_at = next_occurrence_at,
+                    next_occurrence_at = $3,
+                    occurrences = occurrences + 1
+                WHERE task_id = $1
+                "#,
+                recurrence.task_id,
+                task_id,
+                following,
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            created.push(task_id);
+        }
+
+        tx.commit().await?;
+
+        if created.is_empty() {
+            return Ok(created);
+        }
+
+        let tasks = TaskLoader::new(self.clone())
+            .load(&created)
+            .await
+            .map_err(|e| sqlx::Error::Protocol(e.to_string()))?;
+
+        for task_id in &created {
+            let Some(task) = tasks.get(task_id) else {
+                continue;
+            };
+
+            self.subscription_manager
+                .send_task_event(task.clone())
+                .await
+                .ok();
+
+            self.record_activity(
+                ActivityOperationType::Create,
+                ActivityResourceType::Task,
+                task.id,
+                task.owner_id,
+            )
+            .await;
+        }
+
+        Ok(created)
+    }
+}
+
+/// Copies a task with its labels, assignees and subtasks, shifting every due date by
+/// `shift_seconds`. The copies start over in the `ToDo` status. Returns the id of the root copy.
+async fn copy_task_tree(
+    tx: &mut Transaction<'_, Postgres>,
+    task_id: Uuid,
+    shift_seconds: f64,
+) -> Result<Uuid, sqlx::Error> {
+    let mut pending: Vec<(Uuid, Option<Uuid>)> = vec![(task_id, None)];
+    let mut root_id = None;
+
+    while let Some((source_id, parent_id)) = pending.pop() {
+        let copy = sqlx::query!(
+            r#"
+            INSERT INTO tasks (title, description, owner_id, status, priority, due_date, project_id, lead_id, parent_id)
+            SELECT
+                title,
+                description,
+                owner_id,
+                'ToDo',
+                priority,
+                due_date + make_interval(secs => $2),
+                project_id,
+                lead_id,
+                COALESCE($3, parent_id)
+            FROM tasks
+            WHERE id = $1
+            RETURNING id
+            "#,
+            source_id,
+            shift_seconds,
+            parent_id as Option<Uuid>,
+        )
+        .fetch_one(&mut **tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO labels_by_tasks (task_id, label_id)
+            SELECT $2, label_id FROM labels_by_tasks
+            WHERE task_id = $1
+            "#,
+            source_id,
+            copy.id,
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO tasks_by_assignees (task_id, assignee_id)
+            SELECT $2, assignee_id FROM tasks_by_assignees
+            WHERE task_id = $1
+            "#,
+            source_id,
+            copy.id,
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        let subtasks = sqlx::query!(
+            r#"
+            SELECT id FROM tasks
+            WHERE parent_id = $1 AND deleted_at IS NULL
+            ORDER BY created_at
+            "#,
+            source_id,
+        )
+        .fetch_all(&mut **tx)
+        .await?;
+
+        pending.extend(subtasks.into_iter().map(|r| (r.id, Some(copy.id))));
+        root_id.get_or_insert(copy.id);
+    }
+
+    root_id.ok_or(sqlx::Error::RowNotFound)
+}
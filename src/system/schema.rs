@@ -2,7 +2,12 @@ use async_graphql::{dataloader::DataLoader, Schema};
 
 use crate::{
     graphql::{mutations::MutationRoot, queries::QueryRoot, subscription::SubscriptionRoot},
-    sdk::loaders::{LabelLoader, MemberLoader, ProjectLoader, TaskLoader, TeamLoader},
+    sdk::loaders::{
+        ActivityLoader, AttachmentLoader, CommentLoader, CustomFieldLoader, CycleLoader,
+        LabelLoader, MemberLoader, MilestoneLoader, NotificationLoader, ProjectLoader, TaskLoader,
+        TaskTemplateLoader, TeamLoader, TimeEntryLoader, WebhookDeliveryLoader, WebhookLoader,
+        WorkflowStateLoader,
+    },
     system::core::Engine,
 };
 
@@ -32,6 +37,54 @@ impl GraphQLSchema for Engine {
             tokio::spawn,
         ))
         .data(DataLoader::new(TeamLoader::new(self.clone()), tokio::spawn))
+        .data(DataLoader::new(
+            ActivityLoader::new(self.clone()),
+            tokio::spawn,
+        ))
+        .data(DataLoader::new(
+            CommentLoader::new(self.clone()),
+            tokio::spawn,
+        ))
+        .data(DataLoader::new(
+            WorkflowStateLoader::new(self.clone()),
+            tokio::spawn,
+        ))
+        .data(DataLoader::new(
+            TimeEntryLoader::new(self.clone()),
+            tokio::spawn,
+        ))
+        .data(DataLoader::new(
+            CycleLoader::new(self.clone()),
+            tokio::spawn,
+        ))
+        .data(DataLoader::new(
+            MilestoneLoader::new(self.clone()),
+            tokio::spawn,
+        ))
+        .data(DataLoader::new(
+            CustomFieldLoader::new(self.clone()),
+            tokio::spawn,
+        ))
+        .data(DataLoader::new(
+            AttachmentLoader::new(self.clone()),
+            tokio::spawn,
+        ))
+        .data(DataLoader::new(
+            TaskTemplateLoader::new(self.clone()),
+            tokio::spawn,
+        ))
+        .data(DataLoader::new(
+            NotificationLoader::new(self.clone()),
+            tokio::spawn,
+        ))
+        .data(DataLoader::new(
+            WebhookLoader::new(self.clone()),
+            tokio::spawn,
+        ))
+        .data(DataLoader::new(
+            WebhookDeliveryLoader::new(self.clone()),
+            tokio::spawn,
+        ))
         .finish()
     }
 }
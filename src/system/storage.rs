@@ -0,0 +1,109 @@
+use std::{io, path::PathBuf, sync::Arc};
+
+use async_trait::async_trait;
+use tokio::io::{AsyncRead, AsyncWriteExt};
+
+use crate::config::ATTACHMENTS_STORAGE_PATH;
+
+use super::core::Engine;
+
+/// Where attachment contents are kept. Keys are generated by the engine and only contain
+/// alphanumerics, dashes and slashes.
+#[async_trait]
+pub trait StorageBackend: Send + Sync {
+    /// Stores everything read from `content`, returning the number of bytes written. A key
+    /// only becomes readable once its content is complete.
+    async fn put(&self, key: &str, content: &mut (dyn AsyncRead + Send + Unpin))
+        -> io::Result<u64>;
+    /// Opens the content of a key for reading, so it can be served without loading it whole.
+    async fn get(&self, key: &str) -> io::Result<Box<dyn AsyncRead + Send + Unpin>>;
+    /// Deleting a missing key is not an error.
+    async fn delete(&self, key: &str) -> io::Result<()>;
+}
+
+/// Stores files under a directory of the local filesystem, the `/data` volume by default.
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path(&self, key: &str) -> io::Result<PathBuf> {
+        let is_safe = !key.is_empty()
+            && key
+                .split('/')
+                .all(|part| !part.is_empty() && part != "." && part != "..");
+
+        if !is_safe {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid storage key",
+            ));
+        }
+
+        Ok(self.root.join(key))
+    }
+}
+
+impl Default for LocalStorage {
+    fn default() -> Self {
+        Self::new(ATTACHMENTS_STORAGE_PATH.as_str())
+    }
+}
+
+#[async_trait]
+impl StorageBackend for LocalStorage {
+    async fn put(
+        &self,
+        key: &str,
+        content: &mut (dyn AsyncRead + Send + Unpin),
+    ) -> io::Result<u64> {
+        let path = self.path(key)?;
+
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        // Written next to the final path and renamed, so readers never see a partial file.
+        let partial = path.with_extension("part");
+
+        let written = async {
+            let mut file = tokio::fs::File::create(&partial).await?;
+            let size = tokio::io::copy(content, &mut file).await?;
+            file.flush().await?;
+
+            tokio::fs::rename(&partial, &path).await?;
+
+            Ok(size)
+        }
+        .await;
+
+        if written.is_err() {
+            tokio::fs::remove_file(&partial).await.ok();
+        }
+
+        written
+    }
+
+    async fn get(&self, key: &str) -> io::Result<Box<dyn AsyncRead + Send + Unpin>> {
+        Ok(Box::new(tokio::fs::File::open(self.path(key)?).await?))
+    }
+
+    async fn delete(&self, key: &str) -> io::Result<()> {
+        match tokio::fs::remove_file(self.path(key)?).await {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
+    }
+}
+
+impl Engine {
+    /// Replaces the storage backend, the local filesystem by default.
+    pub fn with_storage(mut self, storage: impl StorageBackend + 'static) -> Self {
+        self.storage = Arc::new(storage);
+        self
+    }
+}
@@ -1,9 +1,12 @@
+use crate::config::NOTIFICATION_CHANNEL_CAPACITY;
+use crate::sdk::notification::Notification;
 use crate::sdk::project::Project;
 use crate::sdk::task::Task;
 use crate::sdk::team::Team;
 use std::collections::HashMap;
 use std::error::Error;
 use std::sync::Arc;
+use tokio::sync::broadcast;
 use tokio::sync::mpsc::Sender;
 use tokio::sync::Mutex;
 use uuid::Uuid;
@@ -33,6 +36,8 @@ pub struct SubscriptionManager {
     pub id_task: String,
     pub id_project: String,
     pub id_team: String,
+    /// Every notification, each subscriber keeps the ones of its member.
+    pub notifications: broadcast::Sender<Notification>,
 }
 
 impl Default for SubscriptionManager {
@@ -48,6 +53,7 @@ impl SubscriptionManager {
             id_task: Uuid::new_v4().to_string(),
             id_project: Uuid::new_v4().to_string(),
             id_team: Uuid::new_v4().to_string(),
+            notifications: broadcast::channel(*NOTIFICATION_CHANNEL_CAPACITY).0,
         }
     }
 
@@ -154,4 +160,15 @@ impl SubscriptionManager {
 
         Ok(event)
     }
+
+    pub async fn send_notification_event(&self, event: Notification) -> MyResult<Notification> {
+        // Sending only fails when nobody is listening.
+        self.notifications.send(event.clone()).ok();
+
+        Ok(event)
+    }
+
+    pub fn subscribe_notifications(&self) -> broadcast::Receiver<Notification> {
+        self.notifications.subscribe()
+    }
 }
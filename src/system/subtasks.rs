@@ -0,0 +1,64 @@
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+/// Serializes changes to the task tree for the rest of the transaction, so two concurrent moves
+/// can't close a cycle together.
+pub async fn lock_task_tree(conn: &mut PgConnection) -> Result<(), sqlx::Error> {
+    sqlx::query!("SELECT pg_advisory_xact_lock(hashtext('tasks.parent_id'))")
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(())
+}
+
+/// Whether `candidate_id` is the task itself or one of its subtasks at any level, which is where
+/// the task can't be moved under.
+pub async fn is_in_subtree(
+    conn: &mut PgConnection,
+    task_id: Uuid,
+    candidate_id: Uuid,
+) -> Result<bool, sqlx::Error> {
+    // Walks up from the candidate, the path stops the walk on trees that are already broken.
+    let found = sqlx::query!(
+        r#"
+        WITH RECURSIVE ancestors(id, path) AS (
+            SELECT $2::uuid, ARRAY[$2::uuid]
+            UNION ALL
+            SELECT tasks.parent_id, ancestors.path || tasks.parent_id
+            FROM tasks
+            JOIN ancestors ON tasks.id = ancestors.id
+            WHERE tasks.parent_id IS NOT NULL AND NOT tasks.parent_id = ANY(ancestors.path)
+        )
+        SELECT EXISTS (SELECT 1 FROM ancestors WHERE id = $1) AS "found!"
+        "#,
+        task_id,
+        candidate_id,
+    )
+    .fetch_one(&mut *conn)
+    .await?
+    .found;
+
+    Ok(found)
+}
+
+/// Moves the live direct subtasks of a task under `parent_id`, or to the top level without one.
+/// Returns the moved ids.
+pub async fn move_subtasks(
+    conn: &mut PgConnection,
+    task_id: Uuid,
+    parent_id: Option<Uuid>,
+) -> Result<Vec<Uuid>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"
+        UPDATE tasks SET parent_id = $2
+        WHERE parent_id = $1 AND deleted_at IS NULL
+        RETURNING id
+        "#,
+        task_id,
+        parent_id,
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(rows.into_iter().map(|r| r.id).collect())
+}
@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+
+use async_graphql::dataloader::Loader;
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+use crate::sdk::{
+    activity::{ActivityOperationType, ActivityResourceType},
+    loaders::TaskLoader,
+    task_template::{TaskTemplate, TaskTemplateOverrides, TemplateTask},
+    utilities::DateTimeBridge,
+};
+
+use super::core::Engine;
+
+impl Engine {
+    /// Snapshots a task and its subtasks as a template tree. Due dates are kept relative to the
+    /// creation of the root task, and statuses are left out so copies start in the initial state.
+    pub async fn build_template_tree(&self, task_id: Uuid) -> Result<TemplateTask, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"
+            WITH RECURSIVE tree AS (
+                SELECT id FROM tasks WHERE id = $1
+                UNION
+                SELECT tasks.id FROM tasks
+                JOIN tree ON tasks.parent_id = tree.id
+                WHERE tasks.deleted_at IS NULL
+            )
+            SELECT
+                tasks.id,
+                tasks.parent_id,
+                tasks.title,
+                tasks.description,
+                tasks.priority,
+                EXTRACT(EPOCH FROM (tasks.due_date - root.created_at))::bigint AS due_offset,
+                tasks.estimate,
+                tasks.lead_id,
+                ARRAY(SELECT label_id FROM labels_by_tasks WHERE task_id = tasks.id) AS "label_ids!",
+                ARRAY(SELECT assignee_id FROM tasks_by_assignees WHERE task_id = tasks.id) AS "assignee_ids!"
+            FROM tasks
+            JOIN tree ON tree.id = tasks.id
+            CROSS JOIN (SELECT created_at FROM tasks WHERE id = $1) AS root
+            ORDER BY tasks.created_at, tasks.id
+            "#,
+            task_id,
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+
+        let mut children: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+        let mut nodes: HashMap<Uuid, TemplateTask> = HashMap::new();
+
+        for r in rows {
+            if r.id != task_id {
+                if let Some(parent_id) = r.parent_id {
+                    children.entry(parent_id).or_default().push(r.id);
+                }
+            }
+
+            nodes.insert(
+                r.id,
+                TemplateTask {
+                    title: r.title,
+                    description: r.description,
+                    priority: r.priority,
+                    due_offset: r.due_offset,
+                    estimate: r.estimate,
+                    lead_id: r.lead_id,
+                    label_ids: r.label_ids,
+                    assignee_ids: r.assignee_ids,
+                    subtasks: vec![],
+                },
+            );
+        }
+
+        assemble_tree(task_id, &mut nodes, &children).ok_or(sqlx::Error::RowNotFound)
+    }
+
+    /// Creates the tasks of a template in a single transaction and returns their ids, root
+    /// first.
+    pub async fn instantiate_task_template(
+        &self,
+        template: &TaskTemplate,
+        overrides: TaskTemplateOverrides,
+        owner_id: Uuid,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        let starts_at = overrides.starts_at.unwrap_or_else(Utc::now);
+        let project_id = overrides.project_id.or(template.project_id);
+
+        let mut root = template.root.clone();
+
+        root.title = overrides.title.unwrap_or(root.title);
+        root.description = overrides.description.or(root.description);
+        root.lead_id = overrides.lead_id.or(root.lead_id);
+        root.label_ids = overrides.label_ids.unwrap_or(root.label_ids);
+        root.assignee_ids = overrides.assignee_ids.unwrap_or(root.assignee_ids);
+
+        let mut tx = self.pool.begin().await?;
+        let mut created = vec![];
+        let mut pending: Vec<(&TemplateTask, Option<Uuid>)> = vec![(&root, overrides.parent_id)];
+
+        while let Some((task, parent_id)) = pending.pop() {
+            let due_date = task
+                .due_offset
+                .and_then(|offset| starts_at.checked_add_signed(Duration::seconds(offset)))
+                .map(DateTimeBridge::from_date_time);
+
+            let inserted = sqlx::query!(
+                r#"
+                INSERT INTO tasks (title, description, owner_id, priority, due_date, project_id, lead_id, parent_id, estimate)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                RETURNING id
+                "#,
+                task.title,
+                task.description,
+                owner_id,
+                task.priority,
+                due_date,
+                project_id,
+                task.lead_id,
+                parent_id,
+                task.estimate,
+            )
+            .fetch_one(&mut *tx)
+            .await?;
+
+            // Labels and members removed since the template was saved are skipped.
+            sqlx::query!(
+                r#"
+                INSERT INTO labels_by_tasks (task_id, label_id)
+                SELECT $1, id FROM labels
+                WHERE id = ANY($2)
+                "#,
+                inserted.id,
+                &task.label_ids,
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query!(
+                r#"
+                INSERT INTO tasks_by_assignees (task_id, assignee_id)
+                SELECT $1, id FROM members
+                WHERE id = ANY($2)
+                "#,
+                inserted.id,
+                &task.assignee_ids,
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            created.push(inserted.id);
+
+            // Reversed so subtasks are created in their saved order.
+            pending.extend(
+                task.subtasks
+                    .iter()
+                    .rev()
+                    .map(|subtask| (subtask, Some(inserted.id))),
+            );
+        }
+
+        tx.commit().await?;
+
+        let tasks = TaskLoader::new(self.clone())
+            .load(&created)
+            .await
+            .map_err(|e| sqlx::Error::Protocol(e.to_string()))?;
+
+        for task_id in &created {
+            let Some(task) = tasks.get(task_id) else {
+                continue;
+            };
+
+            self.subscription_manager
+                .send_task_event(task.clone())
+                .await
+                .ok();
+
+            self.record_activity(
+                ActivityOperationType::Create,
+                ActivityResourceType::Task,
+                task.id,
+                owner_id,
+            )
+            .await;
+        }
+
+        Ok(created)
+    }
+}
+
+fn assemble_tree(
+    id: Uuid,
+    nodes: &mut HashMap<Uuid, TemplateTask>,
+    children: &HashMap<Uuid, Vec<Uuid>>,
+) -> Option<TemplateTask> {
+    let mut node = nodes.remove(&id)?;
+
+    node.subtasks = children
+        .get(&id)
+        .map(|ids| {
+            ids.iter()
+                .filter_map(|child_id| assemble_tree(*child_id, nodes, children))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Some(node)
+}
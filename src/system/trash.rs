@@ -0,0 +1,274 @@
+use std::time::Duration;
+
+use sqlx::{types::time::OffsetDateTime, PgConnection};
+use uuid::Uuid;
+
+use crate::config::{TRASH_PURGE_INTERVAL_SECS, TRASH_RETENTION_DAYS};
+
+use super::core::Engine;
+
+/// Moves the live subtasks of a deleted task to the trash, stamped with the task's
+/// `deleted_at` so they are restored together.
+pub async fn trash_subtasks(
+    conn: &mut PgConnection,
+    task_id: Uuid,
+    deleted_at: OffsetDateTime,
+    deleted_by: Uuid,
+) -> Result<Vec<Uuid>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"
+        WITH RECURSIVE tree AS (
+            SELECT id FROM tasks WHERE parent_id = $1 AND deleted_at IS NULL
+            UNION
+            SELECT tasks.id FROM tasks
+            JOIN tree ON tasks.parent_id = tree.id
+            WHERE tasks.deleted_at IS NULL
+        )
+        UPDATE tasks
+        SET deleted_at = $2, deleted_by = $3
+        FROM tree
+        WHERE tasks.id = tree.id
+        RETURNING tasks.id
+        "#,
+        task_id,
+        deleted_at,
+        deleted_by,
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(rows.into_iter().map(|r| r.id).collect())
+}
+
+impl Engine {
+    /// Periodically purges the trash rows older than the retention period.
+    pub fn spawn_trash_purge_scheduler(&self) {
+        let engine = self.clone();
+
+        tokio::spawn(async move {
+            let mut interval =
+                tokio::time::interval(Duration::from_secs(*TRASH_PURGE_INTERVAL_SECS));
+
+            loop {
+                interval.tick().await;
+
+                if let Err(e) = engine.purge_trash().await {
+                    println!("Failed to purge trash: {:?}", e);
+                }
+            }
+        });
+    }
+
+    /// Restores a deleted task and the subtasks that were deleted with it. Returns the restored
+    /// ids, the task first, or nothing if the task isn't in the trash.
+    pub async fn restore_task(&self, task_id: Uuid) -> Result<Vec<Uuid>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"
+            WITH RECURSIVE tree AS (
+                SELECT id, deleted_at FROM tasks WHERE id = $1 AND deleted_at IS NOT NULL
+                UNION
+                SELECT tasks.id, tasks.deleted_at FROM tasks
+                JOIN tree ON tasks.parent_id = tree.id AND tasks.deleted_at = tree.deleted_at
+            )
+            UPDATE tasks
+            SET deleted_at = NULL, deleted_by = NULL
+            FROM tree
+            WHERE tasks.id = tree.id
+            RETURNING tasks.id
+            "#,
+            task_id,
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+
+        let mut ids: Vec<Uuid> = rows.into_iter().map(|r| r.id).collect();
+
+        if let Some(position) = ids.iter().position(|id| *id == task_id) {
+            ids.swap(0, position);
+        }
+
+        Ok(ids)
+    }
+
+    /// Hard deletes the tasks, projects and teams that have been in the trash for longer than
+    /// `TRASH_RETENTION_DAYS`. Relations without a foreign key are cleaned up here, and the
+    /// attachment contents of purged tasks are removed from the storage once committed.
+    pub async fn purge_trash(&self) -> Result<(), sqlx::Error> {
+        let retention_days = *TRASH_RETENTION_DAYS;
+
+        let mut tx = self.pool.begin().await?;
+
+        let tasks = sqlx::query!(
+            r#"
+            SELECT id FROM tasks
+            WHERE deleted_at < NOW() - make_interval(days => $1)
+            "#,
+            retention_days,
+        )
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .map(|r| r.id)
+        .collect::<Vec<Uuid>>();
+
+        let projects = sqlx::query!(
+            r#"
+            SELECT id FROM projects
+            WHERE deleted_at < NOW() - make_interval(days => $1)
+            "#,
+            retention_days,
+        )
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .map(|r| r.id)
+        .collect::<Vec<Uuid>>();
+
+        let teams = sqlx::query!(
+            r#"
+            SELECT id FROM teams
+            WHERE deleted_at < NOW() - make_interval(days => $1)
+            "#,
+            retention_days,
+        )
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .map(|r| r.id)
+        .collect::<Vec<Uuid>>();
+
+        if tasks.is_empty() && projects.is_empty() && teams.is_empty() {
+            return Ok(());
+        }
+
+        // Attachment rows go with their task, their contents don't.
+        let storage_keys = sqlx::query!(
+            r#"
+            SELECT storage_key FROM attachments WHERE task_id = ANY($1)
+            "#,
+            &tasks,
+        )
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .map(|r| r.storage_key)
+        .collect::<Vec<String>>();
+
+        sqlx::query!(
+            r#"
+            DELETE FROM labels_by_tasks WHERE task_id = ANY($1)
+            "#,
+            &tasks,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        // Subtasks restored on their own outlive their parent.
+        sqlx::query!(
+            r#"
+            UPDATE tasks SET parent_id = NULL
+            WHERE parent_id = ANY($1) AND NOT (id = ANY($1))
+            "#,
+            &tasks,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM tasks WHERE id = ANY($1)
+            "#,
+            &tasks,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM watchers
+            WHERE (resource_type = 'Task' AND resource_id = ANY($1))
+                OR (resource_type = 'Project' AND resource_id = ANY($2))
+            "#,
+            &tasks,
+            &projects,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM members_by_projects WHERE project_id = ANY($1)
+            "#,
+            &projects,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM teams_by_projects WHERE project_id = ANY($1) OR team_id = ANY($2)
+            "#,
+            &projects,
+            &teams,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE tasks SET project_id = NULL
+            WHERE project_id = ANY($1)
+            "#,
+            &projects,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM projects WHERE id = ANY($1)
+            "#,
+            &projects,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM members_by_teams WHERE team_id = ANY($1)
+            "#,
+            &teams,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM teams WHERE id = ANY($1)
+            "#,
+            &teams,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        for storage_key in &storage_keys {
+            if let Err(e) = self.storage.delete(storage_key).await {
+                println!(
+                    "Failed to delete attachment content {}: {:?}",
+                    storage_key, e
+                );
+            }
+        }
+
+        println!(
+            "Purged {} tasks, {} projects and {} teams from the trash",
+            tasks.len(),
+            projects.len(),
+            teams.len()
+        );
+
+        Ok(())
+    }
+}
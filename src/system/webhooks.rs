@@ -0,0 +1,395 @@
+use std::{str::FromStr, time::Duration};
+
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use sqlx::{Postgres, QueryBuilder};
+use uuid::Uuid;
+
+use crate::{
+    config::{
+        WEBHOOK_MAX_ATTEMPTS, WEBHOOK_RETRY_BASE_SECS, WEBHOOK_SCHEDULER_INTERVAL_SECS,
+        WEBHOOK_TIMEOUT_SECS,
+    },
+    sdk::{
+        activity::{ActivityOperationType, ActivityResourceType},
+        utilities::DateTimeBridge,
+        webhook::WebhookDeliveryStatus,
+    },
+};
+
+use super::core::Engine;
+
+/// Longest response body kept in the delivery log.
+const RESPONSE_BODY_MAX_LENGTH: usize = 2_000;
+
+const RESOURCE_TYPES: [ActivityResourceType; 12] = [
+    ActivityResourceType::Task,
+    ActivityResourceType::Project,
+    ActivityResourceType::Team,
+    ActivityResourceType::Member,
+    ActivityResourceType::Label,
+    ActivityResourceType::Organization,
+    ActivityResourceType::Comment,
+    ActivityResourceType::TimeEntry,
+    ActivityResourceType::Cycle,
+    ActivityResourceType::Milestone,
+    ActivityResourceType::Attachment,
+    ActivityResourceType::TaskTemplate,
+];
+
+const OPERATIONS: [ActivityOperationType; 4] = [
+    ActivityOperationType::Create,
+    ActivityOperationType::Update,
+    ActivityOperationType::Delete,
+    ActivityOperationType::Restore,
+];
+
+/// Whether a webhook event filter is `*`, a resource wildcard such as `task.*`, or an event such
+/// as `task.created`.
+pub fn is_event_filter(filter: &str) -> bool {
+    if filter == "*" {
+        return true;
+    }
+
+    let Some((resource, operation)) = filter.split_once('.') else {
+        return false;
+    };
+
+    RESOURCE_TYPES.iter().any(|r| r.event_name() == resource)
+        && (operation == "*" || OPERATIONS.iter().any(|o| o.event_name() == operation))
+}
+
+fn event_matches(filter: &str, event: &str) -> bool {
+    filter == "*"
+        || filter == event
+        || filter
+            .strip_suffix(".*")
+            .is_some_and(|resource| event.split_once('.').is_some_and(|(r, _)| r == resource))
+}
+
+/// Hex encoded HMAC-SHA256 of a payload, sent as `X-Plexo-Signature: sha256=<signature>`.
+pub fn sign_payload(secret: &str, payload: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC keys can have any length");
+
+    mac.update(payload);
+
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Delay before the next attempt of a delivery that failed `attempts` times.
+fn retry_delay(attempts: i32) -> chrono::Duration {
+    let factor = 1i64 << (attempts - 1).clamp(0, 20);
+
+    chrono::Duration::seconds(WEBHOOK_RETRY_BASE_SECS.saturating_mul(factor))
+}
+
+impl Engine {
+    /// Queues a delivery of each activity to the active webhooks with a matching event filter.
+    pub async fn queue_webhook_deliveries(&self, activity_ids: &[Uuid]) -> Result<(), sqlx::Error> {
+        let webhooks = sqlx::query!(
+            r#"
+            SELECT id, events FROM webhooks WHERE active
+            "#,
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+
+        if webhooks.is_empty() {
+            return Ok(());
+        }
+
+        let activities = sqlx::query!(
+            r#"
+            SELECT
+                activity.id,
+                activity.created_at,
+                activity.operation,
+                activity.resource_type,
+                activity.resource_id,
+                activity.member_id,
+                members.name AS "member_name?",
+                members.email AS "member_email?"
+            FROM activity
+            LEFT JOIN members ON members.id = activity.member_id
+            WHERE activity.id = ANY($1)
+            "#,
+            activity_ids,
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+
+        for activity in activities {
+            let (Ok(resource_type), Ok(operation)) = (
+                ActivityResourceType::from_str(&activity.resource_type),
+                ActivityOperationType::from_str(&activity.operation),
+            ) else {
+                continue;
+            };
+
+            let event = format!("{}.{}", resource_type.event_name(), operation.event_name());
+
+            let webhook_ids: Vec<Uuid> = webhooks
+                .iter()
+                .filter(|w| w.events.iter().any(|f| event_matches(f, &event)))
+                .map(|w| w.id)
+                .collect();
+
+            if webhook_ids.is_empty() {
+                continue;
+            }
+
+            let payload = json!({
+                "event": event,
+                "activity_id": activity.id,
+                "created_at": DateTimeBridge::from_offset_date_time(activity.created_at).to_rfc3339(),
+                "actor": {
+                    "id": activity.member_id,
+                    "name": activity.member_name,
+                    "email": activity.member_email,
+                },
+                "resource_type": resource_type.event_name(),
+                "resource_id": activity.resource_id,
+                "resource": self.webhook_resource(resource_type, activity.resource_id).await?,
+            });
+
+            sqlx::query!(
+                r#"
+                INSERT INTO webhook_deliveries (webhook_id, event, payload)
+                SELECT webhook_id, $2, $3
+                FROM unnest($1::uuid[]) AS webhook_id
+                "#,
+                &webhook_ids,
+                event,
+                payload,
+            )
+            .execute(&*self.pool)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// The current row of a resource as JSON, without its credentials. Null once it's gone for
+    /// good, and for the organization, whose settings aren't a single row.
+    async fn webhook_resource(
+        &self,
+        resource_type: ActivityResourceType,
+        resource_id: Uuid,
+    ) -> Result<Value, sqlx::Error> {
+        let table = match resource_type {
+            ActivityResourceType::Task => "tasks",
+            ActivityResourceType::Project => "projects",
+            ActivityResourceType::Team => "teams",
+            ActivityResourceType::Member => "members",
+            ActivityResourceType::Label => "labels",
+            ActivityResourceType::Organization => return Ok(Value::Null),
+            ActivityResourceType::Comment => "comments",
+            ActivityResourceType::TimeEntry => "time_entries",
+            ActivityResourceType::Cycle => "cycles",
+            ActivityResourceType::Milestone => "milestones",
+            ActivityResourceType::Attachment => "attachments",
+            ActivityResourceType::TaskTemplate => "task_templates",
+        };
+
+        let mut query: QueryBuilder<'_, Postgres> = QueryBuilder::new(format!(
+            "SELECT to_jsonb(resource) - 'password_hash' - 'storage_key' FROM {} AS resource WHERE id = ",
+            table
+        ));
+
+        query.push_bind(resource_id);
+
+        let resource: Option<Value> = query
+            .build_query_scalar()
+            .fetch_optional(&*self.pool)
+            .await?;
+
+        Ok(resource.unwrap_or(Value::Null))
+    }
+
+    /// Queues a new delivery with the payload of an earlier one. Returns `None` if there's no such
+    /// delivery.
+    pub async fn redeliver_webhook_delivery(
+        &self,
+        delivery_id: Uuid,
+    ) -> Result<Option<Uuid>, sqlx::Error> {
+        let delivery = sqlx::query!(
+            r#"
+            INSERT INTO webhook_deliveries (webhook_id, event, payload)
+            SELECT webhook_id, event, payload FROM webhook_deliveries WHERE id = $1
+            RETURNING id
+            "#,
+            delivery_id,
+        )
+        .fetch_optional(&*self.pool)
+        .await?;
+
+        Ok(delivery.map(|r| r.id))
+    }
+
+    /// Periodically posts the webhook deliveries that are due.
+    pub fn spawn_webhook_scheduler(&self) {
+        let engine = self.clone();
+
+        tokio::spawn(async move {
+            let mut interval =
+                tokio::time::interval(Duration::from_secs(*WEBHOOK_SCHEDULER_INTERVAL_SECS));
+
+            loop {
+                interval.tick().await;
+
+                if let Err(e) = engine.send_webhook_deliveries().await {
+                    println!("Failed to send webhook deliveries: {:?}", e);
+                }
+            }
+        });
+    }
+
+    /// Posts the pending deliveries that are due. A delivery succeeds on a 2xx response and is
+    /// retried with exponential backoff otherwise, until `WEBHOOK_MAX_ATTEMPTS` is reached.
+    pub async fn send_webhook_deliveries(&self) -> Result<(), sqlx::Error> {
+        let deliveries = sqlx::query!(
+            r#"
+            SELECT
+                webhook_deliveries.id,
+                webhook_deliveries.event,
+                webhook_deliveries.payload,
+                webhook_deliveries.attempts,
+                webhooks.url,
+                webhooks.secret
+            FROM webhook_deliveries
+            JOIN webhooks ON webhooks.id = webhook_deliveries.webhook_id
+            WHERE
+                webhook_deliveries.status = 'Pending'
+                AND webhook_deliveries.next_attempt_at <= NOW()
+                AND webhooks.active
+            ORDER BY webhook_deliveries.next_attempt_at
+            LIMIT 100
+            "#,
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+
+        if deliveries.is_empty() {
+            return Ok(());
+        }
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(*WEBHOOK_TIMEOUT_SECS))
+            .build()
+            .unwrap_or_default();
+
+        for delivery in deliveries {
+            let body = delivery.payload.to_string();
+            let signature = sign_payload(&delivery.secret, body.as_bytes());
+
+            let result = client
+                .post(&delivery.url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header("X-Plexo-Event", &delivery.event)
+                .header("X-Plexo-Delivery", delivery.id.to_string())
+                .header("X-Plexo-Signature", format!("sha256={}", signature))
+                .body(body)
+                .send()
+                .await;
+
+            let (response_status, response_body, error) = match result {
+                Ok(response) => {
+                    let status = response.status();
+                    let body: String = response
+                        .text()
+                        .await
+                        .unwrap_or_default()
+                        .chars()
+                        .take(RESPONSE_BODY_MAX_LENGTH)
+                        .collect();
+
+                    (
+                        Some(status.as_u16() as i32),
+                        Some(body),
+                        (!status.is_success()).then(|| format!("Responded with {}", status)),
+                    )
+                }
+                Err(e) => (None, None, Some(e.to_string())),
+            };
+
+            let attempts = delivery.attempts + 1;
+
+            let (status, next_attempt_at) = if error.is_none() {
+                (WebhookDeliveryStatus::Succeeded, None)
+            } else if attempts >= *WEBHOOK_MAX_ATTEMPTS {
+                (WebhookDeliveryStatus::Failed, None)
+            } else {
+                (
+                    WebhookDeliveryStatus::Pending,
+                    Some(Utc::now() + retry_delay(attempts)),
+                )
+            };
+
+            sqlx::query!(
+                r#"
+                UPDATE webhook_deliveries
+                SET
+                    status = $2,
+                    attempts = $3,
+                    next_attempt_at = $4,
+                    delivered_at = CASE WHEN $2 = 'Succeeded' THEN NOW() END,
+                    response_status = $5,
+                    response_body = $6,
+                    error = $7
+                WHERE id = $1
+                "#,
+                delivery.id,
+                status.to_str(),
+                attempts,
+                next_attempt_at.map(DateTimeBridge::from_date_time),
+                response_status,
+                response_body,
+                error,
+            )
+            .execute(&*self.pool)
+            .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signs_payloads_with_hmac_sha256() {
+        // RFC 4231, test case 2.
+        assert_eq!(
+            sign_payload("Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn matches_event_filters() {
+        assert!(event_matches("*", "task.created"));
+        assert!(event_matches("task.created", "task.created"));
+        assert!(event_matches("task.*", "task.deleted"));
+
+        assert!(!event_matches("task.created", "task.updated"));
+        assert!(!event_matches("task.*", "project.created"));
+        assert!(!event_matches("task.*", "task_template.created"));
+        assert!(!event_matches("task", "task.created"));
+    }
+
+    #[test]
+    fn backs_off_exponentially() {
+        let base = *WEBHOOK_RETRY_BASE_SECS;
+
+        assert_eq!(retry_delay(0), chrono::Duration::seconds(base));
+        assert_eq!(retry_delay(1), chrono::Duration::seconds(base));
+        assert_eq!(retry_delay(2), chrono::Duration::seconds(base * 2));
+        assert_eq!(retry_delay(4), chrono::Duration::seconds(base * 8));
+        assert_eq!(retry_delay(100), chrono::Duration::seconds(base << 20));
+    }
+}
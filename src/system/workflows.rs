@@ -0,0 +1,202 @@
+use std::str::FromStr;
+
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use crate::sdk::workflow::WorkflowStateCategory;
+
+use super::core::Engine;
+
+/// Maps a legacy `TaskStatus` value to a state of the project workflow.
+pub async fn resolve_workflow_state(
+    conn: &mut PgConnection,
+    project_id: Option<Uuid>,
+    status: &str,
+) -> Result<Option<Uuid>, sqlx::Error> {
+    sqlx::query!(
+        r#"SELECT resolve_workflow_state($1, $2) AS "state_id""#,
+        project_id as Option<Uuid>,
+        status,
+    )
+    .fetch_one(&mut *conn)
+    .await
+    .map(|r| r.state_id)
+}
+
+pub async fn is_state_in_project_workflow(
+    conn: &mut PgConnection,
+    state_id: Uuid,
+    project_id: Option<Uuid>,
+) -> Result<bool, sqlx::Error> {
+    sqlx::query!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM workflow_states
+            WHERE id = $1 AND project_id IS NOT DISTINCT FROM workflow_project_id($2)
+        ) AS "exists!"
+        "#,
+        state_id,
+        project_id as Option<Uuid>,
+    )
+    .fetch_one(&mut *conn)
+    .await
+    .map(|r| r.exists)
+}
+
+pub async fn is_transition_allowed(
+    conn: &mut PgConnection,
+    from_state_id: Uuid,
+    to_state_id: Uuid,
+) -> Result<bool, sqlx::Error> {
+    sqlx::query!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM workflow_transitions
+            WHERE from_state_id = $1 AND to_state_id = $2
+        ) AS "exists!"
+        "#,
+        from_state_id,
+        to_state_id,
+    )
+    .fetch_one(&mut *conn)
+    .await
+    .map(|r| r.exists)
+}
+
+pub async fn get_workflow_state_category(
+    conn: &mut PgConnection,
+    state_id: Uuid,
+) -> Result<Option<WorkflowStateCategory>, sqlx::Error> {
+    sqlx::query!(
+        r#"
+        SELECT category FROM workflow_states
+        WHERE id = $1
+        "#,
+        state_id,
+    )
+    .fetch_optional(&mut *conn)
+    .await
+    .map(|r| r.and_then(|r| WorkflowStateCategory::from_str(&r.category).ok()))
+}
+
+/// Counts the blockers of a task that are neither done nor canceled.
+pub async fn count_open_blockers(
+    conn: &mut PgConnection,
+    task_id: Uuid,
+) -> Result<i64, sqlx::Error> {
+    sqlx::query!(
+        r#"
+        SELECT COUNT(*) AS "count!"
+        FROM task_dependencies
+        JOIN tasks ON tasks.id = task_dependencies.blocker_id
+        WHERE
+            task_dependencies.blocked_id = $1
+            AND tasks.deleted_at IS NULL
+            AND COALESCE(tasks.status, 'None') NOT IN ('Done', 'Canceled')
+        "#,
+        task_id,
+    )
+    .fetch_one(&mut *conn)
+    .await
+    .map(|r| r.count)
+}
+
+impl Engine {
+    /// Returns the ids of the states making up the workflow of a project, falling back to the
+    /// default workflow when the project has no states of its own.
+    pub async fn get_workflow_state_ids(
+        &self,
+        project_id: Option<Uuid>,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query!(
+            r#"
+            SELECT id FROM workflow_states
+            WHERE project_id IS NOT DISTINCT FROM workflow_project_id($1)
+            ORDER BY position, name
+            "#,
+            project_id as Option<Uuid>,
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .map(|rows| rows.into_iter().map(|r| r.id).collect())
+    }
+
+    /// Gives a project its own copy of the default workflow so it can be customized without
+    /// affecting other projects. Tasks of the project are moved to the copied states by name.
+    /// Does nothing if the project already has its own workflow.
+    pub async fn initialize_project_workflow(&self, project_id: Uuid) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        // Locks the project row so concurrent initializations don't copy the defaults twice.
+        sqlx::query!(
+            r#"
+            SELECT id FROM projects WHERE id = $1 FOR UPDATE
+            "#,
+            project_id,
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let already_initialized = sqlx::query!(
+            r#"
+            SELECT EXISTS (SELECT 1 FROM workflow_states WHERE project_id = $1) AS "exists!"
+            "#,
+            project_id,
+        )
+        .fetch_one(&mut *tx)
+        .await?
+        .exists;
+
+        if already_initialized {
+            return Ok(());
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO workflow_states (project_id, name, category, position)
+            SELECT $1, name, category, position
+            FROM workflow_states
+            WHERE project_id IS NULL
+            "#,
+            project_id,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO workflow_transitions (from_state_id, to_state_id)
+            SELECT project_from.id, project_to.id
+            FROM workflow_transitions
+            JOIN workflow_states default_from ON default_from.id = workflow_transitions.from_state_id
+            JOIN workflow_states default_to ON default_to.id = workflow_transitions.to_state_id
+            JOIN workflow_states project_from
+                ON project_from.project_id = $1 AND project_from.name = default_from.name
+            JOIN workflow_states project_to
+                ON project_to.project_id = $1 AND project_to.name = default_to.name
+            WHERE default_from.project_id IS NULL AND default_to.project_id IS NULL
+            "#,
+            project_id,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE tasks
+            SET state_id = project_state.id
+            FROM workflow_states default_state, workflow_states project_state
+            WHERE
+                tasks.project_id = $1
+                AND default_state.id = tasks.state_id
+                AND project_state.project_id = $1
+                AND project_state.name = default_state.name
+            "#,
+            project_id,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await
+    }
+}